@@ -0,0 +1,207 @@
+//! The derive macro for `transition_tables::StateMachine`
+//!
+//! See the trait's documentation for the attributes it reads.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Expr, ExprLit, Fields, Ident, Lit, LitChar, Meta,
+    MetaNameValue,
+};
+
+/// Derive `transition_tables::StateMachine` for an enum of unit variants
+#[proc_macro_derive(StateMachine, attributes(transition, accepting))]
+pub fn derive_state_machine(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// A variant's state and the transitions leaving it
+struct State {
+    /// The variant's name
+    name: Ident,
+
+    /// Whether the variant has the `accepting` attribute
+    accepting: bool,
+
+    /// The character and target variant of each `transition` attribute
+    transitions: Vec<(LitChar, Ident)>,
+
+    /// The lines of the variant's doc comment
+    doc: Vec<String>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            input,
+            "StateMachine can only be derived for enums",
+        ));
+    };
+    if data.variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            input,
+            "StateMachine needs at least one variant for the starting state",
+        ));
+    }
+
+    // Read the attributes of every variant
+    let mut states = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "StateMachine variants can't have fields",
+            ));
+        }
+
+        let mut state = State {
+            name: variant.ident.clone(),
+            accepting: false,
+            transitions: Vec::new(),
+            doc: Vec::new(),
+        };
+        for attr in &variant.attrs {
+            if attr.path().is_ident("doc") {
+                if let Meta::NameValue(MetaNameValue {
+                    value:
+                        Expr::Lit(ExprLit {
+                            lit: Lit::Str(line),
+                            ..
+                        }),
+                    ..
+                }) = &attr.meta
+                {
+                    let line = line.value();
+                    state
+                        .doc
+                        .push(line.strip_prefix(' ').unwrap_or(&line).to_string());
+                }
+            } else if attr.path().is_ident("accepting") {
+                attr.meta.require_path_only()?;
+                state.accepting = true;
+            } else if attr.path().is_ident("transition") {
+                let (mut on, mut to) = (None, None);
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("on") {
+                        on = Some(meta.value()?.parse::<LitChar>()?);
+                    } else if meta.path.is_ident("to") {
+                        to = Some(meta.value()?.parse::<Ident>()?);
+                    } else {
+                        return Err(meta.error("expected `on` or `to`"));
+                    }
+                    Ok(())
+                })?;
+
+                match (on, to) {
+                    (Some(on), Some(to)) => state.transitions.push((on, to)),
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            attr,
+                            "transitions need both `on = 'c'` and `to = Variant`",
+                        ))
+                    }
+                }
+            }
+        }
+        states.push(state);
+    }
+
+    // Give each distinct character a column, in order of first use
+    let mut chars: Vec<char> = Vec::new();
+    for (on, _) in states.iter().flat_map(|state| &state.transitions) {
+        if !chars.contains(&on.value()) {
+            chars.push(on.value());
+        }
+    }
+
+    // Build each row, checking the targets and that each character has one target per state
+    let mut rows = Vec::new();
+    for (id, state) in states.iter().enumerate() {
+        let mut transitions: Vec<Option<usize>> = vec![None; chars.len()];
+        for (on, to) in &state.transitions {
+            let target = states
+                .iter()
+                .position(|state| state.name == *to)
+                .ok_or_else(|| syn::Error::new_spanned(to, "no variant with this name"))?;
+
+            let column = chars.iter().position(|c| *c == on.value()).unwrap();
+            match transitions[column] {
+                Some(existing) if existing != target => {
+                    return Err(syn::Error::new_spanned(
+                        on,
+                        "this state already has a different transition on this character",
+                    ));
+                }
+                _ => transitions[column] = Some(target),
+            }
+        }
+
+        let accepting = state.accepting;
+        // One-character names can't be labels, since they could be mistyped transitions
+        let label = state.name.to_string();
+        let label = if label.chars().nth(1).is_some() {
+            quote!(::std::option::Option::Some(::std::string::String::from(#label)))
+        } else {
+            quote!(::std::option::Option::None)
+        };
+        let doc = if state.doc.is_empty() {
+            quote!(::std::option::Option::None)
+        } else {
+            let doc = state.doc.join("\n");
+            quote!(::std::option::Option::Some(::std::string::String::from(#doc)))
+        };
+        let transitions = transitions.iter().map(|transition| match transition {
+            Some(target) => quote!(::std::option::Option::Some(#target)),
+            None => quote!(::std::option::Option::None),
+        });
+        rows.push(quote! {
+            ::transition_tables::TransitionTableRow {
+                accepting: #accepting,
+                id: #id,
+                transitions: ::std::vec![#(#transitions),*],
+                doc: #doc,
+                label: #label,
+            }
+        });
+    }
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let variants = states.iter().map(|state| &state.name).collect::<Vec<_>>();
+    let ids = 0..states.len();
+
+    Ok(quote! {
+        impl #impl_generics ::transition_tables::StateMachine for #name #type_generics #where_clause {
+            fn transition_table() -> &'static ::transition_tables::TransitionTable {
+                static TABLE: ::std::sync::OnceLock<::transition_tables::TransitionTable> =
+                    ::std::sync::OnceLock::new();
+                TABLE.get_or_init(|| ::transition_tables::TransitionTable {
+                    rows: ::std::vec![#(#rows),*],
+                    alphabet: ::std::option::Option::None,
+                    metadata: ::std::default::Default::default(),
+                    comment: ::std::option::Option::None,
+                })
+            }
+
+            fn alphabet() -> &'static ::transition_tables::Alphabet {
+                static ALPHABET: ::std::sync::OnceLock<::transition_tables::Alphabet> =
+                    ::std::sync::OnceLock::new();
+                ALPHABET.get_or_init(|| ::transition_tables::Alphabet::from_chars([#(#chars),*]))
+            }
+
+            fn state_id(&self) -> usize {
+                match self {
+                    #(Self::#variants => #ids,)*
+                }
+            }
+
+            fn from_state_id(id: usize) -> ::std::option::Option<Self> {
+                [#(Self::#variants),*].into_iter().nth(id)
+            }
+        }
+    })
+}
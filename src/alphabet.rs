@@ -0,0 +1,310 @@
+use crate::{CharSet, ParseSerializeError};
+
+/// The header cell for the default column
+const DEFAULT_SYMBOL: &str = "*";
+
+/// The separator between a header cell and its description
+const DESCRIPTION_SEPARATOR: char = '=';
+
+/// A mapping from characters to table columns
+///
+/// Column `i` is taken by the characters in class `i`. Characters in no class have no column,
+/// unless the alphabet has a default column, which takes every character no other column takes.
+/// Columns can carry a human description, such as "digit", which exporters show in a legend.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Alphabet {
+    /// The characters of each column
+    classes: Vec<CharSet>,
+
+    /// Every range of every class with its column, sorted for binary search
+    index: Vec<(char, char, usize)>,
+
+    /// The default column, if there is one
+    default: Option<usize>,
+
+    /// The description of each column, if it has one
+    descriptions: Vec<Option<String>>,
+}
+
+impl Alphabet {
+    /// Create an alphabet from the characters of each column
+    ///
+    /// A character in several classes is classified into the first one.
+    pub fn new(classes: Vec<CharSet>) -> Self {
+        let mut index = Vec::new();
+        let mut seen = CharSet::default();
+        for (column, class) in classes.iter().enumerate() {
+            for (start, end) in class.difference(&seen).ranges() {
+                index.push((*start, *end, column));
+            }
+            seen = seen.union(class);
+        }
+        index.sort_unstable();
+
+        Alphabet {
+            descriptions: vec![None; classes.len()],
+            classes,
+            index,
+            default: None,
+        }
+    }
+
+    /// Create an alphabet from the characters of each column, plus a last default column
+    ///
+    /// The default column takes every character not in any of the classes, so realistic tables
+    /// only need columns for the characters they treat specially.
+    pub fn with_default(mut classes: Vec<CharSet>) -> Self {
+        classes.push(CharSet::default());
+        let default = classes.len() - 1;
+        Alphabet::with_default_at(classes, Some(default))
+    }
+
+    /// Create an alphabet whose default column, if any, is at the given column
+    ///
+    /// The class given for the default column is replaced by the characters in no other class.
+    pub(crate) fn with_default_at(mut classes: Vec<CharSet>, default: Option<usize>) -> Self {
+        if let Some(default) = default {
+            let others = classes
+                .iter()
+                .enumerate()
+                .filter(|(column, _)| *column != default)
+                .fold(CharSet::default(), |others, (_, class)| others.union(class));
+            classes[default] = others.complement();
+        }
+
+        Alphabet {
+            default,
+            ..Alphabet::new(classes)
+        }
+    }
+
+    /// Create an alphabet with one column per character, in the given order
+    pub fn from_chars(chars: impl IntoIterator<Item = char>) -> Self {
+        Alphabet::new(chars.into_iter().map(CharSet::single).collect())
+    }
+
+    /// Describe a column, such as "digit" or "operator"
+    ///
+    /// Panics if the column doesn't exist.
+    pub fn with_description(mut self, column: usize, description: &str) -> Self {
+        self.descriptions[column] = Some(description.to_string());
+        self
+    }
+
+    /// Get the description of a column, if it has one
+    pub fn description(&self, column: usize) -> Option<&str> {
+        self.descriptions.get(column)?.as_deref()
+    }
+
+    /// Get the label of a column in diagrams, which is its header cell without the description
+    pub fn label(&self, column: usize) -> String {
+        match self.default {
+            Some(default) if default == column => DEFAULT_SYMBOL.to_string(),
+            _ => self.classes[column].serialize(),
+        }
+    }
+
+    /// Get the label and description of every described column, for legends
+    pub(crate) fn legend(&self) -> Vec<(String, &str)> {
+        (0..self.len())
+            .filter_map(|column| Some((self.label(column), self.description(column)?)))
+            .collect()
+    }
+
+    /// Get the characters of each column
+    pub fn classes(&self) -> &[CharSet] {
+        &self.classes
+    }
+
+    /// Get the default column, which takes every character no other column takes
+    pub fn default_column(&self) -> Option<usize> {
+        self.default
+    }
+
+    /// Get the number of columns
+    pub fn len(&self) -> usize {
+        self.classes.len()
+    }
+
+    /// Check whether the alphabet has no columns
+    pub fn is_empty(&self) -> bool {
+        self.classes.is_empty()
+    }
+
+    /// Get the ranges of characters each column actually takes, sorted and non-overlapping
+    ///
+    /// Unlike [`Alphabet::classes`], a character in several classes only appears once here.
+    pub(crate) fn ranges(&self) -> &[(char, char, usize)] {
+        &self.index
+    }
+
+    /// Get the column of a character
+    pub fn classify(&self, c: char) -> Option<usize> {
+        let index = self.index.partition_point(|(start, _, _)| *start <= c);
+        let (_, end, column) = self.index.get(index.checked_sub(1)?)?;
+        (c <= *end).then_some(*column)
+    }
+
+    /// Parse an alphabet from a header line of whitespace-separated cells, one per column
+    ///
+    /// Each cell is a character set in the bracket syntax of [`CharSet::parse`], or `*` for the
+    /// default column, such as `[a-z] [0-9] *`. A cell can be followed by `=` and a description in
+    /// double quotes, where `\"` and `\\` escape a quote and a backslash, such as `[0-9]="digit"`.
+    pub fn parse(header: &str) -> Result<Self, ParseSerializeError> {
+        let mut classes = Vec::new();
+        let mut default = None;
+        let mut descriptions = Vec::new();
+
+        let mut rest = header.trim_start();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix(DEFAULT_SYMBOL) {
+                if default.replace(classes.len()).is_some() {
+                    return Err(ParseSerializeError {
+                        message: "Alphabet has more than one default column".to_string(),
+                    });
+                }
+                classes.push(CharSet::default());
+                rest = after;
+            } else {
+                // Find the closing bracket, skipping escaped characters
+                let mut escaped = false;
+                let end = rest
+                    .char_indices()
+                    .skip(1)
+                    .find(|(_, c)| {
+                        let closes = !escaped && *c == ']';
+                        escaped = !escaped && *c == '\\';
+                        closes
+                    })
+                    .map(|(index, _)| index + 1)
+                    .unwrap_or(rest.len());
+                classes.push(CharSet::parse(&rest[..end])?);
+                rest = &rest[end..];
+            }
+
+            let description = match rest.strip_prefix(DESCRIPTION_SEPARATOR) {
+                Some(after) => {
+                    let (description, after) = parse_description(after)?;
+                    rest = after;
+                    Some(description)
+                }
+                None => None,
+            };
+            descriptions.push(description);
+
+            if rest.starts_with(|c: char| !c.is_whitespace()) {
+                return Err(ParseSerializeError {
+                    message: format!("Alphabet cells must be separated by whitespace: {}", rest),
+                });
+            }
+            rest = rest.trim_start();
+        }
+
+        Ok(Alphabet {
+            descriptions,
+            ..Alphabet::with_default_at(classes, default)
+        })
+    }
+
+    /// Serialize the alphabet as a header line, in the format read by [`Alphabet::parse`]
+    pub fn serialize(&self) -> String {
+        (0..self.len())
+            .map(|column| match self.description(column) {
+                Some(description) => format!(
+                    "{}{}\"{}\"",
+                    self.label(column),
+                    DESCRIPTION_SEPARATOR,
+                    description.replace('\\', "\\\\").replace('"', "\\\"")
+                ),
+                None => self.label(column),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// Parse a description in double quotes at the start of a header, returning it and the rest
+fn parse_description(header: &str) -> Result<(String, &str), ParseSerializeError> {
+    let unterminated = || ParseSerializeError {
+        message: format!("Unterminated alphabet description: {}", header),
+    };
+    let mut chars = header
+        .strip_prefix('"')
+        .ok_or_else(|| ParseSerializeError {
+            message: format!("Alphabet descriptions must be in double quotes: {}", header),
+        })?
+        .char_indices();
+
+    let mut description = String::new();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '"' => return Ok((description, &header[index + 2..])),
+            '\\' => description.push(chars.next().ok_or_else(unterminated)?.1),
+            _ => description.push(c),
+        }
+    }
+
+    Err(unterminated())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alphabet_classify() {
+        let alphabet = Alphabet::new(vec![
+            CharSet::new([('a', 'z')]),
+            CharSet::new([('0', '9'), ('x', 'x')]),
+        ]);
+
+        assert_eq!(alphabet.len(), 2);
+        assert_eq!(alphabet.classify('a'), Some(0));
+        assert_eq!(alphabet.classify('x'), Some(0));
+        assert_eq!(alphabet.classify('5'), Some(1));
+        assert_eq!(alphabet.classify('_'), None);
+        assert_eq!(Alphabet::from_chars("ab".chars()).classify('b'), Some(1));
+    }
+
+    #[test]
+    fn default_column() -> Result<(), ParseSerializeError> {
+        let alphabet = Alphabet::with_default(vec![CharSet::new([('0', '9')])]);
+        assert_eq!(alphabet.default_column(), Some(1));
+        assert_eq!(alphabet.classify('7'), Some(0));
+        assert_eq!(alphabet.classify('x'), Some(1));
+        assert_eq!(alphabet.classify('\u{10FFFF}'), Some(1));
+
+        let parsed = Alphabet::parse(" [a-z]  * [\\]\u{20}] ")?;
+        assert_eq!(parsed.default_column(), Some(1));
+        assert_eq!(parsed.classify(']'), Some(2));
+        assert_eq!(parsed.classify(' '), Some(2));
+        assert_eq!(parsed.classify('_'), Some(1));
+        assert_eq!(parsed.serialize(), "[a-z] * [\\u{20}\\]]");
+        assert_eq!(Alphabet::parse(&parsed.serialize())?, parsed);
+
+        assert!(Alphabet::parse("* *").is_err());
+        assert!(Alphabet::parse("[a][b]").is_err());
+        assert!(Alphabet::parse("a").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn descriptions() -> Result<(), ParseSerializeError> {
+        let alphabet = Alphabet::parse(r#"[0-9]="digit" [+] *="anything \"else\"""#)?;
+        assert_eq!(alphabet.description(0), Some("digit"));
+        assert_eq!(alphabet.description(1), None);
+        assert_eq!(alphabet.description(2), Some("anything \"else\""));
+        assert_eq!(alphabet.classify('x'), Some(2));
+        assert_eq!(Alphabet::parse(&alphabet.serialize())?, alphabet);
+
+        let described = Alphabet::from_chars("+-".chars()).with_description(1, "minus");
+        assert_eq!(described.serialize(), r#"[+] [\-]="minus""#);
+        assert_eq!(described.legend(), [("[\\-]".to_string(), "minus")]);
+
+        assert!(Alphabet::parse("[a]=digit").is_err());
+        assert!(Alphabet::parse(r#"[a]="digit"#).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{Matrix, TransitionTable, STARTING_STATE_ID};
+
+/// The most power iterations to run when estimating an eigenvalue
+const MAX_ITERATIONS: usize = 100_000;
+
+/// The change in the eigenvector below which power iteration stops
+const TOLERANCE: f64 = 1e-12;
+
+impl TransitionTable {
+    /// Estimate the exponential growth rate of the language
+    ///
+    /// This is the dominant eigenvalue of the transfer matrix of the minimized table, so the number
+    /// of accepted strings of length `n` grows like `rate^n`. Finite languages have a rate of 0, and
+    /// a rate of 1 means the language grows polynomially. Every column counts as a distinct symbol.
+    pub fn language_growth_rate(&self) -> f64 {
+        // The spectral radius of a matrix is the largest among its strongly connected components,
+        // and each component's matrix is irreducible, so power iteration converges quickly on it
+        self.minimize()
+            .split_components()
+            .components
+            .iter()
+            .map(|component| spectral_radius(&component.table.to_adjacency_matrix()))
+            .fold(0.0, f64::max)
+    }
+
+    /// Compute the topological entropy of the language in bits per symbol
+    ///
+    /// This is the base 2 logarithm of [`TransitionTable::language_growth_rate`], clamped to 0 for
+    /// languages that do not grow exponentially.
+    pub fn topological_entropy(&self) -> f64 {
+        self.language_growth_rate().log2().max(0.0)
+    }
+
+    /// Compute the probability that a random string of length `len` is accepted
+    ///
+    /// Each symbol of the string is drawn independently, with `symbol_distribution` giving the
+    /// probability of each column. Any probability not given to a column, including columns past
+    /// the end of the distribution, is treated as a symbol with no column, which rejects the
+    /// string. Returns 0 if the starting state does not exist.
+    pub fn acceptance_probability(&self, len: usize, symbol_distribution: &[f64]) -> f64 {
+        let Some(start) = self.index_of(STARTING_STATE_ID) else {
+            return 0.0;
+        };
+
+        // The probability of being in each row after each prefix length
+        let mut probabilities = vec![0.0; self.rows.len()];
+        probabilities[start] = 1.0;
+        for _ in 0..len {
+            let mut next = vec![0.0; self.rows.len()];
+            for (index, probability) in probabilities.iter().enumerate() {
+                if *probability == 0.0 {
+                    continue;
+                }
+
+                let transitions = &self.rows[index].transitions;
+                for (target, weight) in transitions.iter().zip(symbol_distribution) {
+                    if let Some(target) = target.and_then(|target| self.index_of(target)) {
+                        next[target] += probability * weight;
+                    }
+                }
+            }
+            probabilities = next;
+        }
+
+        probabilities
+            .iter()
+            .zip(&self.rows)
+            .filter(|(_, row)| row.accepting)
+            .map(|(probability, _)| probability)
+            .sum()
+    }
+
+    /// Count the accepted strings of every length from 0 to `max_len` in one pass
+    ///
+    /// Element `n` is the number of accepted sequences of `n` columns, so the result is the first
+    /// coefficients of the language's generating function. Counts too large for a `u128`
+    /// saturate at [`u128::MAX`]. Every count is 0 if the starting state does not exist.
+    pub fn word_count_series(&self, max_len: usize) -> Vec<u128> {
+        let Some(start) = self.index_of(STARTING_STATE_ID) else {
+            return vec![0; max_len + 1];
+        };
+        let count_accepted = |counts: &[u128]| {
+            counts
+                .iter()
+                .zip(&self.rows)
+                .filter(|(_, row)| row.accepting)
+                .fold(0u128, |total, (count, _)| total.saturating_add(*count))
+        };
+
+        // The number of strings of the current length reaching each row
+        let mut counts = vec![0u128; self.rows.len()];
+        counts[start] = 1;
+        let mut series = vec![count_accepted(&counts)];
+        for _ in 0..max_len {
+            let mut next = vec![0u128; self.rows.len()];
+            for (index, count) in counts.iter().enumerate() {
+                if *count == 0 {
+                    continue;
+                }
+
+                for target in self.rows[index].transitions.iter().flatten() {
+                    if let Some(target) = self.index_of(*target) {
+                        next[target] = next[target].saturating_add(*count);
+                    }
+                }
+            }
+            counts = next;
+            series.push(count_accepted(&counts));
+        }
+
+        series
+    }
+}
+
+/// Estimate the dominant eigenvalue of an irreducible non-negative square matrix
+///
+/// Iterates with the matrix plus the identity, which is primitive even when the matrix is
+/// periodic, and so has a dominant eigenvalue that power iteration converges to.
+fn spectral_radius(matrix: &Matrix<u64>) -> f64 {
+    let size = matrix.rows();
+    if size == 0 {
+        return 0.0;
+    }
+
+    let mut vector = vec![1.0; size];
+    let mut eigenvalue = 0.0;
+    for _ in 0..MAX_ITERATIONS {
+        let mut next: Vec<f64> = (0..size)
+            .map(|row| {
+                let product: f64 = matrix
+                    .row(row)
+                    .iter()
+                    .zip(&vector)
+                    .map(|(entry, value)| *entry as f64 * value)
+                    .sum();
+                product + vector[row]
+            })
+            .collect();
+
+        eigenvalue = next.iter().copied().fold(0.0, f64::max);
+        next.iter_mut().for_each(|value| *value /= eigenvalue);
+
+        let change = next
+            .iter()
+            .zip(&vector)
+            .map(|(a, b)| (a - b).abs())
+            .fold(0.0, f64::max);
+        vector = next;
+        if change < TOLERANCE {
+            break;
+        }
+    }
+
+    eigenvalue - 1.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn growth_rate() -> Result<(), ParseSerializeError> {
+        // All strings over two symbols
+        let all = TransitionTable::parse("+ 0 0 0\n")?;
+        assert_close(all.language_growth_rate(), 2.0);
+        assert_close(all.topological_entropy(), 1.0);
+
+        // Strings without two consecutive 1s grow by the golden ratio
+        let fibonacci = TransitionTable::parse("+ 0 0 1\n+ 1 0 E\n")?;
+        assert_close(fibonacci.language_growth_rate(), (1.0 + 5f64.sqrt()) / 2.0);
+
+        // Alternating symbols is periodic, and a*b* grows polynomially
+        let alternating = TransitionTable::parse("+ 0 E 1\n+ 1 0 E\n")?;
+        assert_close(alternating.language_growth_rate(), 1.0);
+        let polynomial = TransitionTable::parse("+ 0 0 1\n+ 1 E 1\n")?;
+        assert_close(polynomial.language_growth_rate(), 1.0);
+        assert_close(polynomial.topological_entropy(), 0.0);
+
+        // The counts of strings without two consecutive 1s are Fibonacci numbers
+        assert_eq!(fibonacci.word_count_series(6), vec![1, 2, 3, 5, 8, 13, 21]);
+        assert_eq!(polynomial.word_count_series(3), vec![1, 2, 3, 4]);
+        assert_eq!(all.word_count_series(200)[200], u128::MAX);
+
+        // Finite languages do not grow
+        let finite = TransitionTable::parse("- 0 1 1\n+ 1 E E\n")?;
+        assert_close(finite.language_growth_rate(), 0.0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn acceptance_probability() -> Result<(), ParseSerializeError> {
+        // Strings with an even number of 1s
+        let even = TransitionTable::parse("+ 0 0 1\n- 1 1 0\n")?;
+        assert_close(even.acceptance_probability(0, &[0.5, 0.5]), 1.0);
+        assert_close(even.acceptance_probability(1, &[0.5, 0.5]), 0.5);
+        assert_close(even.acceptance_probability(2, &[0.9, 0.1]), 0.82);
+
+        // Missing probability mass rejects
+        assert_close(even.acceptance_probability(1, &[0.5]), 0.5);
+        assert_close(even.acceptance_probability(2, &[0.5]), 0.25);
+
+        Ok(())
+    }
+}
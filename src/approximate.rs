@@ -0,0 +1,126 @@
+use crate::{Alphabet, TransitionTable, STARTING_STATE_ID};
+use std::collections::VecDeque;
+
+/// The closest accepted string to an input, found by [`TransitionTable::approximate_run`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ApproximateMatch {
+    /// The number of insertions, deletions, and substitutions between the input and `corrected`
+    pub cost: usize,
+
+    /// The columns of the accepted string closest to the input
+    pub corrected: Vec<usize>,
+}
+
+impl TransitionTable {
+    /// Run the table on a sequence of columns, tolerating up to `k` edits
+    ///
+    /// Each symbol inserted, deleted, or substituted costs 1. Returns the accepted string closest
+    /// to the input and its cost, or `None` if every accepted string needs more than `k` edits.
+    /// Input symbols of `None` have no column, so they can only be deleted or substituted. Ties
+    /// between equally close strings are broken arbitrarily but deterministically.
+    pub fn approximate_run(&self, input: &[Option<usize>], k: usize) -> Option<ApproximateMatch> {
+        let start = self.index_of(STARTING_STATE_ID)?;
+        let width = self.width();
+        let rows = self.rows.len();
+        let node = |position: usize, index: usize| position * rows + index;
+
+        // A breadth-first search over input positions and rows where edges cost 0 or 1
+        let mut costs = vec![usize::MAX; (input.len() + 1) * rows];
+        let mut parents: Vec<Option<(usize, Option<usize>)>> = vec![None; costs.len()];
+        let mut queue = VecDeque::from([(0, start)]);
+        costs[node(0, start)] = 0;
+
+        while let Some((position, index)) = queue.pop_front() {
+            let current = node(position, index);
+            let cost = costs[current];
+            if position == input.len() && self.rows[index].accepting {
+                let mut corrected = Vec::new();
+                let mut at = current;
+                while let Some((parent, symbol)) = parents[at] {
+                    corrected.extend(symbol);
+                    at = parent;
+                }
+                corrected.reverse();
+                return Some(ApproximateMatch { cost, corrected });
+            }
+
+            let mut relax = |position: usize, index: usize, step: usize, symbol: Option<usize>| {
+                let next = node(position, index);
+                if cost + step <= k && cost + step < costs[next] {
+                    costs[next] = cost + step;
+                    parents[next] = Some((current, symbol));
+                    if step == 0 {
+                        queue.push_front((position, index));
+                    } else {
+                        queue.push_back((position, index));
+                    }
+                }
+            };
+
+            // Delete the next input symbol
+            if position < input.len() {
+                relax(position + 1, index, 1, None);
+            }
+
+            for symbol in 0..width {
+                let Some(target) = self
+                    .next_state(self.rows[index].id, symbol)
+                    .and_then(|target| self.index_of(target))
+                else {
+                    continue;
+                };
+
+                // Insert the column, or match or substitute the next input symbol with it
+                relax(position, target, 1, Some(symbol));
+                if let Some(expected) = input.get(position) {
+                    let step = usize::from(*expected != Some(symbol));
+                    relax(position + 1, target, step, Some(symbol));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Run the table on a string with up to `k` edits, classifying each character with `alphabet`
+    pub fn approximate_run_str(
+        &self,
+        alphabet: &Alphabet,
+        input: &str,
+        k: usize,
+    ) -> Option<ApproximateMatch> {
+        let input: Vec<_> = input.chars().map(|c| alphabet.classify(c)).collect();
+        self.approximate_run(&input, k)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn approximate_run() -> Result<(), ParseSerializeError> {
+        // Exactly "abc"
+        let table = TransitionTable::parse("- 0 1 E E\n- 1 E 2 E\n- 2 E E 3\n+ 3 E E E\n")?;
+        let alphabet = Alphabet::from_chars("abc".chars());
+        let cost = |input: &str, k: usize| {
+            table
+                .approximate_run_str(&alphabet, input, k)
+                .map(|found| found.cost)
+        };
+
+        assert_eq!(cost("abc", 0), Some(0));
+        assert_eq!(cost("abd", 1), Some(1));
+        assert_eq!(cost("ac", 1), Some(1));
+        assert_eq!(cost("abbc", 1), Some(1));
+        assert_eq!(cost("cba", 2), Some(2));
+        assert_eq!(cost("cba", 1), None);
+        assert_eq!(cost("", 3), Some(3));
+
+        let found = table.approximate_run_str(&alphabet, "xbc", 1).unwrap();
+        assert_eq!(found.corrected, vec![0, 1, 2]);
+
+        Ok(())
+    }
+}
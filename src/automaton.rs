@@ -0,0 +1,381 @@
+use crate::run_config::run_chars_with;
+use crate::search::{find_at, longest_match};
+use crate::{
+    Alphabet, FrozenTable, RunConfig, SymbolMap, TransitionTable, TransitionTableRow,
+    UnknownSymbolError, STARTING_STATE_ID,
+};
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Range;
+
+/// A deterministic automaton over numbered columns, which running and searching are generic over
+///
+/// Implementing the four required methods gives a backend every provided method, and
+/// [`TransitionTable::from_automaton`] turns it into a table for every other operation in the
+/// crate. The dense [`TransitionTable`], the flat [`FrozenTable`], and, with the `bdd` feature,
+/// the shared-diagram `BddTable` implement it.
+pub trait Automaton {
+    /// The type identifying a state, such as a state ID or row index
+    type State: Copy + Eq;
+
+    /// Get the starting state, or `None` if there isn't one
+    fn start(&self) -> Option<Self::State>;
+
+    /// Check whether a state is accepting
+    fn is_accepting(&self, state: Self::State) -> bool;
+
+    /// Get the state reached from a state on a column, or `None` for an error transition
+    fn next_state(&self, state: Self::State, symbol: usize) -> Option<Self::State>;
+
+    /// Get the number of columns
+    fn width(&self) -> usize;
+
+    /// Check whether a state is a trap, which is not accepting and can never reach an accepting
+    /// state
+    ///
+    /// Checking acceptance stops as soon as a run enters a trap. Backends that don't know their
+    /// traps can leave every state a non-trap.
+    fn is_trap(&self, _state: Self::State) -> bool {
+        false
+    }
+
+    /// Run a sequence of columns from the starting state, returning the state reached
+    ///
+    /// The columns can be any iterator, such as a slice or a lazy decoder.
+    fn run<I>(&self, input: I) -> Option<Self::State>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        input.into_iter().try_fold(self.start()?, |state, symbol| {
+            self.next_state(state, *symbol.borrow())
+        })
+    }
+
+    /// Check whether a sequence of columns is accepted, stopping early at a trap
+    fn accepts<I>(&self, input: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let Some(mut state) = self.start() else {
+            return false;
+        };
+        for symbol in input {
+            match self.next_state(state, *symbol.borrow()) {
+                Some(next) if !self.is_trap(next) => state = next,
+                _ => return false,
+            }
+        }
+        self.is_accepting(state)
+    }
+
+    /// Run columns produced by a fallible iterator, stopping at the first error
+    ///
+    /// Columns are only read until the automaton reaches an error transition, so an error
+    /// produced after that point is not returned.
+    fn try_run<I, B, E>(&self, input: I) -> Result<Option<Self::State>, E>
+    where
+        I: IntoIterator<Item = Result<B, E>>,
+        B: Borrow<usize>,
+    {
+        let Some(mut state) = self.start() else {
+            return Ok(None);
+        };
+        for symbol in input {
+            match self.next_state(state, *symbol?.borrow()) {
+                Some(next) => state = next,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(state))
+    }
+
+    /// Check whether the columns produced by a fallible iterator are accepted
+    fn try_accepts<I, B, E>(&self, input: I) -> Result<bool, E>
+    where
+        I: IntoIterator<Item = Result<B, E>>,
+        B: Borrow<usize>,
+    {
+        Ok(self
+            .try_run(input)?
+            .is_some_and(|state| self.is_accepting(state)))
+    }
+
+    /// Get the length of the longest accepted prefix of a sequence of columns
+    fn longest_prefix<I>(&self, input: I) -> Option<usize>
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let symbols = input
+            .into_iter()
+            .enumerate()
+            .map(|(offset, symbol)| (offset + 1, Some(*symbol.borrow())));
+        longest_match(self, 0, symbols)
+    }
+
+    /// Run a string from the starting state, classifying each character with `alphabet`
+    fn run_chars(&self, alphabet: &Alphabet, input: &str) -> Option<Self::State> {
+        input.chars().try_fold(self.start()?, |state, c| {
+            self.next_state(state, alphabet.classify(c)?)
+        })
+    }
+
+    /// Run a string from the starting state, classifying each character with `alphabet` and
+    /// handling characters outside it as `config` says
+    fn run_chars_with(
+        &self,
+        alphabet: &Alphabet,
+        input: &str,
+        config: &RunConfig,
+    ) -> Result<Option<Self::State>, UnknownSymbolError> {
+        run_chars_with(self, alphabet, input, config)
+    }
+
+    /// Check whether a string is accepted, handling characters outside `alphabet` as `config`
+    /// says
+    fn accepts_chars_with(
+        &self,
+        alphabet: &Alphabet,
+        input: &str,
+        config: &RunConfig,
+    ) -> Result<bool, UnknownSymbolError> {
+        Ok(self
+            .run_chars_with(alphabet, input, config)?
+            .is_some_and(|state| self.is_accepting(state)))
+    }
+
+    /// Run a sequence of symbols from the starting state, mapping each to its column with `symbols`
+    ///
+    /// Symbols that are not mapped are treated like error transitions.
+    fn run_symbols<'s, S: Eq + Hash + 's>(
+        &self,
+        symbols: &SymbolMap<S>,
+        input: impl IntoIterator<Item = &'s S>,
+    ) -> Option<Self::State> {
+        input.into_iter().try_fold(self.start()?, |state, symbol| {
+            self.next_state(state, symbols.column(symbol)?)
+        })
+    }
+
+    /// Check whether a sequence of symbols is accepted, mapping each to its column with `symbols`
+    fn accepts_symbols<'s, S: Eq + Hash + 's>(
+        &self,
+        symbols: &SymbolMap<S>,
+        input: impl IntoIterator<Item = &'s S>,
+    ) -> bool {
+        self.run_symbols(symbols, input)
+            .is_some_and(|state| self.is_accepting(state))
+    }
+
+    /// Find the byte range of the leftmost-longest accepted substring of a string
+    fn find_chars(&self, alphabet: &Alphabet, haystack: &str) -> Option<Range<usize>> {
+        find_at(self, alphabet, haystack, 0)
+    }
+}
+
+impl<A: Automaton + ?Sized> Automaton for &A {
+    type State = A::State;
+
+    fn start(&self) -> Option<Self::State> {
+        (**self).start()
+    }
+
+    fn is_accepting(&self, state: Self::State) -> bool {
+        (**self).is_accepting(state)
+    }
+
+    fn next_state(&self, state: Self::State, symbol: usize) -> Option<Self::State> {
+        (**self).next_state(state, symbol)
+    }
+
+    fn width(&self) -> usize {
+        (**self).width()
+    }
+
+    fn is_trap(&self, state: Self::State) -> bool {
+        (**self).is_trap(state)
+    }
+}
+
+impl Automaton for TransitionTable {
+    /// The state ID
+    type State = usize;
+
+    fn start(&self) -> Option<usize> {
+        self.row(STARTING_STATE_ID).map(|row| row.id)
+    }
+
+    fn is_accepting(&self, state: usize) -> bool {
+        self.row(state).is_some_and(|row| row.accepting)
+    }
+
+    fn next_state(&self, state: usize, symbol: usize) -> Option<usize> {
+        TransitionTable::next_state(self, state, symbol)
+    }
+
+    fn width(&self) -> usize {
+        TransitionTable::width(self)
+    }
+}
+
+impl Automaton for FrozenTable {
+    /// The row index
+    type State = usize;
+
+    fn start(&self) -> Option<usize> {
+        FrozenTable::start(self)
+    }
+
+    fn is_accepting(&self, state: usize) -> bool {
+        FrozenTable::is_accepting(self, state)
+    }
+
+    fn next_state(&self, state: usize, symbol: usize) -> Option<usize> {
+        self.next(state, symbol)
+    }
+
+    fn width(&self) -> usize {
+        FrozenTable::width(self)
+    }
+
+    fn is_trap(&self, state: usize) -> bool {
+        FrozenTable::is_trap(self, state)
+    }
+}
+
+impl TransitionTable {
+    /// Build a table from the states of any automaton reachable from its starting state
+    ///
+    /// States are numbered in breadth-first order from the starting state. An automaton without a
+    /// starting state gives the empty language.
+    pub fn from_automaton<A>(automaton: &A) -> TransitionTable
+    where
+        A: Automaton + ?Sized,
+        A::State: Hash,
+    {
+        let width = automaton.width();
+        let Some(start) = automaton.start() else {
+            return TransitionTable::empty_language(width);
+        };
+
+        let mut ids = HashMap::from([(start, STARTING_STATE_ID)]);
+        let mut queue = VecDeque::from([start]);
+        let mut rows = Vec::new();
+        while let Some(state) = queue.pop_front() {
+            let transitions = (0..width)
+                .map(|symbol| {
+                    let target = automaton.next_state(state, symbol)?;
+                    let next_id = ids.len();
+                    Some(*ids.entry(target).or_insert_with(|| {
+                        queue.push_back(target);
+                        next_id
+                    }))
+                })
+                .collect();
+
+            rows.push(TransitionTableRow {
+                accepting: automaton.is_accepting(state),
+                id: ids[&state],
+                transitions,
+                doc: None,
+                label: None,
+            });
+        }
+
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharSet, ParseSerializeError};
+
+    /// Counts symbols up to a limit, accepting multiples of three
+    struct Counter {
+        limit: u32,
+    }
+
+    impl Automaton for Counter {
+        type State = u32;
+
+        fn start(&self) -> Option<u32> {
+            Some(0)
+        }
+
+        fn is_accepting(&self, state: u32) -> bool {
+            state.is_multiple_of(3)
+        }
+
+        fn next_state(&self, state: u32, _symbol: usize) -> Option<u32> {
+            (state < self.limit).then_some(state + 1)
+        }
+
+        fn width(&self) -> usize {
+            1
+        }
+    }
+
+    #[test]
+    fn automaton_backends() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 2\n- 2 E 1\n")?;
+        let frozen = FrozenTable::from_table(&table);
+        let alphabet = Alphabet::new(vec![CharSet::single('a'), CharSet::single('b')]);
+
+        fn check(automaton: impl Automaton, alphabet: &Alphabet) {
+            assert!(automaton.accepts([0, 0, 1, 1]));
+            assert!(!automaton.accepts([0, 1]));
+            assert_eq!(automaton.longest_prefix([0, 1, 1, 0, 1]), Some(4));
+            assert_eq!(automaton.longest_prefix([1]), None);
+            assert!(automaton.run_chars(alphabet, "abb").is_some());
+            assert_eq!(automaton.find_chars(alphabet, "bbaab"), Some(2..4));
+        }
+        check(&table, &alphabet);
+        check(&frozen, &alphabet);
+
+        assert_eq!(TransitionTable::from_automaton(&frozen), table);
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_automaton() {
+        let counter = Counter { limit: 4 };
+        assert!(counter.accepts([0, 0, 0]));
+        assert!(!counter.accepts([0, 0, 0, 0, 0]));
+
+        let table = TransitionTable::from_automaton(&counter);
+        assert_eq!(table.serialize(), "+ 0 1\n- 1 2\n- 2 3\n+ 3 4\n- 4 E\n");
+        assert_eq!(table.language_growth_rate(), 0.0);
+    }
+
+    #[test]
+    fn run_iterators() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 2\n- 2 E 1\n")?;
+
+        // Columns decoded lazily from bytes
+        let decoded = b"aabb".iter().map(|byte| (byte - b'a') as usize);
+        assert!(table.accepts(decoded));
+        assert_eq!(table.run(vec![0, 1]), Some(2));
+
+        // A decoding error is returned, unless the run already failed
+        let decode = |input: &'static str| {
+            input
+                .chars()
+                .map(|c| c.to_digit(2).map(|d| d as usize).ok_or(c))
+        };
+        assert_eq!(table.try_accepts(decode("011")), Ok(true));
+        assert_eq!(table.try_run(decode("01x")), Err('x'));
+        assert_eq!(table.try_run(decode("1x")), Ok(None));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use crate::flat::{AsciiClassifier, FlatTable, DEAD};
+use crate::{Alphabet, BitSet, TransitionTable};
+
+impl TransitionTable {
+    /// Check which of many strings the table accepts
+    ///
+    /// Bit `i` of the result is set when the table accepts input `i`. The table is flattened into
+    /// one contiguous array first and ASCII characters are classified through a lookup table, so
+    /// this is much faster than checking the strings one at a time for large batches of short
+    /// strings.
+    pub fn classify_batch<I, S>(&self, alphabet: &Alphabet, inputs: I) -> BitSet
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let flat = FlatTable::new(self);
+        let classifier = AsciiClassifier::new(alphabet);
+
+        inputs
+            .into_iter()
+            .map(|input| {
+                let mut state = flat.start;
+                for c in input.as_ref().chars() {
+                    state = match classifier.classify(c) {
+                        Some(symbol) => flat.next(state, symbol),
+                        None => DEAD,
+                    };
+                    if state == DEAD {
+                        break;
+                    }
+                }
+                flat.is_accepting(state)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharSet, ParseSerializeError};
+
+    #[test]
+    fn classify_batch() -> Result<(), ParseSerializeError> {
+        // Even numbers of 'a's, with any number of 'b's
+        let table = TransitionTable::parse("+ 0 1 0\n- 1 0 1\n")?;
+        let alphabet = Alphabet::new(vec![CharSet::single('a'), CharSet::single('b')]);
+
+        let inputs = ["", "a", "aa", "abab", "bab", "aac", "aä"];
+        let accepted = table.classify_batch(&alphabet, inputs);
+
+        assert_eq!(accepted.len(), inputs.len());
+        assert_eq!(accepted.iter_ones().collect::<Vec<_>>(), vec![0, 2, 3]);
+
+        Ok(())
+    }
+}
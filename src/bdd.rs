@@ -0,0 +1,243 @@
+use crate::{Automaton, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::HashMap;
+
+/// A node of a [`BddTable`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Node {
+    /// The target state of every symbol reaching the node, or `None` for error transitions
+    Leaf(Option<usize>),
+
+    /// A test of one bit of the symbol, most significant bit first
+    Branch {
+        /// The index of the bit tested, counting from the most significant bit
+        bit: usize,
+
+        /// The node for symbols with the bit clear
+        low: usize,
+
+        /// The node for symbols with the bit set
+        high: usize,
+    },
+}
+
+/// A transition table storing each state's transition function as a shared binary decision diagram
+///
+/// Every column index is split into bits, and each state has a root node deciding its target
+/// from those bits. Identical subdiagrams are stored once across all states, so tables over wide
+/// alphabets where runs of columns share targets take far less memory than the dense table, at the
+/// cost of one node visit per bit for each lookup.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BddTable {
+    /// All nodes, with each distinct node stored once
+    nodes: Vec<Node>,
+
+    /// The state ID, acceptance, and root node of every state, sorted by state ID
+    states: Vec<(usize, bool, usize)>,
+
+    /// The number of columns
+    width: usize,
+
+    /// The number of bits in a column index
+    bits: usize,
+}
+
+impl BddTable {
+    /// Encode a dense table
+    pub fn from_table(table: &TransitionTable) -> Self {
+        let width = table.width();
+        let bits = usize::BITS as usize - width.saturating_sub(1).leading_zeros() as usize;
+        let mut builder = Builder {
+            nodes: Vec::new(),
+            unique: HashMap::new(),
+            bits,
+        };
+
+        let states = table
+            .rows
+            .iter()
+            .map(|row| {
+                let root = builder.build(&row.transitions, 0, 0);
+                (row.id, row.accepting, root)
+            })
+            .collect();
+
+        BddTable {
+            nodes: builder.nodes,
+            states,
+            width,
+            bits,
+        }
+    }
+
+    /// Decode the table back into a dense table
+    pub fn to_table(&self) -> TransitionTable {
+        let rows = self
+            .states
+            .iter()
+            .map(|(id, accepting, root)| TransitionTableRow {
+                accepting: *accepting,
+                id: *id,
+                transitions: (0..self.width)
+                    .map(|symbol| self.lookup(*root, symbol))
+                    .collect(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+
+    /// Get the state reached from `state` on the given column
+    ///
+    /// Returns `None` for error transitions, and when the state or column does not exist.
+    pub fn next_state(&self, state: usize, symbol: usize) -> Option<usize> {
+        if symbol >= self.width {
+            return None;
+        }
+
+        let index = self
+            .states
+            .binary_search_by_key(&state, |(id, _, _)| *id)
+            .ok()?;
+        self.lookup(self.states[index].2, symbol)
+    }
+
+    /// Check whether the state with the given ID is accepting
+    pub fn is_accepting(&self, state: usize) -> bool {
+        self.states
+            .binary_search_by_key(&state, |(id, _, _)| *id)
+            .is_ok_and(|index| self.states[index].1)
+    }
+
+    /// Get the number of distinct nodes, a measure of the memory used
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Follow the bits of a column from a root node to its leaf
+    fn lookup(&self, root: usize, symbol: usize) -> Option<usize> {
+        let mut node = root;
+        loop {
+            match self.nodes[node] {
+                Node::Leaf(target) => return target,
+                Node::Branch { bit, low, high } => {
+                    node = if symbol >> (self.bits - 1 - bit) & 1 == 0 {
+                        low
+                    } else {
+                        high
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Hash-consing construction of the nodes of a [`BddTable`]
+struct Builder {
+    /// The nodes built so far
+    nodes: Vec<Node>,
+
+    /// The index of every node built so far
+    unique: HashMap<Node, usize>,
+
+    /// The number of bits in a column index
+    bits: usize,
+}
+
+impl Builder {
+    /// Build the node deciding the targets of the columns starting with `prefix`, which has
+    /// `bit` bits
+    fn build(&mut self, transitions: &[Option<usize>], bit: usize, prefix: usize) -> usize {
+        if bit == self.bits {
+            return self.node(Node::Leaf(transitions.get(prefix).copied().flatten()));
+        }
+
+        let low = self.build(transitions, bit + 1, prefix << 1);
+        let high = self.build(transitions, bit + 1, prefix << 1 | 1);
+        if low == high {
+            // The bit doesn't matter, so skip testing it
+            return low;
+        }
+
+        self.node(Node::Branch { bit, low, high })
+    }
+
+    /// Get the index of a node, adding it if it is new
+    fn node(&mut self, node: Node) -> usize {
+        *self.unique.entry(node).or_insert_with(|| {
+            self.nodes.push(node);
+            self.nodes.len() - 1
+        })
+    }
+}
+
+impl Automaton for BddTable {
+    /// The state ID
+    type State = usize;
+
+    fn start(&self) -> Option<usize> {
+        self.states
+            .binary_search_by_key(&STARTING_STATE_ID, |(id, _, _)| *id)
+            .ok()
+            .map(|_| STARTING_STATE_ID)
+    }
+
+    fn is_accepting(&self, state: usize) -> bool {
+        BddTable::is_accepting(self, state)
+    }
+
+    fn next_state(&self, state: usize, symbol: usize) -> Option<usize> {
+        BddTable::next_state(self, state, symbol)
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn bdd_table() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 1 1 1 2\n+ 1 1 1 1 1 2\n+ 2 E E E 0 0\n")?;
+
+        let bdd = BddTable::from_table(&table);
+        assert_eq!(bdd.to_table(), table);
+        assert_eq!(bdd.next_state(0, 4), Some(2));
+        assert_eq!(bdd.next_state(2, 0), None);
+        assert_eq!(bdd.next_state(2, 5), None);
+        assert_eq!(bdd.next_state(3, 0), None);
+        assert!(bdd.is_accepting(1));
+        assert!(!bdd.is_accepting(0));
+
+        // A wide row with one exception needs one node per bit, plus the leaves
+        let mut transitions = vec![Some(0); 1 << 16];
+        transitions[1234] = None;
+        let wide = TransitionTable {
+            rows: vec![TransitionTableRow {
+                accepting: true,
+                id: 0,
+                transitions,
+                doc: None,
+                label: None,
+            }],
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        };
+        let bdd = BddTable::from_table(&wide);
+        assert_eq!(bdd.node_count(), 18);
+        assert_eq!(bdd.to_table(), wide);
+
+        Ok(())
+    }
+}
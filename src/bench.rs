@@ -0,0 +1,224 @@
+//! Workload generators and timing helpers for comparing backends and configurations
+//!
+//! Workloads are generated from a seed, so the same seed gives the same tables and inputs on
+//! every run and machine:
+//!
+//! ```
+//! use transition_tables::bench::{measure, Workload};
+//!
+//! let mut workload = Workload::new(42);
+//! let table = workload.table(100, 4, 0.1);
+//! let inputs = workload.inputs(&table, 50, 16, 0.5).unwrap();
+//!
+//! let measurement = measure(10, || inputs.iter().filter(|input| table.accepts(*input)).count());
+//! println!("{:?} per run", measurement.median());
+//! ```
+
+use crate::rng::Rng;
+use crate::{TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// A seeded generator of random tables and inputs
+#[derive(Clone, Debug)]
+pub struct Workload {
+    /// The random number generator
+    rng: Rng,
+}
+
+impl Workload {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        Workload::with_rng(Rng::new(seed))
+    }
+
+    /// Create a generator drawing from another generator
+    pub fn with_rng(rng: Rng) -> Self {
+        Workload { rng }
+    }
+
+    /// Generate a table with `states` states numbered from 0 and `width` columns
+    ///
+    /// Each transition is an error transition with probability `error_rate`, and otherwise enters a
+    /// uniformly random state. About a quarter of the states accept.
+    pub fn table(&mut self, states: usize, width: usize, error_rate: f64) -> TransitionTable {
+        let states = states.max(1);
+        let threshold = (error_rate.clamp(0.0, 1.0) * u32::MAX as f64) as usize;
+        let rows = (0..states)
+            .map(|id| TransitionTableRow {
+                accepting: self.rng.below(4) == 0,
+                id,
+                transitions: (0..width)
+                    .map(|_| {
+                        (self.rng.below(u32::MAX as usize) >= threshold)
+                            .then(|| self.rng.below(states))
+                    })
+                    .collect(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+
+    /// Generate `count` inputs of `len` columns, of which a fraction `accept_rate` are accepted
+    ///
+    /// Accepted and rejected inputs are random walks restricted to paths that end the right way,
+    /// in a random order. Returns `None` if the table accepts no inputs or rejects every input of
+    /// that length but some were asked for.
+    pub fn inputs(
+        &mut self,
+        table: &TransitionTable,
+        count: usize,
+        len: usize,
+        accept_rate: f64,
+    ) -> Option<Vec<Vec<usize>>> {
+        let accepted = (count as f64 * accept_rate.clamp(0.0, 1.0)).round() as usize;
+        let mut inputs = Vec::with_capacity(count);
+        for index in 0..count {
+            inputs.push(self.walk(table, len, index < accepted)?);
+        }
+
+        // Shuffle so accepted and rejected inputs are interleaved
+        for index in (1..inputs.len()).rev() {
+            inputs.swap(index, self.rng.below(index + 1));
+        }
+
+        Some(inputs)
+    }
+
+    /// Generate a random input of `len` columns that the table accepts or rejects
+    fn walk(&mut self, table: &TransitionTable, len: usize, accept: bool) -> Option<Vec<usize>> {
+        let width = table.width();
+        let sink = table.rows.len();
+        let ends_right = |index: usize| {
+            let accepting = index != sink && table.rows[index].accepting;
+            accepting == accept
+        };
+
+        // `viable[remaining][index]` is whether some walk of `remaining` more columns from a row
+        // index, or the sink, ends the right way
+        let mut viable = vec![(0..=sink).map(ends_right).collect::<Vec<_>>()];
+        for remaining in 1..=len {
+            let previous = &viable[remaining - 1];
+            let row = (0..=sink)
+                .map(|index| (0..width).any(|symbol| previous[table.delta(index, symbol)]))
+                .collect();
+            viable.push(row);
+        }
+
+        let mut index = table.index_of(STARTING_STATE_ID).unwrap_or(sink);
+        if !viable[len][index] {
+            return None;
+        }
+
+        let mut input = Vec::with_capacity(len);
+        for remaining in (0..len).rev() {
+            let choices = (0..width)
+                .filter(|symbol| viable[remaining][table.delta(index, *symbol)])
+                .collect::<Vec<_>>();
+            let symbol = choices[self.rng.below(choices.len())];
+            input.push(symbol);
+            index = table.delta(index, symbol);
+        }
+
+        Some(input)
+    }
+}
+
+/// The times taken by repeated runs of a function
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Measurement {
+    /// The time of each run, sorted
+    samples: Vec<Duration>,
+}
+
+impl Measurement {
+    /// Get the time of each run, sorted from fastest to slowest
+    pub fn samples(&self) -> &[Duration] {
+        &self.samples
+    }
+
+    /// Get the fastest run
+    pub fn min(&self) -> Duration {
+        self.samples.first().copied().unwrap_or_default()
+    }
+
+    /// Get the median run
+    pub fn median(&self) -> Duration {
+        self.samples
+            .get(self.samples.len() / 2)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Get the mean run
+    pub fn mean(&self) -> Duration {
+        let total: Duration = self.samples.iter().sum();
+        total / self.samples.len().max(1) as u32
+    }
+
+    /// Get the number of items processed per second by the median run
+    pub fn throughput(&self, items: usize) -> f64 {
+        items as f64 / self.median().as_secs_f64()
+    }
+}
+
+/// Time `samples` runs of a function after one untimed warm-up run
+///
+/// The function's result is passed through [`black_box`] so the work isn't optimized away.
+pub fn measure<R>(samples: usize, mut f: impl FnMut() -> R) -> Measurement {
+    black_box(f());
+
+    let mut times = (0..samples)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect::<Vec<_>>();
+    times.sort_unstable();
+
+    Measurement { samples: times }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn workload() {
+        let mut workload = Workload::new(3);
+        let table = workload.table(20, 3, 0.2);
+        assert_eq!(table.rows.len(), 20);
+        assert_eq!(Workload::new(3).table(20, 3, 0.2), table);
+
+        let inputs = workload.inputs(&table, 40, 12, 0.25).unwrap();
+        assert_eq!(inputs.len(), 40);
+        assert!(inputs.iter().all(|input| input.len() == 12));
+        assert_eq!(
+            inputs.iter().filter(|input| table.accepts(*input)).count(),
+            10
+        );
+
+        // Nothing is accepted when every state rejects
+        let rejecting = TransitionTable::parse("- 0 0\n").unwrap();
+        assert!(workload.inputs(&rejecting, 2, 3, 0.5).is_none());
+        assert!(workload.inputs(&rejecting, 2, 3, 0.0).is_some());
+    }
+
+    #[test]
+    fn measure_runs() {
+        let mut runs = 0;
+        let measurement = measure(5, || runs += 1);
+        assert_eq!(runs, 6);
+        assert_eq!(measurement.samples().len(), 5);
+        assert!(measurement.min() <= measurement.median());
+    }
+}
@@ -0,0 +1,310 @@
+//! Command line tools for transition tables
+//!
+//! Usage: `ttables tui <table file> [--symbols <characters>]`
+//!
+//! The stepper reads one line at a time, so it works over pipes as well as in a terminal.
+//! Characters are sorted into columns by `--symbols`, or else the table's `alphabet:` header.
+
+use std::io::{BufRead, Write};
+use transition_tables::{Alphabet, TransitionTable, STARTING_STATE_ID};
+
+/// The characters used for the columns when neither `--symbols` nor a header is given
+const DEFAULT_SYMBOLS: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// ANSI escape code that clears the screen and moves the cursor to the top left
+const CLEAR: &str = "\x1b[2J\x1b[H";
+
+/// ANSI escape code for reverse video, used to highlight the current row
+const REVERSE: &str = "\x1b[7m";
+
+/// ANSI escape code for bold red text, used to highlight the taken transition
+const TAKEN: &str = "\x1b[1;31m";
+
+/// ANSI escape code that resets all attributes
+const RESET: &str = "\x1b[0m";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    let result = match args.first().map(String::as_str) {
+        Some("tui") => tui(&args[1..]),
+        _ => Err("usage: ttables tui <table file> [--symbols <characters>]".to_string()),
+    };
+
+    if let Err(message) = result {
+        eprintln!("{}", message);
+        std::process::exit(1);
+    }
+}
+
+/// Run the interactive stepper
+fn tui(args: &[String]) -> Result<(), String> {
+    let path = args.first().ok_or("missing table file")?;
+    let symbols = match args.get(1).map(String::as_str) {
+        Some("--symbols") => Some(args.get(2).ok_or("missing symbols")?.as_str()),
+        Some(other) => return Err(format!("unknown argument {}", other)),
+        None => None,
+    };
+
+    let input = std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let table = TransitionTable::parse(&input).map_err(|e| e.to_string())?;
+
+    let mut stepper = Stepper::new(&table, alphabet(&table, symbols));
+    let stdin = std::io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("{}", stepper.render());
+        std::io::stdout().flush().map_err(|e| e.to_string())?;
+
+        let Some(line) = lines.next() else {
+            break;
+        };
+        if !stepper.handle(&line.map_err(|e| e.to_string())?) {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the alphabet to run with, from `--symbols` if given, or else from the table's header
+fn alphabet(table: &TransitionTable, symbols: Option<&str>) -> Alphabet {
+    match (symbols, &table.alphabet) {
+        (Some(symbols), _) => Alphabet::from_chars(symbols.chars()),
+        (None, Some(alphabet)) => alphabet.clone(),
+        (None, None) => Alphabet::from_chars(DEFAULT_SYMBOLS.chars()),
+    }
+}
+
+/// The state of an interactive run
+struct Stepper<'a> {
+    /// The table being run
+    table: &'a TransitionTable,
+
+    /// The alphabet sorting characters into columns
+    alphabet: Alphabet,
+
+    /// The characters consumed so far
+    consumed: String,
+
+    /// The state before each consumed character, plus the current state
+    ///
+    /// `None` means the run hit an error transition.
+    states: Vec<Option<usize>>,
+
+    /// The last transition taken, as a state ID and column
+    taken: Option<(usize, usize)>,
+
+    /// A message about the last input, if there is one
+    message: Option<String>,
+}
+
+impl<'a> Stepper<'a> {
+    fn new(table: &'a TransitionTable, alphabet: Alphabet) -> Self {
+        Stepper {
+            table,
+            alphabet,
+            consumed: String::new(),
+            states: vec![Some(STARTING_STATE_ID)],
+            taken: None,
+            message: None,
+        }
+    }
+
+    /// The current state, or `None` after an error transition
+    fn current(&self) -> Option<usize> {
+        *self.states.last().unwrap()
+    }
+
+    /// Handle a line of input, returning `false` if it quits
+    ///
+    /// `:q` quits, `:r` resets, `:b` goes back, and anything else is consumed character by
+    /// character.
+    fn handle(&mut self, line: &str) -> bool {
+        match line.trim() {
+            ":q" => return false,
+            ":r" => self.reset(),
+            ":b" => self.back(),
+            _ => {
+                // Only the line ending is dropped, since the alphabet may include whitespace
+                let characters = line.strip_suffix('\n').unwrap_or(line);
+                let characters = characters.strip_suffix('\r').unwrap_or(characters);
+                for character in characters.chars() {
+                    self.step(character);
+                }
+            }
+        }
+        true
+    }
+
+    /// Consume one character
+    fn step(&mut self, character: char) {
+        let Some(symbol) = self.alphabet.classify(character) else {
+            self.message = Some(format!("'{}' is not in the alphabet", character));
+            return;
+        };
+        let Some(state) = self.current() else {
+            self.message = Some("the run has already failed".to_string());
+            return;
+        };
+
+        self.consumed.push(character);
+        self.states.push(self.table.next_state(state, symbol));
+        self.taken = Some((state, symbol));
+        self.message = None;
+    }
+
+    /// Undo the last consumed character
+    fn back(&mut self) {
+        if self.states.len() > 1 {
+            self.states.pop();
+            self.consumed.pop();
+        }
+        self.taken = None;
+        self.message = None;
+    }
+
+    /// Go back to the starting state
+    fn reset(&mut self) {
+        *self = Stepper::new(self.table, self.alphabet.clone());
+    }
+
+    /// Draw the table and run status
+    fn render(&self) -> String {
+        let mut output = String::from(CLEAR);
+
+        // Header with the label of every column, which may be wider than the cells
+        let columns = self.table.width();
+        let labels = (0..columns)
+            .map(|symbol| {
+                if symbol < self.alphabet.len() {
+                    self.alphabet.label(symbol)
+                } else {
+                    "?".to_string()
+                }
+            })
+            .collect::<Vec<_>>();
+        let width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0)
+            .max(3);
+        output.push_str("      ");
+        for label in &labels {
+            output.push_str(&format!(" {:>width$}", label));
+        }
+        output.push('\n');
+
+        for row in &self.table.rows {
+            let current = self.current() == Some(row.id);
+            if current {
+                output.push_str(REVERSE);
+            }
+            output.push_str(&format!(
+                "{} {:>4}",
+                if row.accepting { '+' } else { '-' },
+                row.id
+            ));
+            for (symbol, transition) in row.transitions.iter().enumerate() {
+                let cell = transition.map_or("E".to_string(), |target| target.to_string());
+                if self.taken == Some((row.id, symbol)) {
+                    output.push_str(&format!(" {}{:>width$}{}", TAKEN, cell, RESET));
+                    if current {
+                        output.push_str(REVERSE);
+                    }
+                } else {
+                    output.push_str(&format!(" {:>width$}", cell));
+                }
+            }
+            if current {
+                output.push_str(RESET);
+            }
+            output.push('\n');
+        }
+
+        output.push_str(&format!("\ninput: {}\n", self.consumed));
+        match self.current() {
+            Some(state) => {
+                let accepting = self.table.row(state).is_some_and(|row| row.accepting);
+                output.push_str(&format!(
+                    "state: {} ({})\n",
+                    state,
+                    if accepting {
+                        "accepting"
+                    } else {
+                        "not accepting"
+                    }
+                ));
+            }
+            None => output.push_str("state: error (rejected)\n"),
+        }
+        if let Some(message) = &self.message {
+            output.push_str(&format!("{}\n", message));
+        }
+        output.push_str("\ntype symbols to step, :b to go back, :r to reset, :q to quit\n> ");
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stepper_commands() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 0 1\n").unwrap();
+        let mut stepper = Stepper::new(&table, alphabet(&table, Some("ab")));
+
+        assert!(stepper.handle("ab\n"));
+        assert_eq!(
+            (stepper.consumed.as_str(), stepper.current()),
+            ("ab", Some(1))
+        );
+        assert!(stepper.handle("c"));
+        assert_eq!(
+            stepper.message.as_deref(),
+            Some("'c' is not in the alphabet")
+        );
+
+        assert!(stepper.handle(":b"));
+        assert_eq!(
+            (stepper.consumed.as_str(), stepper.current()),
+            ("a", Some(1))
+        );
+        assert!(stepper.handle("ab"));
+        assert_eq!(stepper.current(), None);
+        assert!(stepper.handle(" :r "));
+        assert_eq!(
+            (stepper.consumed.as_str(), stepper.current()),
+            ("", Some(0))
+        );
+
+        assert!(!stepper.handle(":q"));
+
+        // Whitespace around the input is stepped over when the alphabet has it
+        let mut stepper = Stepper::new(&table, alphabet(&table, Some(" \t")));
+        assert!(stepper.handle(" \t \r\n"));
+        assert_eq!(
+            (stepper.consumed.as_str(), stepper.current()),
+            (" \t ", Some(0))
+        );
+    }
+
+    #[test]
+    fn stepper_uses_table_alphabet() {
+        let table = TransitionTable::parse("alphabet: [a-z] [0-9]\n- 0 1 E\n+ 1 1 1\n").unwrap();
+        let mut stepper = Stepper::new(&table, alphabet(&table, None));
+
+        assert!(stepper.handle("x7"));
+        assert_eq!(stepper.current(), Some(1));
+        assert!(stepper.render().contains("[a-z] [0-9]"));
+
+        // `--symbols` overrides the header
+        let mut stepper = Stepper::new(&table, alphabet(&table, Some("01")));
+        stepper.handle("0");
+        assert_eq!(stepper.current(), Some(1));
+    }
+}
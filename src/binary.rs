@@ -0,0 +1,271 @@
+use crate::flat::{FlatTable, DEAD};
+use crate::{TransitionTable, TransitionTableRow};
+use std::io::{Read, Write};
+
+/// The bytes every binary table file starts with
+pub const BINARY_MAGIC: [u8; 4] = *b"TTBL";
+
+/// The version of the binary table format written by this crate
+pub const BINARY_VERSION: u32 = 1;
+
+/// The size of the binary format's header, in bytes
+pub(crate) const HEADER_SIZE: u64 = 24;
+
+/// The marker for an error transition in the binary format
+pub(crate) const BINARY_DEAD: u64 = u64::MAX;
+
+/// Errors that can occur when reading a binary table
+#[derive(Debug, thiserror::Error)]
+pub enum BinaryFormatError {
+    /// Reading the input failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The input doesn't start with [`BINARY_MAGIC`]
+    #[error("not a binary transition table")]
+    InvalidMagic,
+
+    /// The input was written by an unsupported version of the format
+    #[error("unsupported binary table version {0}")]
+    UnsupportedVersion(u32),
+
+    /// The input is structurally invalid
+    #[error("invalid binary table: {0}")]
+    Invalid(String),
+}
+
+/// The header of a binary table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Header {
+    /// The number of columns
+    pub width: u64,
+
+    /// The number of rows
+    pub row_count: u64,
+}
+
+impl Header {
+    /// Read and validate a header
+    pub fn read(reader: &mut impl Read) -> Result<Self, BinaryFormatError> {
+        let mut bytes = [0; HEADER_SIZE as usize];
+        reader.read_exact(&mut bytes)?;
+        Header::parse(&bytes)
+    }
+
+    /// Validate a header from its bytes
+    pub fn parse(bytes: &[u8]) -> Result<Self, BinaryFormatError> {
+        if bytes.len() < HEADER_SIZE as usize {
+            return Err(BinaryFormatError::Invalid("truncated header".to_string()));
+        }
+        if bytes[..4] != BINARY_MAGIC {
+            return Err(BinaryFormatError::InvalidMagic);
+        }
+
+        let version = u32::from_le_bytes(bytes[4..8].try_into().unwrap());
+        if version != BINARY_VERSION {
+            return Err(BinaryFormatError::UnsupportedVersion(version));
+        }
+
+        Ok(Header {
+            width: u64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+            row_count: u64::from_le_bytes(bytes[16..24].try_into().unwrap()),
+        })
+    }
+
+    /// Get the offset of the transitions section, in bytes
+    ///
+    /// Only valid once [`Header::file_size`] has been checked.
+    pub fn transitions_offset(&self) -> u64 {
+        HEADER_SIZE + 2 * 8 * self.row_count
+    }
+
+    /// Get the total size of a file with this header, in bytes
+    pub fn file_size(&self) -> Option<u64> {
+        let transitions = self.width.checked_mul(self.row_count)?.checked_mul(8)?;
+        let ids_and_accepting = self.row_count.checked_mul(2 * 8)?;
+        transitions
+            .checked_add(ids_and_accepting)?
+            .checked_add(HEADER_SIZE)
+    }
+}
+
+impl TransitionTable {
+    /// Write the table in the binary format
+    ///
+    /// The format is a 24-byte header (the magic bytes, a little-endian `u32` version, and `u64`
+    /// column and row counts), then the sorted state IDs, then the acceptance of each row (0 or
+    /// 1), then the transitions in row-major order. All values after the header are little-endian
+    /// `u64`s, and transitions hold the target's row index, or `u64::MAX` for error transitions.
+    /// Every row has a fixed size, so any row can be read without reading the others.
+    pub fn write_binary(&self, writer: &mut impl Write) -> std::io::Result<()> {
+        let flat = FlatTable::new(self);
+
+        writer.write_all(&BINARY_MAGIC)?;
+        writer.write_all(&BINARY_VERSION.to_le_bytes())?;
+        writer.write_all(&(flat.width as u64).to_le_bytes())?;
+        writer.write_all(&(self.rows.len() as u64).to_le_bytes())?;
+
+        for row in &self.rows {
+            writer.write_all(&(row.id as u64).to_le_bytes())?;
+        }
+        for row in &self.rows {
+            writer.write_all(&u64::from(row.accepting).to_le_bytes())?;
+        }
+        for target in &flat.transitions {
+            let target = if *target == DEAD {
+                BINARY_DEAD
+            } else {
+                *target as u64
+            };
+            writer.write_all(&target.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+
+    /// Read a table in the binary format written by [`TransitionTable::write_binary`]
+    ///
+    /// The sections are read in bounded chunks, so a header claiming more rows or columns than
+    /// the input holds fails as invalid instead of allocating for them.
+    pub fn read_binary(reader: &mut impl Read) -> Result<Self, BinaryFormatError> {
+        let header = Header::read(reader)?;
+        if header.file_size().is_none() {
+            return Err(BinaryFormatError::Invalid(
+                "table size overflows".to_string(),
+            ));
+        }
+        let row_count = usize::try_from(header.row_count)
+            .map_err(|_| BinaryFormatError::Invalid("too many rows".to_string()))?;
+        let width = usize::try_from(header.width)
+            .map_err(|_| BinaryFormatError::Invalid("too many columns".to_string()))?;
+
+        let ids = read_section(reader, row_count, "state IDs")?
+            .into_iter()
+            .map(|id| id as usize)
+            .collect::<Vec<_>>();
+        if ids.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(BinaryFormatError::Invalid(
+                "state IDs are not sorted".to_string(),
+            ));
+        }
+        let accepting = read_section(reader, row_count, "acceptance")?;
+
+        let mut rows = Vec::with_capacity(row_count);
+        for (id, accepting) in ids.iter().zip(accepting) {
+            let transitions = read_section(reader, width, "transitions")?
+                .into_iter()
+                .map(|target| match target {
+                    BINARY_DEAD => Ok(None),
+                    target => ids.get(target as usize).map(|id| Some(*id)).ok_or_else(|| {
+                        BinaryFormatError::Invalid(format!("row index {} out of range", target))
+                    }),
+                })
+                .collect::<Result<_, _>>()?;
+
+            rows.push(TransitionTableRow {
+                accepting: accepting != 0,
+                id: *id,
+                transitions,
+                doc: None,
+                label: None,
+            });
+        }
+
+        Ok(TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        })
+    }
+}
+
+/// Read `count` little-endian `u64`s of a section of a binary table, failing as invalid if the
+/// input ends first
+fn read_section(
+    reader: &mut impl Read,
+    count: usize,
+    section: &str,
+) -> Result<Vec<u64>, BinaryFormatError> {
+    read_u64s(reader, count).map_err(|error| match error.kind() {
+        std::io::ErrorKind::UnexpectedEof | std::io::ErrorKind::InvalidInput => {
+            BinaryFormatError::Invalid(format!("truncated {}", section))
+        }
+        _ => BinaryFormatError::Io(error),
+    })
+}
+
+/// Read `count` little-endian `u64`s
+///
+/// The buffer only grows as bytes arrive, so a large `count` can't allocate more than the input
+/// holds. Fails with [`std::io::ErrorKind::UnexpectedEof`] if the input ends first.
+pub(crate) fn read_u64s(reader: &mut impl Read, count: usize) -> std::io::Result<Vec<u64>> {
+    let len = count
+        .checked_mul(8)
+        .and_then(|len| u64::try_from(len).ok())
+        .ok_or_else(|| std::io::Error::from(std::io::ErrorKind::InvalidInput))?;
+    let mut bytes = Vec::new();
+    reader.by_ref().take(len).read_to_end(&mut bytes)?;
+    if (bytes.len() as u64) < len {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes
+        .chunks_exact(8)
+        .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn binary_round_trip() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 2 E\n+ 2 2 0\n- 7 E 9\n")?;
+
+        let mut bytes = Vec::new();
+        table.write_binary(&mut bytes).unwrap();
+        assert_eq!(bytes.len(), 24 + 3 * 8 + 3 * 8 + 6 * 8);
+
+        // The transition to the missing state 9 becomes an error transition
+        let read = TransitionTable::read_binary(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read.serialize(), "- 0 2 E\n+ 2 2 0\n- 7 E E\n");
+
+        bytes[0] = b'X';
+        assert!(matches!(
+            TransitionTable::read_binary(&mut bytes.as_slice()),
+            Err(BinaryFormatError::InvalidMagic)
+        ));
+        assert!(matches!(
+            TransitionTable::read_binary(&mut &b"TTBL\x02\0\0\0"[..]),
+            Err(BinaryFormatError::Io(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_binary_oversized_header() {
+        let header = |width: u64, row_count: u64| {
+            let mut bytes = BINARY_MAGIC.to_vec();
+            bytes.extend(BINARY_VERSION.to_le_bytes());
+            bytes.extend(width.to_le_bytes());
+            bytes.extend(row_count.to_le_bytes());
+            bytes
+        };
+
+        // A header claiming far more rows than the input holds fails without allocating them
+        let bytes = header(2, 1 << 40);
+        assert!(matches!(
+            TransitionTable::read_binary(&mut bytes.as_slice()),
+            Err(BinaryFormatError::Invalid(message)) if message == "truncated state IDs"
+        ));
+
+        let bytes = header(u64::MAX, 2);
+        assert!(matches!(
+            TransitionTable::read_binary(&mut bytes.as_slice()),
+            Err(BinaryFormatError::Invalid(message)) if message == "table size overflows"
+        ));
+    }
+}
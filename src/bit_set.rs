@@ -0,0 +1,138 @@
+/// The number of bits in a word of a [`BitSet`]
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A fixed-length sequence of bits packed into words
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    /// The bits, least significant bit first
+    words: Vec<u64>,
+
+    /// The number of bits
+    len: usize,
+}
+
+impl BitSet {
+    /// Create a bit set with `len` cleared bits
+    pub fn new(len: usize) -> Self {
+        BitSet {
+            words: vec![0; len.div_ceil(WORD_BITS)],
+            len,
+        }
+    }
+
+    /// Create an empty bit set with room for `capacity` bits
+    pub fn with_capacity(capacity: usize) -> Self {
+        BitSet {
+            words: Vec::with_capacity(capacity.div_ceil(WORD_BITS)),
+            len: 0,
+        }
+    }
+
+    /// Get the number of bits
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether there are no bits
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Get a bit, or `false` if the index is out of range
+    pub fn get(&self, index: usize) -> bool {
+        index < self.len && self.words[index / WORD_BITS] >> (index % WORD_BITS) & 1 == 1
+    }
+
+    /// Set a bit
+    ///
+    /// Panics if the index is out of range.
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "bit index {} out of range", index);
+
+        let mask = 1 << (index % WORD_BITS);
+        if value {
+            self.words[index / WORD_BITS] |= mask;
+        } else {
+            self.words[index / WORD_BITS] &= !mask;
+        }
+    }
+
+    /// Add a bit to the end
+    pub fn push(&mut self, value: bool) {
+        if self.len.is_multiple_of(WORD_BITS) {
+            self.words.push(0);
+        }
+        self.len += 1;
+        self.set(self.len - 1, value);
+    }
+
+    /// Count the set bits
+    pub fn count_ones(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Iterate over the indices of the set bits, in increasing order
+    pub fn iter_ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words
+            .iter()
+            .enumerate()
+            .flat_map(|(word_index, word)| {
+                let mut word = *word;
+                std::iter::from_fn(move || {
+                    if word == 0 {
+                        return None;
+                    }
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    Some(word_index * WORD_BITS + bit)
+                })
+            })
+    }
+
+    /// Get the packed words, least significant bit first
+    ///
+    /// Bits past the end in the last word are always clear.
+    pub fn as_words(&self) -> &[u64] {
+        &self.words
+    }
+}
+
+impl FromIterator<bool> for BitSet {
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        let iter = iter.into_iter();
+        let mut set = BitSet::with_capacity(iter.size_hint().0);
+        for value in iter {
+            set.push(value);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_set() {
+        let mut set = BitSet::new(130);
+        set.set(0, true);
+        set.set(64, true);
+        set.set(129, true);
+        set.set(64, false);
+
+        assert_eq!(set.len(), 130);
+        assert!(set.get(0));
+        assert!(!set.get(64));
+        assert!(set.get(129));
+        assert!(!set.get(500));
+        assert_eq!(set.count_ones(), 2);
+        assert_eq!(set.iter_ones().collect::<Vec<_>>(), vec![0, 129]);
+
+        let collected = [true, false, true].into_iter().collect::<BitSet>();
+        assert_eq!(collected.len(), 3);
+        assert_eq!(collected.as_words(), &[0b101]);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{FrozenTable, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::BTreeMap;
+
+/// Errors from editing or building a table with a [`TableBuilder`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum BuildError {
+    /// A state used by an edit or transition does not exist
+    #[error("state {0} does not exist")]
+    MissingState(usize),
+
+    /// A column is not less than the builder's width
+    #[error("column {symbol} is outside the table's {width} columns")]
+    ColumnOutOfRange { symbol: usize, width: usize },
+
+    /// The starting state does not exist
+    #[error("the starting state does not exist")]
+    MissingStartingState,
+}
+
+/// A mutable table for editing, which is checked when it is built or frozen
+///
+/// Edits are checked as they are made where possible, but transitions may enter states that are
+/// added later, so they are only checked when building. Building gives a [`TransitionTable`] for
+/// analysis and transformation, and freezing gives a [`FrozenTable`] with flat storage for
+/// matching. Both can be thawed back into a builder.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableBuilder {
+    /// The rows by state ID
+    rows: BTreeMap<usize, TransitionTableRow>,
+
+    /// The number of columns
+    width: usize,
+}
+
+impl TableBuilder {
+    /// Create a builder with no states and the given number of columns
+    pub fn new(width: usize) -> Self {
+        TableBuilder {
+            rows: BTreeMap::new(),
+            width,
+        }
+    }
+
+    /// Get the number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Iterate over the state IDs in order
+    pub fn states(&self) -> impl Iterator<Item = usize> + '_ {
+        self.rows.keys().copied()
+    }
+
+    /// Add a state with the next unused ID and only error transitions, returning its ID
+    ///
+    /// The first state added is the starting state.
+    pub fn add_state(&mut self, accepting: bool) -> usize {
+        let id = self
+            .rows
+            .last_key_value()
+            .map_or(STARTING_STATE_ID, |(id, _)| id + 1);
+        self.insert_state(id, accepting);
+        id
+    }
+
+    /// Add a state with the given ID and only error transitions
+    ///
+    /// Returns `false` and changes nothing if the state already exists.
+    pub fn insert_state(&mut self, id: usize, accepting: bool) -> bool {
+        if self.rows.contains_key(&id) {
+            return false;
+        }
+
+        self.rows.insert(
+            id,
+            TransitionTableRow {
+                accepting,
+                id,
+                transitions: vec![None; self.width],
+                doc: None,
+                label: None,
+            },
+        );
+        true
+    }
+
+    /// Remove a state, turning the transitions entering it into error transitions
+    pub fn remove_state(&mut self, id: usize) -> Result<(), BuildError> {
+        self.rows.remove(&id).ok_or(BuildError::MissingState(id))?;
+        for row in self.rows.values_mut() {
+            for transition in &mut row.transitions {
+                if *transition == Some(id) {
+                    *transition = None;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Set whether a state is accepting
+    pub fn set_accepting(&mut self, id: usize, accepting: bool) -> Result<(), BuildError> {
+        self.row_mut(id)?.accepting = accepting;
+        Ok(())
+    }
+
+    /// Set the transition leaving a state on a column, where `None` is an error transition
+    pub fn set_transition(
+        &mut self,
+        from: usize,
+        symbol: usize,
+        to: Option<usize>,
+    ) -> Result<(), BuildError> {
+        let width = self.width;
+        let transition = self
+            .row_mut(from)?
+            .transitions
+            .get_mut(symbol)
+            .ok_or(BuildError::ColumnOutOfRange { symbol, width })?;
+        *transition = to;
+        Ok(())
+    }
+
+    /// Get the transition leaving a state on a column
+    pub fn transition(&self, from: usize, symbol: usize) -> Option<usize> {
+        self.rows.get(&from)?.transitions.get(symbol).copied()?
+    }
+
+    /// Check that the starting state exists and every transition enters an existing state
+    pub fn validate(&self) -> Result<(), BuildError> {
+        if !self.rows.contains_key(&STARTING_STATE_ID) {
+            return Err(BuildError::MissingStartingState);
+        }
+
+        let missing = self
+            .rows
+            .values()
+            .flat_map(|row| row.transitions.iter().flatten())
+            .find(|target| !self.rows.contains_key(target));
+        match missing {
+            Some(target) => Err(BuildError::MissingState(*target)),
+            None => Ok(()),
+        }
+    }
+
+    /// Validate the builder and copy it into a [`TransitionTable`]
+    pub fn build(&self) -> Result<TransitionTable, BuildError> {
+        self.validate()?;
+        Ok(TransitionTable {
+            rows: self.rows.values().cloned().collect(),
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        })
+    }
+
+    /// Validate the builder and copy it into a [`FrozenTable`] for matching
+    pub fn freeze(&self) -> Result<FrozenTable, BuildError> {
+        Ok(FrozenTable::from_table(&self.build()?))
+    }
+
+    /// Get the row of a state for editing
+    fn row_mut(&mut self, id: usize) -> Result<&mut TransitionTableRow, BuildError> {
+        self.rows.get_mut(&id).ok_or(BuildError::MissingState(id))
+    }
+}
+
+impl TransitionTable {
+    /// Copy the table into a [`TableBuilder`] for editing
+    ///
+    /// Rows narrower than the table are padded with error transitions.
+    pub fn thaw(&self) -> TableBuilder {
+        let width = self.width();
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let mut row = row.clone();
+                row.transitions.resize(width, None);
+                (row.id, row)
+            })
+            .collect();
+
+        TableBuilder { rows, width }
+    }
+}
+
+impl FrozenTable {
+    /// Copy the table into a [`TableBuilder`] for editing
+    pub fn thaw(&self) -> TableBuilder {
+        self.to_table().thaw()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn build_and_freeze() -> Result<(), BuildError> {
+        let mut builder = TableBuilder::new(2);
+        let start = builder.add_state(false);
+        let end = builder.add_state(true);
+        builder.set_transition(start, 0, Some(end))?;
+        builder.set_transition(end, 1, Some(7))?;
+
+        assert_eq!(builder.validate(), Err(BuildError::MissingState(7)));
+        assert_eq!(
+            builder.set_transition(start, 2, None),
+            Err(BuildError::ColumnOutOfRange {
+                symbol: 2,
+                width: 2
+            })
+        );
+        assert_eq!(
+            builder.set_accepting(3, true),
+            Err(BuildError::MissingState(3))
+        );
+
+        assert!(builder.insert_state(7, false));
+        assert!(!builder.insert_state(7, true));
+        builder.remove_state(7)?;
+        assert_eq!(builder.transition(end, 1), None);
+
+        let table = builder.build()?;
+        assert_eq!(table.serialize(), "- 0 1 E\n+ 1 E E\n");
+
+        let frozen = builder.freeze()?;
+        assert!(frozen.accepts(&[0]));
+        assert_eq!(frozen.thaw(), builder);
+
+        let mut empty = TableBuilder::new(1);
+        empty.insert_state(1, true);
+        assert_eq!(empty.build(), Err(BuildError::MissingStartingState));
+
+        Ok(())
+    }
+
+    #[test]
+    fn thaw() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n")?;
+        let mut builder = table.thaw();
+        assert_eq!(builder.build(), Ok(table));
+
+        builder.set_accepting(0, true).unwrap();
+        assert_eq!(builder.add_state(false), 2);
+        assert_eq!(builder.states().collect::<Vec<_>>(), vec![0, 1, 2]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{TransitionTable, TransitionTableRow};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+
+/// An operation memoized by an [`OperationCache`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Operation {
+    Intersection,
+    Union,
+}
+
+/// A cached result with the operands it was computed from
+#[derive(Clone, Debug)]
+struct Entry {
+    /// The minimized operands without annotations, to rule out fingerprint collisions
+    operands: (TransitionTable, TransitionTable),
+
+    /// The minimized result
+    result: TransitionTable,
+
+    /// The tick the entry was last used
+    tick: u64,
+}
+
+/// A size-bounded cache of product constructions, keyed by the languages of their operands
+///
+/// Operands are identified by [`TransitionTable::fingerprint`], so tables accepting the same
+/// language share cache entries however they are numbered or annotated. Results are minimized and
+/// unannotated, and when the cache is full the least recently used entry is evicted.
+#[derive(Clone, Debug)]
+pub struct OperationCache {
+    /// The cached results by operation and operand fingerprints
+    entries: HashMap<(Operation, u64, u64), Entry>,
+
+    /// The cached keys by the tick they were last used
+    recency: BTreeMap<u64, (Operation, u64, u64)>,
+
+    /// The maximum number of cached results
+    capacity: usize,
+
+    /// A counter incremented on every cache access
+    tick: u64,
+
+    /// The number of lookups answered from the cache
+    hits: u64,
+}
+
+impl OperationCache {
+    /// Create a cache holding up to `capacity` results
+    pub fn new(capacity: usize) -> Self {
+        OperationCache {
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+            capacity: capacity.max(1),
+            tick: 0,
+            hits: 0,
+        }
+    }
+
+    /// Get the number of cached results
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the cache is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the number of lookups answered from the cache
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    /// Get the minimal table accepting the strings both tables accept
+    pub fn intersection(&mut self, a: &TransitionTable, b: &TransitionTable) -> TransitionTable {
+        self.get_or_compute(Operation::Intersection, a, b, |a, b| a && b)
+    }
+
+    /// Get the minimal table accepting the strings either table accepts
+    pub fn union(&mut self, a: &TransitionTable, b: &TransitionTable) -> TransitionTable {
+        self.get_or_compute(Operation::Union, a, b, |a, b| a || b)
+    }
+
+    /// Look up a commutative product, computing and caching it if it is missing
+    fn get_or_compute(
+        &mut self,
+        operation: Operation,
+        a: &TransitionTable,
+        b: &TransitionTable,
+        accepting: impl Fn(bool, bool) -> bool,
+    ) -> TransitionTable {
+        let (a, b) = (structure(a.minimize()), structure(b.minimize()));
+        let (a_print, b_print) = (fingerprint_minimized(&a), fingerprint_minimized(&b));
+
+        // Both operations are commutative, so order the operands to share entries
+        let (key, operands) = if a_print <= b_print {
+            ((operation, a_print, b_print), (a, b))
+        } else {
+            ((operation, b_print, a_print), (b, a))
+        };
+
+        self.tick += 1;
+        if let Some(entry) = self.entries.get_mut(&key) {
+            if entry.operands == operands {
+                self.hits += 1;
+                self.recency.remove(&entry.tick);
+                entry.tick = self.tick;
+                self.recency.insert(self.tick, key);
+                return entry.result.clone();
+            }
+        }
+
+        let result = operands.0.product(&operands.1, accepting).minimize();
+        if !self.entries.contains_key(&key) && self.entries.len() >= self.capacity {
+            if let Some((_, oldest)) = self.recency.pop_first() {
+                self.entries.remove(&oldest);
+            }
+        }
+        if let Some(old) = self.entries.insert(
+            key,
+            Entry {
+                operands,
+                result: result.clone(),
+                tick: self.tick,
+            },
+        ) {
+            self.recency.remove(&old.tick);
+        }
+        self.recency.insert(self.tick, key);
+
+        result
+    }
+}
+
+impl TransitionTable {
+    /// Compute a hash of the table's language
+    ///
+    /// Tables accepting the same language have the same fingerprint, since it is a hash of the
+    /// minimized table. Different languages almost always have different fingerprints.
+    pub fn fingerprint(&self) -> u64 {
+        fingerprint_minimized(&self.minimize())
+    }
+}
+
+/// Drop the header, metadata, comment, docs, and labels of a table, leaving only its states
+fn structure(table: TransitionTable) -> TransitionTable {
+    TransitionTable {
+        rows: table
+            .rows
+            .into_iter()
+            .map(|row| TransitionTableRow {
+                doc: None,
+                label: None,
+                ..row
+            })
+            .collect(),
+        alphabet: None,
+        metadata: Default::default(),
+        comment: None,
+    }
+}
+
+/// Hash a minimized table
+fn fingerprint_minimized(table: &TransitionTable) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for row in &table.rows {
+        (row.accepting, row.id, &row.transitions).hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn operation_cache() -> Result<(), ParseSerializeError> {
+        // Strings ending in 1, and strings of even length, each written two ways
+        let ends_in_one = TransitionTable::parse("- 0 0 1\n+ 1 0 1\n")?;
+        let ends_in_one_renumbered = TransitionTable::parse("- 0 0 5\n+ 5 0 5\n- 9 9 9\n")?;
+        let even = TransitionTable::parse("+ 0 1 1\n- 1 0 0\n")?;
+        assert_eq!(
+            ends_in_one.fingerprint(),
+            ends_in_one_renumbered.fingerprint()
+        );
+        assert_ne!(ends_in_one.fingerprint(), even.fingerprint());
+
+        let mut cache = OperationCache::new(2);
+        let both = cache.intersection(&ends_in_one, &even);
+        assert_eq!(both.serialize(), "- 0 1 1\n- 1 0 2\n+ 2 1 1\n");
+        assert_eq!(cache.intersection(&even, &ends_in_one_renumbered), both);
+        assert_eq!(cache.hits(), 1);
+
+        let either = cache.union(&ends_in_one, &even);
+        assert_eq!(either.serialize(), "+ 0 1 2\n- 1 0 0\n+ 2 0 0\n");
+        assert_eq!(cache.len(), 2);
+
+        // Evicts the least recently used intersection
+        cache.union(&even, &even);
+        assert_eq!(cache.len(), 2);
+        cache.intersection(&ends_in_one, &even);
+        assert_eq!(cache.hits(), 1);
+
+        // Annotations don't change the language, so they share entries
+        let annotated = TransitionTable::parse(
+            "# Ends in one\n\n%meta generator test\nalphabet: [a] [b]\n- 0 start 0 1\n+ 1 0 1\n",
+        )?;
+        assert_eq!(cache.intersection(&annotated, &even), both);
+        assert_eq!(cache.hits(), 2);
+
+        Ok(())
+    }
+}
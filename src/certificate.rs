@@ -0,0 +1,256 @@
+use crate::{Renumbering, TransitionTable, STARTING_STATE_ID};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// A state of a minimized table and the original states merged into it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MergedState {
+    /// The ID of the state in the minimized table
+    pub state: usize,
+
+    /// The IDs of the original states merged into the state
+    pub originals: Vec<usize>,
+
+    /// Whether the state is accepting
+    pub accepting: bool,
+
+    /// The minimized state entered on each column, or `None` for the dead state
+    pub targets: Vec<Option<usize>>,
+}
+
+/// An explanation of how [`TransitionTable::minimize_with_certificate`] arrived at its result
+///
+/// Every original state is either merged into a minimized state, dead, or unreachable. The merged
+/// states form a bisimulation: every original state merged into a state agrees with it on
+/// acceptance, and each of its transitions enters an original state merged into the corresponding
+/// target. Dead states are non-accepting and only enter dead states. Both facts can be checked
+/// with [`MinimizationCertificate::verify`], and together they show the minimized table accepts the
+/// same language.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MinimizationCertificate {
+    /// The states of the minimized table, in order of ID
+    pub merged: Vec<MergedState>,
+
+    /// The reachable original states that can never reach an accepting state
+    pub dead: Vec<usize>,
+
+    /// The original states that cannot be reached from the starting state
+    pub unreachable: Vec<usize>,
+}
+
+impl MinimizationCertificate {
+    /// Check the certificate's argument against the original table
+    ///
+    /// Returns `true` if the starting state is merged into minimized state 0 or is dead, every
+    /// original merged state agrees with its minimized state, and every dead state is
+    /// non-accepting with only dead or error transitions.
+    pub fn verify(&self, original: &TransitionTable) -> bool {
+        // `None` marks a dead state
+        let mut merged_into = HashMap::new();
+        for merged in &self.merged {
+            for original in &merged.originals {
+                merged_into.insert(*original, Some(merged.state));
+            }
+        }
+        for dead in &self.dead {
+            merged_into.insert(*dead, None);
+        }
+
+        // A transition's target, as the minimized state it was merged into or the dead state
+        let target = |transition: Option<usize>| match transition {
+            Some(target) if original.row(target).is_some() => merged_into.get(&target).copied(),
+            _ => Some(None),
+        };
+
+        let starts_correctly = match merged_into.get(&STARTING_STATE_ID) {
+            Some(start) => start.is_none_or(|start| start == 0),
+            None => original.row(STARTING_STATE_ID).is_none(),
+        };
+
+        let merged_agree = self.merged.iter().all(|merged| {
+            merged.originals.iter().all(|id| {
+                original.row(*id).is_some_and(|row| {
+                    row.accepting == merged.accepting
+                        && (0..merged.targets.len().max(row.transitions.len())).all(|symbol| {
+                            let transition = row.transitions.get(symbol).copied().flatten();
+                            target(transition)
+                                == Some(merged.targets.get(symbol).copied().flatten())
+                        })
+                })
+            })
+        });
+
+        let dead_agree = self.dead.iter().all(|id| {
+            original.row(*id).is_some_and(|row| {
+                !row.accepting
+                    && row
+                        .transitions
+                        .iter()
+                        .all(|transition| target(*transition) == Some(None))
+            })
+        });
+
+        starts_correctly && merged_agree && dead_agree
+    }
+}
+
+impl fmt::Display for MinimizationCertificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |ids: &[usize]| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        for merged in &self.merged {
+            match merged.originals.as_slice() {
+                [original] => writeln!(f, "State {} is original state {}", merged.state, original)?,
+                originals => {
+                    writeln!(
+                        f,
+                        "State {} merges original states {}",
+                        merged.state,
+                        list(originals)
+                    )?;
+
+                    let targets = merged
+                        .targets
+                        .iter()
+                        .enumerate()
+                        .map(|(symbol, target)| match target {
+                            Some(target) => format!("column {} enters state {}", symbol, target),
+                            None => format!("column {} enters the dead state", symbol),
+                        })
+                        .collect::<Vec<_>>();
+                    writeln!(
+                        f,
+                        "  every one is {}, and from each, {}",
+                        if merged.accepting {
+                            "accepting"
+                        } else {
+                            "non-accepting"
+                        },
+                        if targets.is_empty() {
+                            "there are no columns".to_string()
+                        } else {
+                            targets.join(", ")
+                        }
+                    )?;
+                }
+            }
+        }
+
+        let states = |ids: &[usize]| match ids {
+            [id] => format!("Original state {} is", id),
+            ids => format!("Original states {} are", list(ids)),
+        };
+        if !self.dead.is_empty() {
+            writeln!(
+                f,
+                "{} dead: non-accepting, and only entering dead states",
+                states(&self.dead)
+            )?;
+        }
+        if !self.unreachable.is_empty() {
+            writeln!(
+                f,
+                "{} unreachable from the starting state",
+                states(&self.unreachable)
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TransitionTable {
+    /// Minimize the table like [`TransitionTable::minimize`], also explaining the result
+    ///
+    /// The certificate records which original states were merged, which were dead, and which were
+    /// unreachable, along with the argument for why the merges preserve the language. It does not
+    /// argue that the merged states are distinct from each other.
+    pub fn minimize_with_certificate(&self) -> (TransitionTable, MinimizationCertificate) {
+        let (block_of, block_count) = self.equivalence_classes();
+        let (minimized, blocks) =
+            self.quotient_blocks(&block_of, block_count, Renumbering::BreadthFirst);
+
+        let new_state: HashMap<usize, usize> = blocks
+            .iter()
+            .enumerate()
+            .map(|(state, block)| (*block, state))
+            .collect();
+        let reachable = match self.row(STARTING_STATE_ID) {
+            Some(_) => self.renumber_reachable(STARTING_STATE_ID).1,
+            None => Vec::new(),
+        };
+        let reachable: HashSet<usize> = reachable.into_iter().collect();
+
+        let mut certificate = MinimizationCertificate {
+            merged: minimized
+                .rows
+                .iter()
+                .filter(|_| !blocks.is_empty())
+                .map(|row| MergedState {
+                    state: row.id,
+                    originals: Vec::new(),
+                    accepting: row.accepting,
+                    targets: row.transitions.clone(),
+                })
+                .collect(),
+            ..Default::default()
+        };
+        for (index, row) in self.rows.iter().enumerate() {
+            if !reachable.contains(&row.id) {
+                certificate.unreachable.push(row.id);
+            } else if let Some(state) = new_state.get(&block_of[index]) {
+                certificate.merged[*state].originals.push(row.id);
+            } else {
+                certificate.dead.push(row.id);
+            }
+        }
+
+        (minimized, certificate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn minimize_with_certificate() -> Result<(), ParseSerializeError> {
+        // States 1 and 2 are equivalent, state 4 is dead, and state 5 is unreachable
+        let table =
+            TransitionTable::parse("- 0 1 2\n- 1 3 4\n- 2 3 4\n+ 3 3 3\n- 4 4 4\n+ 5 0 0\n")?;
+        let (minimized, certificate) = table.minimize_with_certificate();
+
+        assert_eq!(minimized, table.minimize());
+        assert_eq!(certificate.merged[1].originals, vec![1, 2]);
+        assert_eq!(certificate.dead, vec![4]);
+        assert_eq!(certificate.unreachable, vec![5]);
+        assert!(certificate.verify(&table));
+        assert_eq!(
+            certificate.to_string(),
+            "State 0 is original state 0\n\
+             State 1 merges original states 1, 2\n  \
+             every one is non-accepting, and from each, column 0 enters state 2, column 1 enters \
+             the dead state\n\
+             State 2 is original state 3\n\
+             Original state 4 is dead: non-accepting, and only entering dead states\n\
+             Original state 5 is unreachable from the starting state\n"
+        );
+
+        // A wrong merge fails to verify
+        let mut wrong = certificate.clone();
+        wrong.merged[1].originals.push(0);
+        assert!(!wrong.verify(&table));
+
+        // Everything is dead in the empty language
+        let (_, empty) = TransitionTable::parse("- 0 1 0\n- 1 0 1\n")?.minimize_with_certificate();
+        assert_eq!(empty.dead, vec![0, 1]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,311 @@
+use crate::ParseSerializeError;
+
+/// A set of characters, stored as sorted, non-overlapping, non-adjacent inclusive ranges
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CharSet {
+    /// The ranges in the set
+    ranges: Vec<(char, char)>,
+}
+
+impl CharSet {
+    /// Create a set from arbitrary inclusive ranges
+    ///
+    /// Ranges may overlap or be given in any order. Ranges whose start is after their end are
+    /// ignored.
+    pub fn new(ranges: impl IntoIterator<Item = (char, char)>) -> Self {
+        let mut ranges = ranges
+            .into_iter()
+            .filter(|(start, end)| start <= end)
+            .collect::<Vec<_>>();
+        ranges.sort_unstable();
+
+        // Merge overlapping and adjacent ranges
+        let mut merged: Vec<(char, char)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if next_char(last.1).is_none_or(|next| start <= next) => {
+                    last.1 = last.1.max(end);
+                }
+                _ => merged.push((start, end)),
+            }
+        }
+
+        CharSet { ranges: merged }
+    }
+
+    /// Create a set containing a single character
+    pub fn single(c: char) -> Self {
+        CharSet {
+            ranges: vec![(c, c)],
+        }
+    }
+
+    /// Create a set containing every character
+    pub fn any() -> Self {
+        CharSet {
+            ranges: vec![('\0', char::MAX)],
+        }
+    }
+
+    /// Get the ranges in the set, sorted and non-overlapping
+    pub fn ranges(&self) -> &[(char, char)] {
+        &self.ranges
+    }
+
+    /// Check whether the set contains a character
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges
+            .binary_search_by(|(start, end)| {
+                if *end < c {
+                    std::cmp::Ordering::Less
+                } else if *start > c {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// Check whether the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Get the characters in either set
+    pub fn union(&self, other: &CharSet) -> CharSet {
+        CharSet::new(self.ranges.iter().chain(&other.ranges).copied())
+    }
+
+    /// Get the characters in both sets
+    pub fn intersection(&self, other: &CharSet) -> CharSet {
+        let mut ranges = Vec::new();
+        let (mut left, mut right) = (0, 0);
+
+        while left < self.ranges.len() && right < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[left];
+            let (b_start, b_end) = other.ranges[right];
+
+            let (start, end) = (a_start.max(b_start), a_end.min(b_end));
+            if start <= end {
+                ranges.push((start, end));
+            }
+
+            // Advance past whichever range ends first
+            if a_end < b_end {
+                left += 1;
+            } else {
+                right += 1;
+            }
+        }
+
+        CharSet { ranges }
+    }
+
+    /// Get the characters not in the set
+    pub fn complement(&self) -> CharSet {
+        let mut ranges = Vec::new();
+        let mut next = Some('\0');
+
+        for (start, end) in &self.ranges {
+            if let (Some(gap_start), Some(gap_end)) = (next, previous_char(*start)) {
+                if gap_start <= gap_end {
+                    ranges.push((gap_start, gap_end));
+                }
+            }
+            next = next_char(*end);
+        }
+        if let Some(gap_start) = next {
+            ranges.push((gap_start, char::MAX));
+        }
+
+        CharSet { ranges }
+    }
+
+    /// Get the characters in this set but not the other
+    pub fn difference(&self, other: &CharSet) -> CharSet {
+        self.intersection(&other.complement())
+    }
+
+    /// Check whether the set contains at least one character
+    pub fn is_satisfiable(&self) -> bool {
+        !self.is_empty()
+    }
+
+    /// Get the smallest character in the set, if there is one
+    pub fn first(&self) -> Option<char> {
+        self.ranges.first().map(|(start, _)| *start)
+    }
+
+    /// Parse a set in the bracket syntax used by [`CharSet::serialize`], such as `[a-z_]`
+    ///
+    /// Inside the brackets, `\` escapes the next character and `\u{...}` is a hexadecimal code
+    /// point.
+    pub fn parse(input: &str) -> Result<Self, ParseSerializeError> {
+        let error = |message: &str| ParseSerializeError {
+            message: format!("Invalid character set {}: {}", input, message),
+        };
+
+        let inner = input
+            .strip_prefix('[')
+            .and_then(|inner| inner.strip_suffix(']'))
+            .ok_or_else(|| error("expected brackets"))?;
+
+        // Decode the escapes first, so ranges work the same for escaped characters
+        let mut chars = Vec::new();
+        let mut iter = inner.chars();
+        while let Some(c) = iter.next() {
+            if c != '\\' {
+                chars.push((c, false));
+                continue;
+            }
+
+            match iter.next() {
+                Some('u') => {
+                    let rest = iter.as_str();
+                    let hex = rest
+                        .strip_prefix('{')
+                        .and_then(|rest| rest.split_once('}'))
+                        .map(|(hex, _)| hex)
+                        .ok_or_else(|| error("unterminated code point escape"))?;
+                    let c = u32::from_str_radix(hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| error("invalid code point"))?;
+                    chars.push((c, true));
+                    iter = rest[hex.len() + 2..].chars();
+                }
+                Some(c) => chars.push((c, true)),
+                None => return Err(error("trailing escape")),
+            }
+        }
+
+        let mut ranges = Vec::new();
+        let mut index = 0;
+        while index < chars.len() {
+            let (start, _) = chars[index];
+            match chars.get(index + 1..index + 3) {
+                Some([('-', false), (end, _)]) => {
+                    if *end < start {
+                        return Err(error("range out of order"));
+                    }
+                    ranges.push((start, *end));
+                    index += 3;
+                }
+                _ => {
+                    ranges.push((start, start));
+                    index += 1;
+                }
+            }
+        }
+
+        Ok(CharSet::new(ranges))
+    }
+
+    /// Serialize the set in bracket syntax, escaping characters that would be ambiguous
+    pub fn serialize(&self) -> String {
+        let mut output = String::from("[");
+
+        for (start, end) in &self.ranges {
+            push_escaped(*start, &mut output);
+            if start != end {
+                if next_char(*start) != Some(*end) {
+                    output.push('-');
+                }
+                push_escaped(*end, &mut output);
+            }
+        }
+
+        output.push(']');
+        output
+    }
+}
+
+/// Get the character after `c`, skipping the surrogate gap
+pub(crate) fn next_char(c: char) -> Option<char> {
+    match c {
+        '\u{D7FF}' => Some('\u{E000}'),
+        _ => char::from_u32(c as u32 + 1),
+    }
+}
+
+/// Get the character before `c`, skipping the surrogate gap
+pub(crate) fn previous_char(c: char) -> Option<char> {
+    match c {
+        '\u{E000}' => Some('\u{D7FF}'),
+        _ => char::from_u32((c as u32).checked_sub(1)?),
+    }
+}
+
+/// Push a character in bracket syntax
+fn push_escaped(c: char, output: &mut String) {
+    if c.is_whitespace() || c.is_control() || c == ':' {
+        output.push_str(&format!("\\u{{{:x}}}", c as u32));
+    } else if matches!(c, '[' | ']' | '\\' | '-' | '^') {
+        output.push('\\');
+        output.push(c);
+    } else {
+        output.push(c);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn char_set_new() {
+        let set = CharSet::new([('x', 'z'), ('a', 'c'), ('b', 'f'), ('g', 'g'), ('q', 'p')]);
+
+        assert_eq!(set.ranges(), &[('a', 'g'), ('x', 'z')]);
+        assert!(set.contains('d'));
+        assert!(set.contains('z'));
+        assert!(!set.contains('h'));
+        assert!(CharSet::new([]).is_empty());
+    }
+
+    #[test]
+    fn char_set_parse_serialize() -> Result<(), ParseSerializeError> {
+        let set = CharSet::parse(r"[a-z_\-\u{20}]")?;
+
+        assert_eq!(
+            set.ranges(),
+            &[(' ', ' '), ('-', '-'), ('_', '_'), ('a', 'z')]
+        );
+        assert_eq!(set.serialize(), r"[\u{20}\-_a-z]");
+        assert_eq!(CharSet::parse(&set.serialize())?, set);
+        assert_eq!(CharSet::parse("[ab]")?.serialize(), "[ab]");
+
+        assert!(CharSet::parse("a-z").is_err());
+        assert!(CharSet::parse("[z-a]").is_err());
+        assert!(CharSet::parse(r"[\u{110000}]").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn char_set_algebra() {
+        let letters = CharSet::new([('a', 'z'), ('A', 'Z')]);
+        let hex = CharSet::new([('0', '9'), ('a', 'f'), ('A', 'F')]);
+
+        assert_eq!(
+            letters.intersection(&hex).ranges(),
+            &[('A', 'F'), ('a', 'f')]
+        );
+        assert_eq!(
+            letters.union(&hex).ranges(),
+            &[('0', '9'), ('A', 'Z'), ('a', 'z')]
+        );
+        assert_eq!(hex.difference(&letters).ranges(), &[('0', '9')]);
+        assert!(!letters.intersection(&CharSet::single('0')).is_satisfiable());
+
+        let complement = letters.complement();
+        assert!(complement.contains('\0'));
+        assert!(complement.contains('_'));
+        assert!(complement.contains(char::MAX));
+        assert!(!complement.contains('q'));
+        assert_eq!(complement.complement(), letters);
+        assert_eq!(CharSet::any().complement(), CharSet::default());
+        assert_eq!(CharSet::default().complement(), CharSet::any());
+    }
+}
@@ -0,0 +1,307 @@
+use crate::{SymbolMap, TransitionTable, STARTING_STATE_ID};
+use std::fmt::Display;
+use std::hash::Hash;
+
+/// A target language for [`TransitionTable::generate`]
+///
+/// Implement this to add a language without changing the crate. [`Go`], [`Java`], and [`Python`]
+/// are built in.
+pub trait CodegenBackend {
+    /// Generate source code for a recognizer named `name`
+    fn generate(&self, recognizer: &Recognizer, name: &str) -> String;
+}
+
+/// A Go package with an `<Name>Accepts` function
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Go;
+
+/// A Java class with a static `accepts` method
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Java;
+
+/// A Python class with an `accepts` class method
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Python;
+
+/// A table laid out for code generation, with states numbered by row index
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recognizer {
+    /// The row index of the starting state, if it exists
+    pub start: Option<usize>,
+
+    /// Whether each row is accepting
+    pub accepting: Vec<bool>,
+
+    /// The target row index of each row's transitions, with `None` for error transitions and
+    /// transitions to missing states
+    pub transitions: Vec<Vec<Option<usize>>>,
+
+    /// The label of every column, which is its index unless symbols were given
+    pub symbols: Vec<String>,
+}
+
+impl Recognizer {
+    /// Lay out a table, labeling its columns
+    fn new(table: &TransitionTable, symbols: Vec<String>) -> Self {
+        let sink = table.rows.len();
+        Recognizer {
+            start: table.index_of(STARTING_STATE_ID),
+            accepting: table.rows.iter().map(|row| row.accepting).collect(),
+            transitions: (0..table.rows.len())
+                .map(|index| {
+                    (0..table.width())
+                        .map(|symbol| Some(table.delta(index, symbol)).filter(|t| *t != sink))
+                        .collect()
+                })
+                .collect(),
+            symbols,
+        }
+    }
+
+    /// Get the number of columns
+    pub fn width(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Write the rows of transitions, one per line, with `missing` for error transitions
+    fn rows(&self, indent: &str, open: &str, close: &str, missing: &str) -> String {
+        self.transitions
+            .iter()
+            .map(|row| {
+                let targets = row
+                    .iter()
+                    .map(|target| target.map_or_else(|| missing.to_string(), |t| t.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{}{}{}{},\n", indent, open, targets, close)
+            })
+            .collect()
+    }
+
+    /// Write the accepting flags as a comma-separated list
+    fn accepting(&self, yes: &str, no: &str) -> String {
+        self.accepting
+            .iter()
+            .map(|accepting| if *accepting { yes } else { no })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl CodegenBackend for Go {
+    fn generate(&self, recognizer: &Recognizer, name: &str) -> String {
+        let private = lower_first(name);
+        format!(
+            "package {package}
+
+// {private}Start is the starting state, or -1 if there is none
+const {private}Start = {start}
+
+// {private}Accepting is whether each state is accepting
+var {private}Accepting = []bool{{{accepting}}}
+
+// {private}Transitions is the state reached from each state on each symbol, or -1
+var {private}Transitions = [][]int{{
+{rows}}}
+
+// {name}Accepts reports whether the {name} recognizer accepts the symbols
+func {name}Accepts(symbols []int) bool {{
+\tstate := {private}Start
+\tfor _, symbol := range symbols {{
+\t\tif state < 0 || symbol < 0 || symbol >= len({private}Transitions[state]) {{
+\t\t\treturn false
+\t\t}}
+\t\tstate = {private}Transitions[state][symbol]
+\t}}
+\treturn state >= 0 && {private}Accepting[state]
+}}
+",
+            package = name.to_lowercase(),
+            start = recognizer
+                .start
+                .map_or_else(|| "-1".to_string(), |s| s.to_string()),
+            accepting = recognizer.accepting("true", "false"),
+            rows = recognizer.rows("\t", "{", "}", "-1"),
+        )
+    }
+}
+
+impl CodegenBackend for Java {
+    fn generate(&self, recognizer: &Recognizer, name: &str) -> String {
+        format!(
+            "/** A table-driven recognizer generated from a transition table */
+public final class {name} {{
+    /** The starting state, or -1 if there is none */
+    private static final int START = {start};
+
+    /** Whether each state is accepting */
+    private static final boolean[] ACCEPTING = {{{accepting}}};
+
+    /** The state reached from each state on each symbol, or -1 */
+    private static final int[][] TRANSITIONS = {{
+{rows}    }};
+
+    private {name}() {{}}
+
+    /** Check whether the recognizer accepts the symbols */
+    public static boolean accepts(int[] symbols) {{
+        int state = START;
+        for (int symbol : symbols) {{
+            if (state < 0 || symbol < 0 || symbol >= TRANSITIONS[state].length) {{
+                return false;
+            }}
+            state = TRANSITIONS[state][symbol];
+        }}
+        return state >= 0 && ACCEPTING[state];
+    }}
+}}
+",
+            start = recognizer
+                .start
+                .map_or_else(|| "-1".to_string(), |s| s.to_string()),
+            accepting = recognizer.accepting("true", "false"),
+            rows = recognizer.rows("        ", "{", "}", "-1"),
+        )
+    }
+}
+
+impl CodegenBackend for Python {
+    fn generate(&self, recognizer: &Recognizer, name: &str) -> String {
+        // One-element tuples need a trailing comma
+        let mut accepting = recognizer.accepting("True", "False");
+        if recognizer.accepting.len() == 1 {
+            accepting.push(',');
+        }
+        let close_row = if recognizer.width() == 1 { ",)" } else { ")" };
+
+        format!(
+            "class {name}:
+    \"\"\"A table-driven recognizer generated from a transition table\"\"\"
+
+    # The starting state, or None if there is none
+    START = {start}
+
+    # Whether each state is accepting
+    ACCEPTING = ({accepting})
+
+    # The state reached from each state on each symbol, or None
+    TRANSITIONS = (
+{rows}    )
+
+    @classmethod
+    def accepts(cls, symbols):
+        \"\"\"Check whether the recognizer accepts the symbols\"\"\"
+        state = cls.START
+        for symbol in symbols:
+            if state is None or not 0 <= symbol < len(cls.TRANSITIONS[state]):
+                return False
+            state = cls.TRANSITIONS[state][symbol]
+        return state is not None and cls.ACCEPTING[state]
+",
+            start = recognizer
+                .start
+                .map_or_else(|| "None".to_string(), |s| s.to_string()),
+            rows = recognizer.rows("        ", "(", close_row, "None"),
+        )
+    }
+}
+
+/// Lowercase the first character of an identifier
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+impl TransitionTable {
+    /// Generate source code for a table-driven recognizer named `name` in another language
+    ///
+    /// The recognizer checks whether a sequence of column indices is accepted, like
+    /// [`crate::Automaton::accepts`]. States are numbered by row index, and error transitions,
+    /// transitions to missing states, and out-of-range columns reject. `name` should be a valid
+    /// type name in the target language, such as `Turnstile`.
+    pub fn generate(&self, backend: &impl CodegenBackend, name: &str) -> String {
+        let symbols = (0..self.width()).map(|column| column.to_string()).collect();
+        backend.generate(&Recognizer::new(self, symbols), name)
+    }
+
+    /// Generate source code like [`TransitionTable::generate`], passing the backend the columns'
+    /// symbols
+    ///
+    /// Columns without a symbol are labeled with their index.
+    pub fn generate_with_symbols<S: Eq + Hash + Display>(
+        &self,
+        backend: &impl CodegenBackend,
+        name: &str,
+        symbols: &SymbolMap<S>,
+    ) -> String {
+        let mut labels = symbols.labels();
+        labels.extend((labels.len()..self.width()).map(|column| column.to_string()));
+        backend.generate(&Recognizer::new(self, labels), name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generate() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 3\n").unwrap();
+
+        let go = table.generate(&Go, "Turnstile");
+        assert!(go.starts_with("package turnstile\n"));
+        assert!(go.contains("var turnstileTransitions = [][]int{\n\t{1, -1},\n\t{1, -1},\n}\n"));
+        assert!(go.contains("func TurnstileAccepts(symbols []int) bool {"));
+
+        let java = table.generate(&Java, "Turnstile");
+        assert!(java.contains("public final class Turnstile {"));
+        assert!(java.contains("ACCEPTING = {false, true};"));
+        assert!(java.contains("        {1, -1},\n        {1, -1},\n    };"));
+
+        let python = table.generate(&Python, "Turnstile");
+        assert!(python.contains("    START = 0\n"));
+        assert!(python.contains("        (1, None),\n"));
+        assert!(python.contains("    ACCEPTING = (False, True)\n"));
+    }
+
+    #[test]
+    fn generate_without_start() {
+        let table = TransitionTable::parse("+ 1 1\n").unwrap();
+        let python = table.generate(&Python, "Empty");
+        assert!(python.contains("    START = None\n"));
+        assert!(python.contains("    ACCEPTING = (True,)\n"));
+        assert!(table
+            .generate(&Go, "Empty")
+            .contains("const emptyStart = -1\n"));
+    }
+
+    #[test]
+    fn custom_backend() {
+        /// Lists every transition as `state --symbol--> target`
+        struct Listing;
+
+        impl CodegenBackend for Listing {
+            fn generate(&self, recognizer: &Recognizer, name: &str) -> String {
+                let mut output = format!("{}\n", name);
+                for (state, row) in recognizer.transitions.iter().enumerate() {
+                    for (symbol, target) in recognizer.symbols.iter().zip(row) {
+                        if let Some(target) = target {
+                            output.push_str(&format!("{} --{}--> {}\n", state, symbol, target));
+                        }
+                    }
+                }
+                output
+            }
+        }
+
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n").unwrap();
+        let symbols: SymbolMap<char> = ['a'].into_iter().collect();
+        assert_eq!(
+            table.generate_with_symbols(&Listing, "ab", &symbols),
+            "ab\n0 --a--> 1\n1 --a--> 1\n1 --1--> 0\n"
+        );
+    }
+}
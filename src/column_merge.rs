@@ -0,0 +1,127 @@
+use crate::{Alphabet, CharSet, TransitionTable, TransitionTableRow};
+use std::collections::HashMap;
+
+impl TransitionTable {
+    /// Merge columns that behave the same in every state
+    ///
+    /// Two columns are merged when every row has the same transition on both, so the table keeps
+    /// its language over the merged alphabet. Also returns the old columns in each new column,
+    /// ordered by their first column, which [`Alphabet::merge_columns`] applies to an alphabet.
+    pub fn merge_equivalent_columns(&self) -> (TransitionTable, Vec<Vec<usize>>) {
+        let width = self.width();
+        let column = |symbol: usize| -> Vec<Option<usize>> {
+            self.rows
+                .iter()
+                .map(|row| row.transitions.get(symbol).copied().flatten())
+                .collect()
+        };
+
+        let mut group_of = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for symbol in 0..width {
+            let group = *group_of.entry(column(symbol)).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[group].push(symbol);
+        }
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| TransitionTableRow {
+                accepting: row.accepting,
+                id: row.id,
+                transitions: groups
+                    .iter()
+                    .map(|group| row.transitions.get(group[0]).copied().flatten())
+                    .collect(),
+                doc: row.doc.clone(),
+                label: row.label.clone(),
+            })
+            .collect();
+
+        (
+            TransitionTable {
+                rows,
+                alphabet: None,
+                metadata: Default::default(),
+                comment: None,
+            },
+            groups,
+        )
+    }
+}
+
+impl Alphabet {
+    /// Merge columns into groups, so new column `i` takes the characters of the old columns in
+    /// `groups[i]`
+    ///
+    /// The new column holding the default column, if any, becomes the default column, and new
+    /// columns are described by the descriptions of their old columns, joined by commas.
+    pub fn merge_columns(&self, groups: &[Vec<usize>]) -> Alphabet {
+        let classes = groups
+            .iter()
+            .map(|group| {
+                group
+                    .iter()
+                    .filter_map(|column| self.classes().get(*column))
+                    .fold(CharSet::default(), |merged, class| merged.union(class))
+            })
+            .collect();
+        let default = self
+            .default_column()
+            .and_then(|default| groups.iter().position(|group| group.contains(&default)));
+
+        let merged = Alphabet::with_default_at(classes, default);
+        groups
+            .iter()
+            .enumerate()
+            .fold(merged, |merged, (column, group)| {
+                let descriptions: Vec<&str> = group
+                    .iter()
+                    .filter_map(|column| self.description(*column))
+                    .collect();
+                if descriptions.is_empty() {
+                    merged
+                } else {
+                    merged.with_description(column, &descriptions.join(", "))
+                }
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn merge_equivalent_columns() -> Result<(), ParseSerializeError> {
+        // A column per character, where only the digits and the letters are told apart
+        let table = TransitionTable::parse("- 0 1 1 2 2\n+ 1 1 1 E E\n+ 2 E E 2 2\n")?;
+        let alphabet = Alphabet::from_chars("01ab".chars());
+
+        let (merged, groups) = table.merge_equivalent_columns();
+        assert_eq!(merged.serialize(), "- 0 1 2\n+ 1 1 E\n+ 2 E 2\n");
+        assert_eq!(groups, vec![vec![0, 1], vec![2, 3]]);
+
+        let merged_alphabet = alphabet.merge_columns(&groups);
+        assert_eq!(merged_alphabet.classify('1'), Some(0));
+        assert_eq!(merged_alphabet.classify('b'), Some(1));
+        for word in ["", "0110", "ab", "a0", "1"] {
+            assert_eq!(
+                merged.accepts_str(&merged_alphabet, word),
+                table.accepts_str(&alphabet, word)
+            );
+        }
+
+        // The default column survives merging
+        let with_default = Alphabet::with_default(vec![CharSet::single('x'), CharSet::single('y')]);
+        let merged_default = with_default.merge_columns(&[vec![0], vec![1, 2]]);
+        assert_eq!(merged_default.default_column(), Some(1));
+        assert_eq!(merged_default.classify('z'), Some(1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use crate::{Alphabet, TransitionTable};
+
+/// The error returned by a [`recognize_dfa`] parser when the input doesn't start with a match
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("input doesn't start with a match of the table")]
+pub struct RecognizeError<'a> {
+    /// The input the parser was given
+    pub input: &'a str,
+}
+
+/// Create a parser recognizing the longest prefix of its input accepted by a table
+///
+/// The parser returns `(remaining, matched)` like a parser-combinator library's result, so it can be
+/// used as a token parser in a combinator grammar by mapping the error into the library's type.
+pub fn recognize_dfa<'t>(
+    table: &'t TransitionTable,
+    alphabet: &'t Alphabet,
+) -> impl Fn(&str) -> Result<(&str, &str), RecognizeError<'_>> + 't {
+    move |input| match table.longest_match_str(alphabet, input, 0) {
+        Some(end) => Ok((&input[end..], &input[..end])),
+        None => Err(RecognizeError { input }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharSet, ParseSerializeError};
+
+    #[test]
+    fn recognize_dfa() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1\n+ 1 1\n")?;
+        let alphabet = Alphabet::new(vec![CharSet::new([('0', '9')])]);
+        let digits = super::recognize_dfa(&table, &alphabet);
+
+        assert_eq!(digits("42 apples"), Ok((" apples", "42")));
+        assert_eq!(digits("7"), Ok(("", "7")));
+        assert_eq!(digits("x7"), Err(RecognizeError { input: "x7" }));
+
+        // Parsers compose like any other combinator
+        let pair = |input| {
+            let (input, first) = digits(input)?;
+            let input = input.strip_prefix(',').ok_or(RecognizeError { input })?;
+            let (input, second) = digits(input)?;
+            Ok::<_, RecognizeError>((input, (first, second)))
+        };
+        assert_eq!(pair("12,345;"), Ok((";", ("12", "345"))));
+
+        Ok(())
+    }
+}
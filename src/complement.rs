@@ -0,0 +1,98 @@
+use crate::{TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+
+impl TransitionTable {
+    /// Make a table accepting exactly the inputs this table rejects
+    ///
+    /// Error transitions, transitions to missing states, and columns past the end of a row all
+    /// reject, so flipping accepting states alone would still reject the inputs that use them. The
+    /// table is completed first with an explicit sink, which gets the ID after the largest one and
+    /// becomes accepting like any other rejecting state. The sink is only added if some transition
+    /// needs it, and the other states keep their IDs. A table without a starting state rejects
+    /// everything, so its complement is a single state accepting everything.
+    pub fn complement(&self) -> TransitionTable {
+        let width = self.width();
+        if self.index_of(STARTING_STATE_ID).is_none() {
+            return TransitionTable {
+                rows: vec![TransitionTableRow {
+                    accepting: true,
+                    id: STARTING_STATE_ID,
+                    transitions: vec![Some(STARTING_STATE_ID); width],
+                    doc: None,
+                    label: None,
+                }],
+                alphabet: self.alphabet.clone(),
+                metadata: self.metadata.clone(),
+                comment: self.comment.clone(),
+            };
+        }
+
+        let sink = self.rows.len();
+        let sink_id = self.rows.last().map_or(STARTING_STATE_ID, |row| row.id + 1);
+        let id_of = |index: usize| {
+            if index == sink {
+                sink_id
+            } else {
+                self.rows[index].id
+            }
+        };
+
+        let mut needs_sink = false;
+        let mut rows: Vec<TransitionTableRow> = (0..sink)
+            .map(|index| TransitionTableRow {
+                accepting: !self.rows[index].accepting,
+                id: self.rows[index].id,
+                transitions: (0..width)
+                    .map(|symbol| {
+                        let target = self.delta(index, symbol);
+                        needs_sink |= target == sink;
+                        Some(id_of(target))
+                    })
+                    .collect(),
+                doc: self.rows[index].doc.clone(),
+                label: self.rows[index].label.clone(),
+            })
+            .collect();
+        if needs_sink {
+            rows.push(TransitionTableRow {
+                accepting: true,
+                id: sink_id,
+                transitions: vec![Some(sink_id); width],
+                doc: None,
+                label: None,
+            });
+        }
+
+        TransitionTable {
+            rows,
+            alphabet: self.alphabet.clone(),
+            metadata: self.metadata.clone(),
+            comment: self.comment.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TransitionTable;
+
+    #[test]
+    fn complement() {
+        // Inputs over a and b that are exactly "ab"
+        let table = TransitionTable::parse("- 0 1 E\n- 1 E 2\n+ 2 E E\n").unwrap();
+        let complement = table.complement();
+        assert_eq!(
+            complement.to_string(),
+            "+ 0 1 3\n+ 1 3 2\n- 2 3 3\n+ 3 3 3\n"
+        );
+        for input in [&[][..], &[0], &[0, 1], &[0, 1, 1], &[1, 0], &[0, 0]] {
+            assert_ne!(table.accepts(input), complement.accepts(input));
+        }
+
+        // A complete table doesn't need a sink
+        let complete = TransitionTable::parse("- 0 1\n+ 1 0\n").unwrap();
+        assert_eq!(complete.complement().to_string(), "+ 0 1\n- 1 0\n");
+
+        let empty = TransitionTable::parse("- 1 1\n").unwrap();
+        assert!(empty.complement().accepts([0, 0]));
+    }
+}
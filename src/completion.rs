@@ -0,0 +1,175 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+use std::collections::HashSet;
+
+/// What remains of a table's language after a prefix, from [`TransitionTable::residual`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Residual {
+    /// The state the prefix reaches in the original table
+    pub state: usize,
+
+    /// The part of the table reachable from `state`, renumbered so `state` is the starting state
+    ///
+    /// It accepts exactly the strings that complete the prefix.
+    pub table: TransitionTable,
+}
+
+impl TransitionTable {
+    /// Run a prefix of columns, returning the state reached and the table of its continuations
+    ///
+    /// Returns `None` if the prefix takes an error transition or the starting state does not exist.
+    pub fn residual(&self, prefix: &[usize]) -> Option<Residual> {
+        let state = self.run_prefix(prefix)?;
+        Some(Residual {
+            state,
+            table: self.sub_automaton(state)?,
+        })
+    }
+
+    /// Check whether a prefix of columns can still be extended into an accepted string
+    ///
+    /// This includes the prefix being accepted itself.
+    pub fn accepts_extension(&self, prefix: &[usize]) -> bool {
+        self.run_prefix(prefix)
+            .and_then(|state| self.index_of(state))
+            .is_some_and(|index| self.live_states()[index])
+    }
+
+    /// Get the columns with a transition from the state a prefix of columns reaches
+    ///
+    /// Error transitions and transitions to states that do not exist are left out. The result is
+    /// empty if the prefix itself takes an error transition, so the columns are exactly those that
+    /// can be added to the prefix without an error.
+    pub fn valid_next_symbols(&self, prefix: &[usize]) -> Vec<usize> {
+        let Some(row) = self.run_prefix(prefix).and_then(|state| self.row(state)) else {
+            return Vec::new();
+        };
+
+        (0..row.transitions.len())
+            .filter(|symbol| self.next_state(row.id, *symbol).is_some())
+            .collect()
+    }
+
+    /// Get the longest sequence of columns that every accepted string starts with
+    ///
+    /// The prefix is empty when the language is empty.
+    pub fn common_prefix(&self) -> Vec<usize> {
+        let live = self.live_states();
+        let mut prefix = Vec::new();
+        let Some(mut index) = self
+            .index_of(STARTING_STATE_ID)
+            .filter(|index| live[*index])
+        else {
+            return prefix;
+        };
+
+        // Follow the only live transition until a state accepts, branches, or repeats
+        let mut visited = HashSet::new();
+        while visited.insert(index) && !self.rows[index].accepting {
+            let mut live_transitions =
+                self.rows[index]
+                    .transitions
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(symbol, target)| {
+                        let target = self.index_of((*target)?)?;
+                        live[target].then_some((symbol, target))
+                    });
+
+            match (live_transitions.next(), live_transitions.next()) {
+                (Some((symbol, target)), None) => {
+                    prefix.push(symbol);
+                    index = target;
+                }
+                _ => break,
+            }
+        }
+
+        prefix
+    }
+
+    /// Run a sequence of columns from the starting state, returning the state ID reached
+    pub(crate) fn run_prefix(&self, prefix: &[usize]) -> Option<usize> {
+        let start = self.row(STARTING_STATE_ID)?.id;
+        prefix
+            .iter()
+            .try_fold(start, |state, symbol| self.next_state(state, *symbol))
+    }
+
+    /// Find which rows, by index, can reach an accepting state
+    pub(crate) fn live_states(&self) -> Vec<bool> {
+        // The rows entering each row
+        let mut inverse = vec![Vec::new(); self.rows.len()];
+        for (index, row) in self.rows.iter().enumerate() {
+            for target in row.transitions.iter().flatten() {
+                if let Some(target) = self.index_of(*target) {
+                    inverse[target].push(index);
+                }
+            }
+        }
+
+        let mut live: Vec<bool> = self.rows.iter().map(|row| row.accepting).collect();
+        let mut pending: Vec<usize> = (0..self.rows.len()).filter(|index| live[*index]).collect();
+        while let Some(index) = pending.pop() {
+            for source in &inverse[index] {
+                if !live[*source] {
+                    live[*source] = true;
+                    pending.push(*source);
+                }
+            }
+        }
+
+        live
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    /// Strings starting with 0 1, then any number of 0s, ending in 1, with a dead branch
+    const TABLE: &str = "- 0 1 4\n- 1 E 2\n- 2 2 3\n+ 3 E E\n- 4 4 4\n";
+
+    #[test]
+    fn residual() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(TABLE)?;
+
+        let residual = table.residual(&[0, 1]).unwrap();
+        assert_eq!(residual.state, 2);
+        assert_eq!(residual.table.serialize(), "- 0 0 1\n+ 1 E E\n");
+        assert!(table.residual(&[0, 0]).is_none());
+
+        assert!(table.accepts_extension(&[0]));
+        assert!(table.accepts_extension(&[0, 1, 1]));
+        assert!(!table.accepts_extension(&[1]));
+        assert!(!table.accepts_extension(&[0, 1, 1, 0]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn valid_next_symbols() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(TABLE)?;
+
+        assert_eq!(table.valid_next_symbols(&[]), vec![0, 1]);
+        assert_eq!(table.valid_next_symbols(&[0]), vec![1]);
+        assert_eq!(table.valid_next_symbols(&[0, 1, 1]), Vec::<usize>::new());
+        assert_eq!(table.valid_next_symbols(&[0, 0]), Vec::<usize>::new());
+
+        Ok(())
+    }
+
+    #[test]
+    fn common_prefix() -> Result<(), ParseSerializeError> {
+        assert_eq!(TransitionTable::parse(TABLE)?.common_prefix(), vec![0, 1]);
+        assert_eq!(
+            TransitionTable::parse("- 0 1 E\n- 1 0 2\n+ 2 E E\n")?.common_prefix(),
+            vec![0]
+        );
+        assert!(TransitionTable::parse("- 0 0 E\n")?
+            .common_prefix()
+            .is_empty());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use crate::metadata::fnv1a;
+use crate::{Sampler, TransitionTable, STARTING_STATE_ID};
+use std::collections::{BTreeSet, VecDeque};
+use std::path::Path;
+
+/// Options for [`TransitionTable::export_corpus_with`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CorpusOptions {
+    /// The seed of the sampler choosing accepted inputs and near misses
+    pub seed: u64,
+
+    /// How many accepted inputs to sample
+    pub accepted: usize,
+
+    /// How many near misses to sample
+    pub near_misses: usize,
+
+    /// The longest sampled input, in columns
+    pub max_len: usize,
+}
+
+impl Default for CorpusOptions {
+    fn default() -> Self {
+        CorpusOptions {
+            seed: 0,
+            accepted: 64,
+            near_misses: 64,
+            max_len: 32,
+        }
+    }
+}
+
+/// How many files of each kind [`TransitionTable::export_corpus`] wrote, after removing repeats
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CorpusReport {
+    /// Sampled inputs the table accepts
+    pub accepted: usize,
+
+    /// Accepted inputs with one column edited, at the boundary of the language
+    pub near_misses: usize,
+
+    /// Shortest inputs taking each reachable transition
+    pub coverage: usize,
+}
+
+/// Errors that can occur when exporting a corpus
+#[derive(Debug, thiserror::Error)]
+pub enum CorpusError {
+    /// Writing a file failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A column has no bytes to write it as
+    #[error("column {0} can't be written as bytes")]
+    Unencodable(usize),
+}
+
+impl TransitionTable {
+    /// Write a seed corpus for a fuzzer like libFuzzer or cargo-fuzz to a directory, with the
+    /// default [`CorpusOptions`]
+    pub fn export_corpus(&self, dir: impl AsRef<Path>) -> Result<CorpusReport, CorpusError> {
+        self.export_corpus_with(dir, &CorpusOptions::default())
+    }
+
+    /// Write a seed corpus for a fuzzer like libFuzzer or cargo-fuzz to a directory
+    ///
+    /// The corpus holds a shortest input for every reachable transition, sampled accepted inputs,
+    /// and near misses, one raw input per file, so the fuzzer starts from inputs that exercise
+    /// every part of the table. Files are named by their kind and a hash of their contents, and
+    /// an input repeating an earlier one, in that order of kinds, isn't written again. The
+    /// directory is created if needed.
+    ///
+    /// If the table has an [`TransitionTable::alphabet`], each column is written as the UTF-8 of
+    /// its smallest character. Otherwise each column is written as the byte of its index,
+    /// which fails for tables with more than 256 columns.
+    pub fn export_corpus_with(
+        &self,
+        dir: impl AsRef<Path>,
+        options: &CorpusOptions,
+    ) -> Result<CorpusReport, CorpusError> {
+        let encodings = self.column_encodings()?;
+        let encode = |input: &[usize]| -> Vec<u8> {
+            input
+                .iter()
+                .flat_map(|column| encodings[*column].iter().copied())
+                .collect()
+        };
+
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let mut written = BTreeSet::new();
+        let mut write = |kind: &str, input: &[usize]| -> std::io::Result<bool> {
+            let bytes = encode(input);
+            if !written.insert(bytes.clone()) {
+                return Ok(false);
+            }
+            let name = format!("{}-{:016x}", kind, fnv1a(&bytes));
+            std::fs::write(dir.join(name), bytes)?;
+            Ok(true)
+        };
+
+        let mut report = CorpusReport::default();
+        for input in self.transition_cover() {
+            report.coverage += write("coverage", &input)? as usize;
+        }
+        let mut sampler = Sampler::new(self, options.seed);
+        for _ in 0..options.accepted {
+            let Some(input) = sampler.sample(options.max_len) else {
+                break;
+            };
+            report.accepted += write("accepted", &input)? as usize;
+        }
+        for input in sampler.sample_near_misses(options.near_misses, options.max_len) {
+            report.near_misses += write("near-miss", &input)? as usize;
+        }
+
+        Ok(report)
+    }
+
+    /// Get the bytes to write each column as in a corpus
+    fn column_encodings(&self) -> Result<Vec<Vec<u8>>, CorpusError> {
+        match &self.alphabet {
+            Some(alphabet) => (0..self.width())
+                .map(|column| {
+                    let c = alphabet
+                        .classes()
+                        .get(column)
+                        .and_then(|class| class.first())
+                        .ok_or(CorpusError::Unencodable(column))?;
+                    Ok(c.to_string().into_bytes())
+                })
+                .collect(),
+            None => (0..self.width())
+                .map(|column| {
+                    u8::try_from(column)
+                        .map(|byte| vec![byte])
+                        .map_err(|_| CorpusError::Unencodable(column))
+                })
+                .collect(),
+        }
+    }
+
+    /// Get a shortest input from the starting state taking each reachable transition, in
+    /// breadth-first order
+    fn transition_cover(&self) -> Vec<Vec<usize>> {
+        let Some(start) = self.index_of(STARTING_STATE_ID) else {
+            return Vec::new();
+        };
+
+        let mut access: Vec<Option<Vec<usize>>> = vec![None; self.rows.len()];
+        access[start] = Some(Vec::new());
+        let mut queue = VecDeque::from([start]);
+        let mut cover = Vec::new();
+        while let Some(index) = queue.pop_front() {
+            let prefix = access[index].clone().unwrap();
+            for (symbol, target) in self.rows[index].transitions.iter().enumerate() {
+                let Some(target) = target.and_then(|target| self.index_of(target)) else {
+                    continue;
+                };
+                let mut input = prefix.clone();
+                input.push(symbol);
+                if access[target].is_none() {
+                    access[target] = Some(input.clone());
+                    queue.push_back(target);
+                }
+                cover.push(input);
+            }
+        }
+        cover
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn export_corpus() -> Result<(), Box<dyn std::error::Error>> {
+        let table = TransitionTable::parse("alphabet: [a] [b]\n- 0 1 E\n+ 1 1 0\n")?;
+        let dir = std::env::temp_dir().join(format!("corpus-{}", std::process::id()));
+        let report = table.export_corpus(&dir)?;
+        assert_eq!(report.coverage, 3);
+        assert!(report.accepted > 0 && report.near_misses > 0);
+
+        let mut files = 0;
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let contents = String::from_utf8(std::fs::read(entry.path())?)?;
+            let name = entry.file_name().into_string().unwrap();
+            if name.starts_with("accepted-") {
+                assert!(table.accepts_chars(&contents), "{}", contents);
+            } else if name.starts_with("near-miss-") {
+                assert!(!table.accepts_chars(&contents), "{}", contents);
+            }
+            files += 1;
+        }
+        assert_eq!(
+            files,
+            report.accepted + report.near_misses + report.coverage
+        );
+        std::fs::remove_dir_all(&dir)?;
+
+        // Without an alphabet, columns are written as their index
+        let wide = TransitionTable::parse(&format!("+ 0{}\n", " E".repeat(300)))?;
+        let wide_dir = dir.with_extension("wide");
+        assert!(matches!(
+            wide.export_corpus(&wide_dir),
+            Err(CorpusError::Unencodable(256))
+        ));
+        assert!(!wide_dir.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_cover() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 2\n- 1 E 2\n+ 2 2 E\n- 3 3 3\n")?;
+        assert_eq!(
+            table.transition_cover(),
+            [vec![0], vec![1], vec![0, 1], vec![1, 0]]
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,327 @@
+use crate::{Trace, TraceStep, TransitionTable};
+
+/// A condition that pauses [`RunDebugger::resume`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Pause after a step that enters the given state
+    EnterState(usize),
+
+    /// Pause after a step that consumes one of the given columns
+    ///
+    /// If `state` is set, only steps leaving that state count.
+    Consume {
+        /// The state the step must leave, or `None` for any state
+        state: Option<usize>,
+
+        /// The columns that trigger the breakpoint, such as every column of a symbol class
+        symbols: Vec<usize>,
+    },
+
+    /// Pause after a step that takes an error transition
+    Error,
+}
+
+impl Breakpoint {
+    /// Check whether a step triggers the breakpoint
+    pub fn matches(&self, step: &TraceStep) -> bool {
+        match self {
+            Breakpoint::EnterState(state) => step.to == Some(*state),
+            Breakpoint::Consume { state, symbols } => {
+                state.is_none_or(|state| state == step.from) && symbols.contains(&step.symbol)
+            }
+            Breakpoint::Error => step.to.is_none(),
+        }
+    }
+}
+
+/// A value of the run that pauses [`RunDebugger::resume`] when a step changes it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Watchpoint {
+    /// Whether the current state is accepting
+    Accepting,
+
+    /// Whether the current state is one of the given states, such as every state of a token
+    InStates(Vec<usize>),
+}
+
+impl Watchpoint {
+    /// Get the watched value in a state of `table`, or after an error transition for `None`
+    pub fn value(&self, table: &TransitionTable, state: Option<usize>) -> bool {
+        match self {
+            Watchpoint::Accepting => {
+                state.is_some_and(|state| table.row(state).is_some_and(|row| row.accepting))
+            }
+            Watchpoint::InStates(states) => state.is_some_and(|state| states.contains(&state)),
+        }
+    }
+
+    /// Check whether a step of a run of `table` changes the watched value
+    pub fn changed(&self, table: &TransitionTable, step: &TraceStep) -> bool {
+        self.value(table, Some(step.from)) != self.value(table, step.to)
+    }
+}
+
+/// Why [`RunDebugger::resume`] paused
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Pause {
+    /// A step triggered the breakpoint with the given index
+    Breakpoint(usize),
+
+    /// A step changed the value of the watchpoint with the given index
+    Watchpoint(usize),
+}
+
+/// A recorded run that can be stepped through in both directions
+///
+/// The whole run is traced up front, so moving around in it never re-runs the table.
+#[derive(Clone, Debug)]
+pub struct RunDebugger<'a> {
+    /// The table being debugged
+    table: &'a TransitionTable,
+
+    /// The input of the run
+    input: Vec<usize>,
+
+    /// The recorded run
+    trace: Trace,
+
+    /// The number of steps taken so far
+    position: usize,
+
+    /// The breakpoints checked by `resume`
+    breakpoints: Vec<Breakpoint>,
+
+    /// The watchpoints checked by `resume`
+    watchpoints: Vec<Watchpoint>,
+}
+
+impl<'a> RunDebugger<'a> {
+    /// Record a run of `table` on `input`, positioned before the first step
+    pub fn new(table: &'a TransitionTable, input: &[usize]) -> Self {
+        RunDebugger {
+            table,
+            input: input.to_vec(),
+            trace: table.trace(input),
+            position: 0,
+            breakpoints: Vec::new(),
+            watchpoints: Vec::new(),
+        }
+    }
+
+    /// Get the recorded run
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    /// Get the input of the run
+    pub fn input(&self) -> &[usize] {
+        &self.input
+    }
+
+    /// Get the number of steps taken so far
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Get the current state, or `None` if the last step was an error transition
+    pub fn state(&self) -> Option<usize> {
+        self.trace.state_at(self.position)
+    }
+
+    /// Take the next recorded step, returning it or `None` at the end of the run
+    pub fn step_forward(&mut self) -> Option<&TraceStep> {
+        let step = self.trace.steps.get(self.position)?;
+        self.position += 1;
+        Some(step)
+    }
+
+    /// Undo the last step, returning it or `None` at the start of the run
+    pub fn step_back(&mut self) -> Option<&TraceStep> {
+        self.position = self.position.checked_sub(1)?;
+        Some(&self.trace.steps[self.position])
+    }
+
+    /// Jump to the given number of steps taken
+    ///
+    /// Returns `false` without moving if the run has fewer steps than `position`.
+    pub fn jump_to(&mut self, position: usize) -> bool {
+        if position > self.trace.steps.len() {
+            return false;
+        }
+
+        self.position = position;
+        true
+    }
+
+    /// Add a breakpoint, returning its index
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    /// Get the breakpoints, in the order they were added
+    pub fn breakpoints(&self) -> &[Breakpoint] {
+        &self.breakpoints
+    }
+
+    /// Remove all breakpoints
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Add a watchpoint, returning its index
+    pub fn add_watchpoint(&mut self, watchpoint: Watchpoint) -> usize {
+        self.watchpoints.push(watchpoint);
+        self.watchpoints.len() - 1
+    }
+
+    /// Get the watchpoints, in the order they were added
+    pub fn watchpoints(&self) -> &[Watchpoint] {
+        &self.watchpoints
+    }
+
+    /// Remove all watchpoints
+    pub fn clear_watchpoints(&mut self) {
+        self.watchpoints.clear();
+    }
+
+    /// Step forward until a step triggers a breakpoint or changes a watchpoint, or the run ends
+    ///
+    /// Returns the first breakpoint triggered, or else the first watchpoint changed, or `None` if
+    /// the run ended first.
+    pub fn resume(&mut self) -> Option<Pause> {
+        self.resume_with(|_, _| {})
+    }
+
+    /// Like [`RunDebugger::resume`], calling `on_break` with each triggered breakpoint and changed
+    /// watchpoint and the step that caused it before pausing
+    pub fn resume_with(&mut self, mut on_break: impl FnMut(Pause, &TraceStep)) -> Option<Pause> {
+        while let Some(step) = self.step_forward().copied() {
+            let breakpoints = self
+                .breakpoints
+                .iter()
+                .enumerate()
+                .filter(|(_, breakpoint)| breakpoint.matches(&step))
+                .map(|(index, _)| Pause::Breakpoint(index));
+            let watchpoints = self
+                .watchpoints
+                .iter()
+                .enumerate()
+                .filter(|(_, watchpoint)| watchpoint.changed(self.table, &step))
+                .map(|(index, _)| Pause::Watchpoint(index));
+            let triggered = breakpoints.chain(watchpoints).collect::<Vec<_>>();
+
+            for pause in &triggered {
+                on_break(*pause, &step);
+            }
+            if let Some(pause) = triggered.first() {
+                return Some(*pause);
+            }
+        }
+
+        None
+    }
+
+    /// Get the state that the given column would enter from the current state
+    ///
+    /// Returns `None` if the column would take an error transition, or if the run has already
+    /// failed.
+    pub fn what_if(&self, symbol: usize) -> Option<usize> {
+        self.table.next_state(self.state()?, symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn run_debugger() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 0 1\n")?;
+        let mut debugger = RunDebugger::new(&table, &[0, 1, 0, 1]);
+
+        assert_eq!(debugger.state(), Some(0));
+        assert!(debugger.step_back().is_none());
+        assert_eq!(debugger.step_forward().unwrap().to, Some(1));
+        assert_eq!(debugger.what_if(0), Some(0));
+
+        assert!(debugger.jump_to(4));
+        assert_eq!(debugger.state(), None);
+        assert!(debugger.step_forward().is_none());
+        assert_eq!(debugger.what_if(0), None);
+        assert!(!debugger.jump_to(5));
+
+        assert_eq!(debugger.step_back().unwrap().from, 0);
+        assert_eq!(debugger.state(), Some(0));
+        assert_eq!(debugger.position(), 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn run_debugger_breakpoints() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 2 E\n+ 1 1 2 0\n- 2 1 2 E\n")?;
+        let mut debugger = RunDebugger::new(&table, &[1, 0, 0, 2, 1, 2]);
+
+        let enter = debugger.add_breakpoint(Breakpoint::EnterState(0));
+        let consume = debugger.add_breakpoint(Breakpoint::Consume {
+            state: Some(2),
+            symbols: vec![0, 1],
+        });
+        debugger.add_breakpoint(Breakpoint::Error);
+
+        assert_eq!(debugger.resume(), Some(Pause::Breakpoint(consume)));
+        assert_eq!(debugger.position(), 2);
+        assert_eq!(debugger.resume(), Some(Pause::Breakpoint(enter)));
+        assert_eq!(debugger.position(), 4);
+
+        let mut hits = Vec::new();
+        assert_eq!(
+            debugger.resume_with(|index, step| hits.push((index, *step))),
+            Some(Pause::Breakpoint(2))
+        );
+        assert_eq!(
+            hits,
+            vec![(
+                Pause::Breakpoint(2),
+                TraceStep {
+                    from: 2,
+                    symbol: 2,
+                    to: None
+                }
+            )]
+        );
+        assert_eq!(debugger.resume(), None);
+
+        Ok(())
+    }
+    #[test]
+    fn run_debugger_watchpoints() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 2 E\n+ 1 1 2 0\n- 2 1 2 E\n")?;
+        let mut debugger = RunDebugger::new(&table, &[1, 0, 0, 2, 1, 2]);
+
+        let accepting = debugger.add_watchpoint(Watchpoint::Accepting);
+        let in_two = debugger.add_watchpoint(Watchpoint::InStates(vec![2]));
+        debugger.add_breakpoint(Breakpoint::EnterState(0));
+
+        // Entering state 2 from 0 leaves acceptance unchanged
+        assert_eq!(debugger.resume(), Some(Pause::Watchpoint(in_two)));
+        assert_eq!(debugger.position(), 1);
+
+        // Leaving state 2 for the accepting state 1 changes both
+        let mut hits = Vec::new();
+        debugger.resume_with(|pause, _| hits.push(pause));
+        assert_eq!(
+            hits,
+            vec![Pause::Watchpoint(accepting), Pause::Watchpoint(in_two)]
+        );
+        assert_eq!(debugger.position(), 2);
+
+        // Breakpoints come before watchpoints
+        assert_eq!(debugger.resume(), Some(Pause::Breakpoint(0)));
+        assert_eq!(debugger.position(), 4);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{Renumbering, TransitionTable, TransitionTableRow};
+use std::collections::HashMap;
+
+/// A strongly connected component of a transition table
+#[derive(Clone, Debug, PartialEq)]
+pub struct Component {
+    /// The component as a standalone table, with transitions leaving the component as errors
+    pub table: TransitionTable,
+
+    /// The original ID of each state in the component, indexed by its ID in `table`
+    pub states: Vec<usize>,
+}
+
+/// A transition between two different components, using original state IDs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CrossEdge {
+    /// The state the transition leaves
+    pub from: usize,
+
+    /// The column of the transition
+    pub symbol: usize,
+
+    /// The state the transition enters
+    pub to: usize,
+}
+
+/// A transition table split into its strongly connected components
+#[derive(Clone, Debug, PartialEq)]
+pub struct Components {
+    /// The components, in topological order (edges only go from earlier to later components)
+    pub components: Vec<Component>,
+
+    /// The transitions between components
+    pub cross_edges: Vec<CrossEdge>,
+}
+
+impl TransitionTable {
+    /// Extract the part of the table reachable from the given state
+    ///
+    /// The result is a standalone table whose starting state is `from_state`. States are renumbered
+    /// in breadth-first order, so `from_state` becomes state 0. Returns `None` if the state does
+    /// not exist.
+    pub fn sub_automaton(&self, from_state: usize) -> Option<TransitionTable> {
+        self.row(from_state)?;
+
+        Some(self.renumber_reachable(from_state).0)
+    }
+
+    /// Restrict the table to transitions on the given columns
+    ///
+    /// Transitions on every other column become error transitions, and states no longer reachable
+    /// from the starting state are dropped. The remaining states keep their IDs, and the table
+    /// keeps its width.
+    pub fn slice_by_symbols(&self, symbols: &[usize]) -> TransitionTable {
+        let mut sliced = self.clone();
+        for row in &mut sliced.rows {
+            for (symbol, transition) in row.transitions.iter_mut().enumerate() {
+                if !symbols.contains(&symbol) {
+                    *transition = None;
+                }
+            }
+        }
+
+        sliced.canonicalize(Renumbering::Preserve)
+    }
+
+    /// Split the table into its strongly connected components
+    ///
+    /// Each component is renumbered from 0 in order of original state ID. Transitions to states that
+    /// do not exist are dropped.
+    pub fn split_components(&self) -> Components {
+        let sccs = self.strongly_connected_components();
+
+        // Find the component of every state
+        let mut component_of = HashMap::new();
+        for (component_index, states) in sccs.iter().enumerate() {
+            for (new_id, id) in states.iter().enumerate() {
+                component_of.insert(*id, (component_index, new_id));
+            }
+        }
+
+        let mut cross_edges = Vec::new();
+        let components = sccs
+            .into_iter()
+            .enumerate()
+            .map(|(component_index, states)| {
+                let rows = states
+                    .iter()
+                    .enumerate()
+                    .map(|(new_id, id)| {
+                        let row = self.row(*id).unwrap();
+                        let transitions = row
+                            .transitions
+                            .iter()
+                            .enumerate()
+                            .map(|(symbol, transition)| {
+                                let (target_component, target_id) =
+                                    *component_of.get(&(*transition)?)?;
+                                if target_component == component_index {
+                                    Some(target_id)
+                                } else {
+                                    cross_edges.push(CrossEdge {
+                                        from: *id,
+                                        symbol,
+                                        to: transition.unwrap(),
+                                    });
+                                    None
+                                }
+                            })
+                            .collect();
+
+                        TransitionTableRow {
+                            accepting: row.accepting,
+                            id: new_id,
+                            transitions,
+                            doc: row.doc.clone(),
+                            label: row.label.clone(),
+                        }
+                    })
+                    .collect();
+
+                Component {
+                    table: TransitionTable {
+                        rows,
+                        alphabet: self.alphabet.clone(),
+                        metadata: self.metadata.clone(),
+                        comment: self.comment.clone(),
+                    },
+                    states,
+                }
+            })
+            .collect();
+
+        Components {
+            components,
+            cross_edges,
+        }
+    }
+
+    /// Find the strongly connected components using an iterative version of Tarjan's algorithm
+    ///
+    /// Components are returned in topological order, each with its state IDs sorted.
+    fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let count = self.rows.len();
+
+        let mut order = vec![usize::MAX; count];
+        let mut low_link = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut next_order = 0;
+        let mut sccs = Vec::new();
+
+        for root in 0..count {
+            if order[root] != usize::MAX {
+                continue;
+            }
+
+            // Each frame is a row index plus the next transition to visit
+            let mut frames = vec![(root, 0)];
+            order[root] = next_order;
+            low_link[root] = next_order;
+            next_order += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some((index, transition)) = frames.last_mut() {
+                let index = *index;
+                let transitions = &self.rows[index].transitions;
+
+                if *transition < transitions.len() {
+                    let target = transitions[*transition].and_then(|id| self.index_of(id));
+                    *transition += 1;
+
+                    match target {
+                        Some(target) if order[target] == usize::MAX => {
+                            order[target] = next_order;
+                            low_link[target] = next_order;
+                            next_order += 1;
+                            stack.push(target);
+                            on_stack[target] = true;
+                            frames.push((target, 0));
+                        }
+                        Some(target) if on_stack[target] => {
+                            low_link[index] = low_link[index].min(order[target]);
+                        }
+                        _ => {}
+                    }
+                    continue;
+                }
+
+                // All transitions are visited, so close the frame
+                frames.pop();
+                if let Some((parent, _)) = frames.last() {
+                    low_link[*parent] = low_link[*parent].min(low_link[index]);
+                }
+
+                if low_link[index] == order[index] {
+                    let mut scc = Vec::new();
+                    loop {
+                        let member = stack.pop().unwrap();
+                        on_stack[member] = false;
+                        scc.push(self.rows[member].id);
+                        if member == index {
+                            break;
+                        }
+                    }
+                    scc.sort_unstable();
+                    sccs.push(scc);
+                }
+            }
+        }
+
+        // Tarjan's algorithm finds components in reverse topological order
+        sccs.reverse();
+        sccs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn sub_automaton() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n- 1 2 3\n+ 2 E 2\n+ 3 E E\n- 4 0 3\n")?;
+
+        let sub = table.sub_automaton(1).unwrap();
+        assert_eq!(sub.serialize(), "- 0 1 2\n+ 1 E 1\n+ 2 E E\n");
+
+        assert_eq!(table.sub_automaton(4).unwrap().rows.len(), 5);
+        assert!(table.sub_automaton(5).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn slice_by_symbols() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 2\n+ 1 1 3\n- 2 E 0\n+ 3 3 3\n")?;
+
+        let sliced = table.slice_by_symbols(&[0]);
+        assert_eq!(sliced.serialize(), "- 0 1 E\n+ 1 1 E\n");
+        assert_eq!(table.slice_by_symbols(&[0, 1]), table);
+        assert_eq!(table.slice_by_symbols(&[]).serialize(), "- 0 E E\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn split_components() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n- 1 0 2\n+ 2 E 2\n- 3 E 2\n")?;
+
+        let split = table.split_components();
+        assert_eq!(split.components.len(), 3);
+        assert_eq!(split.components[0].states, vec![3]);
+        assert_eq!(split.components[1].states, vec![0, 1]);
+        assert_eq!(split.components[1].table.serialize(), "- 0 1 E\n- 1 0 E\n");
+        assert_eq!(split.components[2].states, vec![2]);
+        assert_eq!(split.components[2].table.serialize(), "+ 0 E 0\n");
+        assert_eq!(
+            split.cross_edges,
+            vec![
+                CrossEdge {
+                    from: 3,
+                    symbol: 1,
+                    to: 2
+                },
+                CrossEdge {
+                    from: 1,
+                    symbol: 1,
+                    to: 2
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Nfa, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+impl Nfa {
+    /// Build a table accepting the same inputs with the subset construction
+    pub fn determinize(&self) -> TransitionTable {
+        self.determinize_with_sets().0
+    }
+
+    /// Build a table accepting the same inputs, along with the set of NFA states every new state
+    /// stands for, indexed by new state ID
+    ///
+    /// Each new state is the epsilon closure of a set of NFA states, starting from the closure of
+    /// the starting state. Only the sets reachable from it are built, numbered in breadth-first
+    /// order, and a column leading to no NFA states becomes an error transition. A set is accepting
+    /// if any of its states is. An NFA without a starting state gives a single rejecting state
+    /// standing for no NFA states.
+    pub fn determinize_with_sets(&self) -> (TransitionTable, Vec<BTreeSet<usize>>) {
+        let width = self.width();
+        let start = self.start_states();
+        if start.is_empty() {
+            return (TransitionTable::empty_language(width), vec![start]);
+        }
+
+        let mut ids = HashMap::from([(start.clone(), STARTING_STATE_ID)]);
+        let mut sets = vec![start.clone()];
+        let mut queue = VecDeque::from([start]);
+        let mut rows = Vec::new();
+        while let Some(set) = queue.pop_front() {
+            let transitions = (0..width)
+                .map(|symbol| {
+                    let target = self.step(&set, symbol);
+                    if target.is_empty() {
+                        return None;
+                    }
+                    let next_id = ids.len();
+                    Some(*ids.entry(target.clone()).or_insert_with(|| {
+                        sets.push(target.clone());
+                        queue.push_back(target);
+                        next_id
+                    }))
+                })
+                .collect();
+
+            rows.push(TransitionTableRow {
+                accepting: set
+                    .iter()
+                    .any(|state| self.row(*state).is_some_and(|row| row.accepting)),
+                id: ids[&set],
+                transitions,
+                doc: None,
+                label: None,
+            });
+        }
+
+        (
+            TransitionTable {
+                rows,
+                alphabet: None,
+                metadata: Default::default(),
+                comment: None,
+            },
+            sets,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseError;
+
+    #[test]
+    fn determinize() -> Result<(), ParseError> {
+        // Over a and b: inputs ending in "ab", with an epsilon transition to a second way in
+        let nfa = Nfa::parse("- 0 {0,1} 0 {2}\n- 1 E 3 E\n- 2 1 E E\n+ 3 E E E\n")?;
+        let (table, sets) = nfa.determinize_with_sets();
+        assert_eq!(table.to_string(), "- 0 1 0\n- 1 1 2\n+ 2 1 0\n");
+        assert_eq!(
+            sets,
+            [
+                BTreeSet::from([0, 2]),
+                BTreeSet::from([0, 1, 2]),
+                BTreeSet::from([0, 2, 3])
+            ]
+        );
+        for input in [&[][..], &[0, 1], &[1, 0, 1], &[0, 1, 0], &[1, 1]] {
+            assert_eq!(table.accepts(input), nfa.accepts(input));
+        }
+
+        let (table, sets) = Nfa::parse("+ 1 1 E\n")?.determinize_with_sets();
+        assert_eq!(table.to_string(), "- 0 E\n");
+        assert_eq!(sets, [BTreeSet::new()]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,165 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+impl TransitionTable {
+    /// Find every shortest input, of at most `max_len` columns, that one table accepts and the
+    /// other rejects
+    ///
+    /// Searches both tables together breadth-first, each completed with its sink, to find the
+    /// shortest length at which they disagree, then lists all inputs of that length where they do,
+    /// in lexicographic order. Returns no inputs if the tables agree on everything up to `max_len`.
+    pub fn find_divergence(
+        spec: &TransitionTable,
+        implementation: &TransitionTable,
+        max_len: usize,
+    ) -> Vec<Vec<usize>> {
+        let width = spec.width().max(implementation.width());
+        let start = |table: &TransitionTable| {
+            table
+                .index_of(STARTING_STATE_ID)
+                .unwrap_or(table.rows.len())
+        };
+        let accepting = |table: &TransitionTable, index: usize| {
+            table.rows.get(index).is_some_and(|row| row.accepting)
+        };
+        let disagree = |(a, b): (usize, usize)| accepting(spec, a) != accepting(implementation, b);
+        let step = |(a, b): (usize, usize), symbol: usize| {
+            (spec.delta(a, symbol), implementation.delta(b, symbol))
+        };
+
+        // Find the pairs reached by inputs of each length, up to the first disagreement
+        let mut layers = vec![HashSet::from([(start(spec), start(implementation))])];
+        loop {
+            let layer = layers.last().unwrap();
+            if layer.iter().any(|pair| disagree(*pair)) {
+                break;
+            }
+            if layers.len() > max_len {
+                return Vec::new();
+            }
+
+            let next = layer
+                .iter()
+                .flat_map(|pair| (0..width).map(move |symbol| step(*pair, symbol)))
+                .collect::<HashSet<_>>();
+            // Once the reachable pairs repeat, no longer input can disagree either
+            if layers.contains(&next) {
+                return Vec::new();
+            }
+            layers.push(next);
+        }
+        let len = layers.len() - 1;
+
+        // `viable[depth]` holds the pairs at that depth leading to a disagreement at `len`
+        let mut viable = vec![HashSet::new(); len + 1];
+        viable[len] = layers[len]
+            .iter()
+            .copied()
+            .filter(|pair| disagree(*pair))
+            .collect();
+        for depth in (0..len).rev() {
+            viable[depth] = layers[depth]
+                .iter()
+                .copied()
+                .filter(|pair| {
+                    (0..width).any(|symbol| viable[depth + 1].contains(&step(*pair, symbol)))
+                })
+                .collect();
+        }
+
+        // List the inputs along viable pairs depth-first, in lexicographic order
+        let mut inputs = Vec::new();
+        let mut stack = vec![(layers[0].iter().next().copied().unwrap(), Vec::new())];
+        while let Some((pair, input)) = stack.pop() {
+            if input.len() == len {
+                inputs.push(input);
+                continue;
+            }
+            for symbol in (0..width).rev() {
+                let next = step(pair, symbol);
+                if viable[input.len() + 1].contains(&next) {
+                    let mut extended = input.clone();
+                    extended.push(symbol);
+                    stack.push((next, extended));
+                }
+            }
+        }
+
+        inputs
+    }
+
+    /// Find a shortest input that one table accepts and the other rejects
+    ///
+    /// Returns `None` if the tables accept the same language.
+    pub(crate) fn shortest_divergence(
+        a: &TransitionTable,
+        b: &TransitionTable,
+    ) -> Option<Vec<usize>> {
+        let width = a.width().max(b.width());
+        let start = |table: &TransitionTable| {
+            table
+                .index_of(STARTING_STATE_ID)
+                .unwrap_or(table.rows.len())
+        };
+        let accepting = |table: &TransitionTable, index: usize| {
+            table.rows.get(index).is_some_and(|row| row.accepting)
+        };
+
+        // The pair and column each pair was first reached from
+        let start = (start(a), start(b));
+        let mut parents = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pair) = queue.pop_front() {
+            if accepting(a, pair.0) != accepting(b, pair.1) {
+                let mut input = Vec::new();
+                let mut current = pair;
+                while let Some((parent, symbol)) = parents[&current] {
+                    input.push(symbol);
+                    current = parent;
+                }
+                input.reverse();
+                return Some(input);
+            }
+
+            for symbol in 0..width {
+                let next = (a.delta(pair.0, symbol), b.delta(pair.1, symbol));
+                parents.entry(next).or_insert_with(|| {
+                    queue.push_back(next);
+                    Some((pair, symbol))
+                });
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn find_divergence() -> Result<(), ParseSerializeError> {
+        // Strings with an even number of 1s, and a buggy version that ignores 1s after a 0
+        let spec = TransitionTable::parse("+ 0 0 1\n- 1 1 0\n")?;
+        let buggy = TransitionTable::parse("+ 0 2 1\n- 1 1 0\n+ 2 2 2\n")?;
+
+        assert_eq!(
+            TransitionTable::find_divergence(&spec, &buggy, 5),
+            vec![vec![0, 1]]
+        );
+        assert!(TransitionTable::find_divergence(&spec, &buggy, 1).is_empty());
+        assert!(TransitionTable::find_divergence(&spec, &spec.minimize(), 10).is_empty());
+
+        // Every shortest disagreement is listed
+        let empty = TransitionTable::parse("- 0 E E\n")?;
+        let one_symbol = TransitionTable::parse("- 0 1 1\n+ 1 E E\n")?;
+        assert_eq!(
+            TransitionTable::find_divergence(&empty, &one_symbol, 3),
+            vec![vec![0], vec![1]]
+        );
+
+        Ok(())
+    }
+}
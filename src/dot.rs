@@ -0,0 +1,668 @@
+use crate::transition_table::is_label;
+use crate::{
+    Alphabet, ImportedTable, ParseSerializeError, SymbolMap, TransitionTable, TransitionTableRow,
+    STARTING_STATE_ID,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+impl TransitionTable {
+    /// Export the table as a Graphviz DOT graph, labeling edges like
+    /// [`TransitionTable::to_dot_with_alphabet`] with the table's [`TransitionTable::alphabet`],
+    /// or with column indices if it has none
+    pub fn to_dot(&self) -> String {
+        match &self.alphabet {
+            Some(alphabet) => self.to_dot_with_alphabet(alphabet),
+            None => {
+                let labels: Vec<String> =
+                    (0..self.width()).map(|column| column.to_string()).collect();
+                self.to_dot_labeled(&labels)
+            }
+        }
+    }
+
+    /// Export the table as a Graphviz DOT graph, labeling edges with the columns' symbols
+    ///
+    /// Columns without a symbol are labeled with their index.
+    pub fn to_dot_with_symbols<S: Eq + Hash + Display>(&self, symbols: &SymbolMap<S>) -> String {
+        let mut labels = symbols.labels();
+        labels.extend((labels.len()..self.width()).map(|column| column.to_string()));
+        self.to_dot_labeled(&labels)
+    }
+
+    /// Export the table as a Graphviz DOT graph, labeling edges with the columns' character sets
+    ///
+    /// If any column has a description, a legend node lists the described columns.
+    pub fn to_dot_with_alphabet(&self, alphabet: &Alphabet) -> String {
+        let labels: Vec<String> = (0..self.width())
+            .map(|column| {
+                if column < alphabet.len() {
+                    alphabet.label(column)
+                } else {
+                    column.to_string()
+                }
+            })
+            .collect();
+        let mut output = self.to_dot_labeled(&labels);
+
+        let legend = alphabet.legend();
+        if !legend.is_empty() {
+            // Left-justify every line of the legend
+            let lines: String = legend
+                .iter()
+                .map(|(label, description)| {
+                    format!("{}: {}\\l", escape(label), escape(description))
+                })
+                .collect();
+            output.truncate(output.len() - "}\n".len());
+            writeln!(output, "    legend [shape=note, label=\"{}\"];", lines).unwrap();
+            output.push_str("}\n");
+        }
+
+        output
+    }
+
+    /// Export the table as a Graphviz DOT graph with a label for every column
+    ///
+    /// Accepting states are double circles, and an arrow from an invisible node marks the starting
+    /// state. Transitions between the same pair of states are one edge labeled with all of their
+    /// symbols. A labeled state shows its label instead of its ID, and a documented state shows its
+    /// documentation as a tooltip.
+    fn to_dot_labeled(&self, labels: &[String]) -> String {
+        let mut output = String::from("digraph {\n    rankdir=LR;\n    start [shape=point];\n");
+        for row in &self.rows {
+            let shape = if row.accepting {
+                "doublecircle"
+            } else {
+                "circle"
+            };
+            let mut attributes = format!("shape={}", shape);
+            if let Some(label) = &row.label {
+                write!(attributes, ", label=\"{}\"", escape(label)).unwrap();
+            }
+            if let Some(doc) = &row.doc {
+                let tooltip = escape(doc).replace('\n', "\\n");
+                write!(attributes, ", tooltip=\"{}\"", tooltip).unwrap();
+            }
+            writeln!(output, "    {} [{}];", row.id, attributes).unwrap();
+        }
+        if self.row(STARTING_STATE_ID).is_some() {
+            writeln!(output, "    start -> {};", STARTING_STATE_ID).unwrap();
+        }
+
+        for row in &self.rows {
+            let mut edges: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+            for (symbol, target) in row.transitions.iter().enumerate() {
+                if let Some(target) = target {
+                    edges.entry(*target).or_default().push(&labels[symbol]);
+                }
+            }
+            for (target, symbols) in edges {
+                let label = escape(&symbols.join(", "));
+                writeln!(
+                    output,
+                    "    {} -> {} [label=\"{}\"];",
+                    row.id, target, label
+                )
+                .unwrap();
+            }
+        }
+
+        output.push_str("}\n");
+        output
+    }
+}
+
+/// Escape a label for a DOT string
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// DOT attributes that only affect how a graph is drawn, which are ignored without a warning
+const COSMETIC_ATTRIBUTES: &[&str] = &[
+    "arrowhead",
+    "arrowsize",
+    "bgcolor",
+    "color",
+    "fillcolor",
+    "fontcolor",
+    "fontname",
+    "fontsize",
+    "height",
+    "label",
+    "margin",
+    "nodesep",
+    "penwidth",
+    "rankdir",
+    "ranksep",
+    "size",
+    "style",
+    "width",
+];
+
+/// A token of the DOT language
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum DotToken {
+    /// An identifier, number, or quoted string, unquoted
+    Id(String),
+
+    /// A punctuation mark, or `->` for an edge
+    Punct(&'static str),
+}
+
+/// Split DOT source into tokens, skipping whitespace and comments
+fn tokenize_dot(input: &str) -> Result<Vec<DotToken>, ParseSerializeError> {
+    let error = |message: &str| ParseSerializeError {
+        message: format!("DOT {}", message),
+    };
+
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '/' if chars.peek() == Some(&'/') => {
+                chars.find(|c| *c == '\n');
+            }
+            '#' => {
+                chars.find(|c| *c == '\n');
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                loop {
+                    match chars.next() {
+                        Some('/') if previous == '*' => break,
+                        Some(c) => previous = c,
+                        None => return Err(error("comment is never closed")),
+                    }
+                }
+            }
+            '{' => tokens.push(DotToken::Punct("{")),
+            '}' => tokens.push(DotToken::Punct("}")),
+            '[' => tokens.push(DotToken::Punct("[")),
+            ']' => tokens.push(DotToken::Punct("]")),
+            '=' => tokens.push(DotToken::Punct("=")),
+            ';' => tokens.push(DotToken::Punct(";")),
+            ',' => tokens.push(DotToken::Punct(",")),
+            '-' if chars.peek() == Some(&'>') => {
+                chars.next();
+                tokens.push(DotToken::Punct("->"));
+            }
+            '-' if chars.peek() == Some(&'-') => {
+                return Err(error("undirected edges can't describe transitions"));
+            }
+            '"' => {
+                let mut id = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('"') => id.push('"'),
+                            Some('\\') => id.push('\\'),
+                            Some('\n') => {}
+                            Some(c) => {
+                                id.push('\\');
+                                id.push(c);
+                            }
+                            None => return Err(error("string is never closed")),
+                        },
+                        Some(c) => id.push(c),
+                        None => return Err(error("string is never closed")),
+                    }
+                }
+                tokens.push(DotToken::Id(id));
+            }
+            c if c.is_alphanumeric() || c == '_' || c == '.' || c == '-' => {
+                let mut id = c.to_string();
+                while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_' || *c == '.')
+                {
+                    id.push(c);
+                }
+                tokens.push(DotToken::Id(id));
+            }
+            c => return Err(error(&format!("has an unexpected character {:?}", c))),
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// The nodes and edges of a DOT graph, before they are numbered
+#[derive(Debug, Default)]
+struct DotGraph {
+    /// The name of every node, in the order they first appear
+    nodes: Vec<String>,
+
+    /// The shape of every node that has one
+    shapes: HashMap<String, String>,
+
+    /// The tooltip of every node that has one
+    tooltips: HashMap<String, String>,
+
+    /// The label of every node that has one usable as a row label
+    labels: HashMap<String, String>,
+
+    /// The source, target, and label of every edge
+    edges: Vec<(String, String, Option<String>)>,
+
+    /// The ignored parts of the graph, without repeats
+    warnings: Vec<String>,
+}
+
+impl DotGraph {
+    fn node(&mut self, name: &str) {
+        if !self.nodes.iter().any(|node| node == name) {
+            self.nodes.push(name.to_string());
+        }
+    }
+
+    fn warn(&mut self, warning: String) {
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Warn about an ignored attribute, unless it only affects how the graph is drawn
+    fn ignore_attribute(&mut self, kind: &str, attribute: &str) {
+        if !COSMETIC_ATTRIBUTES.contains(&attribute) {
+            self.warn(format!("{} attribute {} is ignored", kind, attribute));
+        }
+    }
+}
+
+/// Parse an attribute list after its opening `[`, up to and including the closing `]`
+fn parse_attributes(
+    tokens: &mut std::iter::Peekable<std::vec::IntoIter<DotToken>>,
+) -> Result<Vec<(String, String)>, ParseSerializeError> {
+    let mut attributes = Vec::new();
+    loop {
+        match tokens.next() {
+            Some(DotToken::Punct("]")) => return Ok(attributes),
+            Some(DotToken::Punct(",")) | Some(DotToken::Punct(";")) => {}
+            Some(DotToken::Id(name)) => {
+                if tokens.next() != Some(DotToken::Punct("=")) {
+                    return Err(ParseSerializeError {
+                        message: format!("DOT attribute {} has no value", name),
+                    });
+                }
+                match tokens.next() {
+                    Some(DotToken::Id(value)) => attributes.push((name, value)),
+                    _ => {
+                        return Err(ParseSerializeError {
+                            message: format!("DOT attribute {} has no value", name),
+                        })
+                    }
+                }
+            }
+            _ => {
+                return Err(ParseSerializeError {
+                    message: "DOT attribute list is never closed".to_string(),
+                })
+            }
+        }
+    }
+}
+
+impl TransitionTable {
+    /// Read a table from a restricted dialect of Graphviz DOT
+    ///
+    /// The graph must be a `digraph`. Nodes with `shape=doublecircle` are accepting, and an edge
+    /// from a node with `shape=point` marks the starting state, which is otherwise the first node.
+    /// Nodes with `shape=note`, such as legends, aren't states, node labels that are valid row
+    /// labels name their states, and node tooltips document their states. Edge labels name the
+    /// symbols of their transitions, with commas separating several symbols on one edge.
+    /// `node [shape=...]` sets the shape of the nodes that follow it. Everything else, such as
+    /// cosmetic attributes and subgraphs, is ignored and listed in the warnings, along with edges
+    /// without labels and conflicting transitions, of which the first is kept.
+    pub fn parse_dot(input: &str) -> Result<ImportedTable, ParseSerializeError> {
+        let error = |message: &str| ParseSerializeError {
+            message: format!("DOT {}", message),
+        };
+        let mut tokens = tokenize_dot(input)?.into_iter().peekable();
+
+        if tokens.peek() == Some(&DotToken::Id("strict".to_string())) {
+            tokens.next();
+        }
+        match tokens.next() {
+            Some(DotToken::Id(keyword)) if keyword == "digraph" => {}
+            Some(DotToken::Id(keyword)) if keyword == "graph" => {
+                return Err(error(
+                    "graph is undirected, so it can't describe transitions",
+                ));
+            }
+            _ => return Err(error("input doesn't start with digraph")),
+        }
+        if let Some(DotToken::Id(_)) = tokens.peek() {
+            tokens.next();
+        }
+        if tokens.next() != Some(DotToken::Punct("{")) {
+            return Err(error("graph has no body"));
+        }
+
+        let mut graph = DotGraph::default();
+        let mut default_shape = None;
+        let mut depth = 0;
+        loop {
+            let name = match tokens.next() {
+                Some(DotToken::Punct("}")) if depth == 0 => break,
+                Some(DotToken::Punct("}")) => {
+                    depth -= 1;
+                    continue;
+                }
+                Some(DotToken::Punct("{")) => {
+                    graph.warn("subgraph grouping is ignored".to_string());
+                    depth += 1;
+                    continue;
+                }
+                Some(DotToken::Punct(";")) | Some(DotToken::Punct(",")) => continue,
+                Some(DotToken::Id(name)) => name,
+                Some(DotToken::Punct(punct)) => {
+                    return Err(error(&format!("graph has an unexpected {}", punct)))
+                }
+                None => return Err(error("graph is never closed")),
+            };
+
+            match (name.as_str(), tokens.peek()) {
+                ("subgraph", _) => {
+                    if let Some(DotToken::Id(_)) = tokens.peek() {
+                        tokens.next();
+                    }
+                }
+                ("graph" | "node" | "edge", Some(DotToken::Punct("["))) => {
+                    tokens.next();
+                    for (attribute, value) in parse_attributes(&mut tokens)? {
+                        if name == "node" && attribute == "shape" {
+                            default_shape = Some(value);
+                        } else {
+                            graph.ignore_attribute(&name, &attribute);
+                        }
+                    }
+                }
+                (_, Some(DotToken::Punct("="))) => {
+                    tokens.next();
+                    tokens.next();
+                    graph.ignore_attribute("graph", &name);
+                }
+                (_, Some(DotToken::Punct("->"))) => {
+                    // An edge chain, with attributes applying to every edge in it
+                    let mut chain = vec![name];
+                    while tokens.next_if_eq(&DotToken::Punct("->")).is_some() {
+                        match tokens.next() {
+                            Some(DotToken::Id(target)) => chain.push(target),
+                            _ => return Err(error("edge has no target")),
+                        }
+                    }
+                    let mut label = None;
+                    if tokens.next_if_eq(&DotToken::Punct("[")).is_some() {
+                        for (attribute, value) in parse_attributes(&mut tokens)? {
+                            if attribute == "label" {
+                                label = Some(value);
+                            } else {
+                                graph.ignore_attribute("edge", &attribute);
+                            }
+                        }
+                    }
+                    for node in &chain {
+                        graph.node(node);
+                        if let Some(shape) = &default_shape {
+                            graph.shapes.entry(node.clone()).or_insert(shape.clone());
+                        }
+                    }
+                    for pair in chain.windows(2) {
+                        graph
+                            .edges
+                            .push((pair[0].clone(), pair[1].clone(), label.clone()));
+                    }
+                }
+                _ => {
+                    graph.node(&name);
+                    let mut shape = default_shape.clone();
+                    if tokens.next_if_eq(&DotToken::Punct("[")).is_some() {
+                        for (attribute, value) in parse_attributes(&mut tokens)? {
+                            if attribute == "shape" {
+                                shape = Some(value);
+                            } else if attribute == "tooltip" {
+                                graph
+                                    .tooltips
+                                    .insert(name.clone(), value.replace("\\n", "\n"));
+                            } else if attribute == "label" && is_label(&value) {
+                                graph.labels.insert(name.clone(), value);
+                            } else {
+                                graph.ignore_attribute("node", &attribute);
+                            }
+                        }
+                    }
+                    if let Some(shape) = shape {
+                        graph.shapes.insert(name, shape);
+                    }
+                }
+            }
+        }
+
+        TransitionTable::from_dot_graph(graph)
+    }
+
+    /// Number the nodes of a parsed DOT graph and build its table
+    fn from_dot_graph(mut graph: DotGraph) -> Result<ImportedTable, ParseSerializeError> {
+        let is_marker = |graph: &DotGraph, node: &str| {
+            graph.shapes.get(node).is_some_and(|shape| shape == "point")
+        };
+
+        // Find the starting node from the edges leaving start markers
+        let mut start = None;
+        for (source, target, _) in &graph.edges {
+            if is_marker(&graph, source) && !is_marker(&graph, target) {
+                match &start {
+                    None => start = Some(target.clone()),
+                    Some(start) if start == target => {}
+                    Some(_) => {
+                        let warning = "several starting states are marked".to_string();
+                        if !graph.warnings.contains(&warning) {
+                            graph.warnings.push(warning);
+                        }
+                    }
+                }
+            }
+        }
+        let states: Vec<String> = graph
+            .nodes
+            .iter()
+            .filter(|node| {
+                !is_marker(&graph, node)
+                    && graph.shapes.get(*node).is_none_or(|shape| shape != "note")
+            })
+            .cloned()
+            .collect();
+        let start = match start {
+            Some(start) => start,
+            None => {
+                let first = states.first().cloned().ok_or_else(|| ParseSerializeError {
+                    message: "DOT graph has no states".to_string(),
+                })?;
+                graph.warnings.push(format!(
+                    "no starting state is marked, so the first node {} starts",
+                    first
+                ));
+                first
+            }
+        };
+        let states: Vec<String> = std::iter::once(start.clone())
+            .chain(states.into_iter().filter(|node| *node != start))
+            .collect();
+        let id_of: HashMap<&str, usize> = states
+            .iter()
+            .enumerate()
+            .map(|(id, node)| (node.as_str(), id))
+            .collect();
+
+        let mut symbols = SymbolMap::new();
+        let mut transitions: BTreeMap<(usize, usize), usize> = BTreeMap::new();
+        let mut warnings = graph.warnings.clone();
+        let mut warn = |warning: String| {
+            if !warnings.contains(&warning) {
+                warnings.push(warning);
+            }
+        };
+        for (source, target, label) in &graph.edges {
+            let (Some(source_id), Some(target_id)) =
+                (id_of.get(source.as_str()), id_of.get(target.as_str()))
+            else {
+                continue;
+            };
+            let Some(label) = label else {
+                warn(format!("edge {} -> {} has no label", source, target));
+                continue;
+            };
+            for symbol in label
+                .split(',')
+                .map(str::trim)
+                .filter(|symbol| !symbol.is_empty())
+            {
+                let column = symbols.insert(symbol.to_string());
+                let existing = *transitions
+                    .entry((*source_id, column))
+                    .or_insert(*target_id);
+                if existing != *target_id {
+                    warn(format!(
+                        "edge {} -> {} conflicts with another edge on {}",
+                        source, target, symbol
+                    ));
+                }
+            }
+        }
+
+        let width = symbols.len();
+        let rows = states
+            .iter()
+            .enumerate()
+            .map(|(id, node)| TransitionTableRow {
+                accepting: graph
+                    .shapes
+                    .get(node)
+                    .is_some_and(|shape| shape == "doublecircle"),
+                id,
+                transitions: (0..width)
+                    .map(|column| transitions.get(&(id, column)).copied())
+                    .collect(),
+                doc: graph.tooltips.get(node).cloned(),
+                label: graph.labels.get(node).cloned(),
+            })
+            .collect();
+
+        Ok(ImportedTable {
+            table: TransitionTable {
+                rows,
+                alphabet: None,
+                metadata: Default::default(),
+                comment: None,
+            },
+            symbols,
+            states,
+            warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn to_dot() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 1 E\n+ 1 E 1 0\n")?;
+        let symbols: SymbolMap<char> = "a\"".chars().collect();
+
+        assert_eq!(
+            table.to_dot_with_symbols(&symbols),
+            "digraph {\n    rankdir=LR;\n    start [shape=point];\n    0 [shape=circle];\n    \
+             1 [shape=doublecircle];\n    start -> 0;\n    0 -> 1 [label=\"a, \\\"\"];\n    \
+             1 -> 0 [label=\"2\"];\n    1 -> 1 [label=\"\\\"\"];\n}\n"
+        );
+        assert!(table.to_dot().contains("0 -> 1 [label=\"0, 1\"];"));
+
+        let imported = TransitionTable::parse_dot(&table.to_dot_with_symbols(&symbols))?;
+        assert_eq!(imported.table, table);
+        assert_eq!(
+            imported.symbols.iter().collect::<Vec<_>>(),
+            ["a", "\"", "2"]
+        );
+        assert!(imported.warnings.is_empty());
+
+        // Documentation survives as tooltips
+        let documented = TransitionTable::parse("# Reads \"a\"\n# first\n- 0 1\n+ 1 E\n")?;
+        let dot = documented.to_dot();
+        assert!(dot.contains("0 [shape=circle, tooltip=\"Reads \\\"a\\\"\\nfirst\"];"));
+        assert_eq!(TransitionTable::parse_dot(&dot)?.table, documented);
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_dot_with_alphabet() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n")?;
+        let alphabet = Alphabet::parse(r#"[0-9]="digit" [+\-]="sign, \"+\" or \"-\"""#)?;
+
+        let dot = table.to_dot_with_alphabet(&alphabet);
+        assert!(dot.contains(r#"    1 -> 0 [label="[+\\-]"];"#));
+        assert!(dot.ends_with(
+            "    legend [shape=note, label=\"[0-9]: digit\\l[+\\\\-]: sign, \\\"+\\\" or \\\"-\\\"\\l\"];\n}\n"
+        ));
+
+        // The legend isn't a state
+        let imported = TransitionTable::parse_dot(&dot)?;
+        assert_eq!(imported.table, table);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_dot() -> Result<(), ParseSerializeError> {
+        // The finite state machine example from the Graphviz gallery, shortened
+        let dot = r#"
+            digraph finite_state_machine {
+                rankdir=LR;
+                size="8,5"
+                ordering=out
+                node [shape = doublecircle]; LR_3;
+                node [shape = circle];
+                LR_0 -> LR_2 [label = "SS(B)"];
+                LR_0 -> LR_1 [label = "SS(S)"];
+                LR_1 -> LR_3 [label = "S($end)", color=red];
+                LR_2 -> LR_3 [label = "SS(S)"]; // Shares a symbol with LR_0 -> LR_1
+                LR_2 -> LR_0 [weight=2];
+                start [shape=point]; start -> LR_0;
+            }
+        "#;
+        let imported = TransitionTable::parse_dot(dot)?;
+
+        assert_eq!(imported.states, ["LR_0", "LR_3", "LR_2", "LR_1"]);
+        assert_eq!(
+            imported.table.serialize(),
+            "- 0 2 3 E\n+ 1 E E E\n- 2 E 1 E\n- 3 E E 1\n"
+        );
+        assert_eq!(imported.symbols.column(&"S($end)".to_string()), Some(2));
+        assert_eq!(
+            imported.warnings,
+            [
+                "graph attribute ordering is ignored",
+                "edge attribute weight is ignored",
+                "edge LR_2 -> LR_0 has no label",
+            ]
+        );
+
+        let unmarked = TransitionTable::parse_dot("digraph { b -> a [label=x] }")?;
+        assert_eq!(unmarked.states, ["b", "a"]);
+        assert_eq!(
+            unmarked.warnings,
+            ["no starting state is marked, so the first node b starts"]
+        );
+
+        assert!(TransitionTable::parse_dot("graph { a -- b }").is_err());
+        assert!(TransitionTable::parse_dot("digraph { a -> }").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use crate::{ParseSerializeError, TransitionTable, TransitionTableRow};
+use std::collections::{BTreeMap, BTreeSet};
+
+impl TransitionTable {
+    /// Build a table from `(source, column, target)` edges
+    ///
+    /// The table has a row for every state that appears in an edge or in `accepting`, and every
+    /// missing transition is an error transition. Fails if an edge's column is not less than
+    /// `width`, or if two edges leave the same state on the same column for different targets.
+    pub fn from_edges(
+        edges: impl IntoIterator<Item = (usize, usize, usize)>,
+        accepting: impl IntoIterator<Item = usize>,
+        width: usize,
+    ) -> Result<Self, ParseSerializeError> {
+        let mut table_rows: BTreeMap<usize, TransitionTableRow> = BTreeMap::new();
+        let new_row = |id: usize| TransitionTableRow {
+            accepting: false,
+            id,
+            transitions: vec![None; width],
+            doc: None,
+            label: None,
+        };
+
+        for (source, symbol, target) in edges {
+            if symbol >= width {
+                return Err(ParseSerializeError {
+                    message: format!(
+                        "Edge {} {} {} has a column outside the table's {} columns",
+                        source, symbol, target, width
+                    ),
+                });
+            }
+
+            table_rows.entry(target).or_insert_with(|| new_row(target));
+            let row = table_rows.entry(source).or_insert_with(|| new_row(source));
+            match row.transitions[symbol] {
+                Some(existing) if existing != target => {
+                    return Err(ParseSerializeError {
+                        message: format!(
+                            "State {} has edges to both {} and {} on column {}",
+                            source, existing, target, symbol
+                        ),
+                    });
+                }
+                _ => row.transitions[symbol] = Some(target),
+            }
+        }
+
+        for id in accepting {
+            table_rows
+                .entry(id)
+                .or_insert_with(|| new_row(id))
+                .accepting = true;
+        }
+
+        Ok(TransitionTable {
+            rows: table_rows.into_values().collect(),
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        })
+    }
+
+    /// Iterate over the table's transitions as `(source, column, target)` edges
+    ///
+    /// Error transitions are left out.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, usize)> + '_ {
+        self.rows.iter().flat_map(|row| {
+            row.transitions
+                .iter()
+                .enumerate()
+                .filter_map(move |(symbol, target)| Some((row.id, symbol, (*target)?)))
+        })
+    }
+
+    /// Parse a table from the edge list format
+    ///
+    /// Each line is either an edge as `source column target`, an accepting state as `+ state`, or
+    /// the number of columns as `width columns`. Without a `width` line, the table is as wide as
+    /// the largest column used.
+    pub fn parse_edges(input: &str) -> Result<Self, ParseSerializeError> {
+        let mut edges = Vec::new();
+        let mut accepting = BTreeSet::new();
+        let mut width = None;
+
+        for (line_index, line) in input.lines().enumerate() {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+            let number = |column: &str| {
+                column.parse::<usize>().map_err(|e| ParseSerializeError {
+                    message: format!("Line {} has an invalid number: {}", line_index + 1, e),
+                })
+            };
+
+            match columns.as_slice() {
+                ["+", state] => {
+                    accepting.insert(number(state)?);
+                }
+                ["width", columns] => width = Some(number(columns)?),
+                [source, symbol, target] => {
+                    edges.push((number(source)?, number(symbol)?, number(target)?));
+                }
+                _ => {
+                    return Err(ParseSerializeError {
+                        message: format!("Line {} is not an edge", line_index + 1),
+                    });
+                }
+            }
+        }
+
+        let width = width.unwrap_or_else(|| {
+            edges
+                .iter()
+                .map(|(_, symbol, _)| symbol + 1)
+                .max()
+                .unwrap_or(0)
+        });
+        TransitionTable::from_edges(edges, accepting, width)
+    }
+
+    /// Serialize the table in the edge list format
+    ///
+    /// Non-accepting states without any transitions to or from them are left out, since the
+    /// format has no way to declare them.
+    pub fn serialize_edges(&self) -> String {
+        let mut output = format!("width {}\n", self.width());
+
+        for row in self.rows.iter().filter(|row| row.accepting) {
+            output.push_str(&format!("+ {}\n", row.id));
+        }
+        for (source, symbol, target) in self.edges() {
+            output.push_str(&format!("{} {} {}\n", source, symbol, target));
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_edges() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::from_edges([(0, 0, 1), (1, 1, 1), (1, 0, 0)], [1], 3)?;
+        assert_eq!(table.serialize(), "- 0 1 E E\n+ 1 0 1 E\n");
+        assert_eq!(
+            table.edges().collect::<Vec<_>>(),
+            vec![(0, 0, 1), (1, 0, 0), (1, 1, 1)]
+        );
+
+        assert!(TransitionTable::from_edges([(0, 3, 1)], [], 3).is_err());
+        assert!(TransitionTable::from_edges([(0, 0, 1), (0, 0, 2)], [], 1).is_err());
+        assert!(TransitionTable::from_edges([(0, 0, 1), (0, 0, 1)], [], 1).is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_serialize_edges() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E E\n+ 1 0 1 E\n")?;
+
+        let edges = table.serialize_edges();
+        assert_eq!(edges, "width 3\n+ 1\n0 0 1\n1 0 0\n1 1 1\n");
+        assert_eq!(TransitionTable::parse_edges(&edges)?, table);
+        assert_eq!(TransitionTable::parse_edges("0 1 1\n+ 1\n")?.width(), 2);
+        assert!(TransitionTable::parse_edges("0 1\n").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{CharSet, Regex, TransitionTable, STARTING_STATE_ID};
+
+/// Errors that can occur when converting a table to a regex
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ToRegexError {
+    /// A column has no character in the alphabet to write it as
+    #[error("column {0} has no character in the alphabet")]
+    MissingCharacter(usize),
+}
+
+impl TransitionTable {
+    /// Convert the table into a regex matching the same strings, with column `i` written as
+    /// `alphabet[i]`
+    ///
+    /// Uses state elimination: the table becomes a generalized NFA with regexes on its edges, a
+    /// new starting state, and a new accepting state, and its states are removed one at a time,
+    /// each time rerouting the paths through the removed state around it. States with the fewest
+    /// paths through them are removed first, which keeps the regex short. The result is in the
+    /// syntax read by [`Regex::parse`], and is `[]` for a table accepting nothing. Fails if the
+    /// alphabet has fewer characters than the table has columns.
+    pub fn to_regex(&self, alphabet: &[char]) -> Result<String, ToRegexError> {
+        if alphabet.len() < self.width() {
+            return Err(ToRegexError::MissingCharacter(alphabet.len()));
+        }
+
+        // The edges between states by row index, then the new starting and accepting states
+        let states = self.rows.len();
+        let (start, end) = (states, states + 1);
+        let mut edges: Vec<Vec<Option<Regex>>> = vec![vec![None; states + 2]; states + 2];
+        for (index, row) in self.rows.iter().enumerate() {
+            for (symbol, target) in row.transitions.iter().enumerate() {
+                if let Some(target) = target.and_then(|target| self.index_of(target)) {
+                    let class = Regex::Class(CharSet::single(alphabet[symbol]));
+                    edges[index][target] = Some(union(edges[index][target].take(), class));
+                }
+            }
+            if row.accepting {
+                edges[index][end] = Some(Regex::Empty);
+            }
+        }
+        if let Some(index) = self.index_of(STARTING_STATE_ID) {
+            edges[start][index] = Some(Regex::Empty);
+        }
+
+        let mut remaining: Vec<usize> = (0..states).collect();
+        while !remaining.is_empty() {
+            let paths = |state: usize| {
+                let count = |edge: &dyn Fn(usize) -> bool| {
+                    (0..states + 2)
+                        .filter(|other| *other != state && edge(*other))
+                        .count()
+                };
+                count(&|other| edges[other][state].is_some())
+                    * count(&|other| edges[state][other].is_some())
+            };
+            let position = (0..remaining.len())
+                .min_by_key(|position| paths(remaining[*position]))
+                .unwrap();
+            let removed = remaining.remove(position);
+
+            // Route every path into the removed state through its loop to every state it leads to
+            let repeat = edges[removed][removed].take().map(star);
+            let outgoing = std::mem::replace(&mut edges[removed], vec![None; states + 2]);
+            for row in &mut edges {
+                let Some(into) = row[removed].take() else {
+                    continue;
+                };
+                for (to, out) in outgoing.iter().enumerate() {
+                    if let Some(out) = out {
+                        let path = concat(concat(into.clone(), repeat.clone()), Some(out.clone()));
+                        row[to] = Some(union(row[to].take(), path));
+                    }
+                }
+            }
+        }
+
+        Ok(edges[start][end]
+            .take()
+            .unwrap_or(Regex::Class(CharSet::default()))
+            .serialize())
+    }
+}
+
+/// Match either of two regexes, merging character classes and flattening alternations
+///
+/// An alternation with [`Regex::Empty`] becomes optional.
+fn union(left: Option<Regex>, right: Regex) -> Regex {
+    let Some(left) = left else {
+        return right;
+    };
+    match (left, right) {
+        (Regex::Class(left), Regex::Class(right)) => Regex::Class(left.union(&right)),
+        (Regex::Empty, regex) | (regex, Regex::Empty) => optional(regex),
+        (Regex::Optional(left), right) | (right, Regex::Optional(left)) => {
+            optional(union(Some(*left), right))
+        }
+        (left, right) => {
+            let mut branches = match left {
+                Regex::Alternate(branches) => branches,
+                left => vec![left],
+            };
+            let right = match right {
+                Regex::Alternate(branches) => branches,
+                right => vec![right],
+            };
+            for branch in right {
+                if !branches.contains(&branch) {
+                    branches.push(branch);
+                }
+            }
+            Regex::Alternate(branches)
+        }
+    }
+}
+
+/// Match a regex zero or one times
+fn optional(regex: Regex) -> Regex {
+    match regex {
+        Regex::Empty | Regex::Star(_) | Regex::Optional(_) => regex,
+        Regex::Plus(regex) => Regex::Star(regex),
+        regex => Regex::Optional(Box::new(regex)),
+    }
+}
+
+/// Match a regex any number of times
+fn star(regex: Regex) -> Regex {
+    match regex {
+        Regex::Empty => Regex::Empty,
+        Regex::Star(regex) | Regex::Plus(regex) | Regex::Optional(regex) => Regex::Star(regex),
+        regex => Regex::Star(Box::new(regex)),
+    }
+}
+
+/// Match a regex followed by an optional one, dropping empty parts and flattening concatenations
+fn concat(left: Regex, right: Option<Regex>) -> Regex {
+    let Some(right) = right else {
+        return left;
+    };
+    match (left, right) {
+        (Regex::Empty, regex) | (regex, Regex::Empty) => regex,
+        (left, right) => {
+            let mut parts = match left {
+                Regex::Concat(parts) => parts,
+                left => vec![left],
+            };
+            match right {
+                Regex::Concat(right) => parts.extend(right),
+                right => parts.push(right),
+            }
+            Regex::Concat(parts)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alphabet, Regex, ToRegexError, TransitionTable};
+
+    #[test]
+    fn to_regex() {
+        // Binary strings with an odd number of ones
+        let table = TransitionTable::parse("- 0 0 1\n+ 1 1 0\n").unwrap();
+        let regex = table.to_regex(&['0', '1']).unwrap();
+        assert_eq!(regex, "0*1(0|10*1)*");
+
+        let alphabet = Alphabet::from_chars("01".chars());
+        let compiled = Regex::parse(&regex).unwrap().compile(&alphabet).unwrap();
+        assert!(compiled.equivalent(&table).is_ok());
+
+        for input in ["- 0 E\n", "+ 0 E\n", "- 0 1 E\n+ 1 1 1\n- 2 0 2\n"] {
+            let table = TransitionTable::parse(input).unwrap();
+            let regex = Regex::parse(&table.to_regex(&['.', '|']).unwrap()).unwrap();
+            let alphabet = Alphabet::from_chars(".|".chars());
+            let compiled = regex.compile(&alphabet).unwrap();
+            assert!(compiled.equivalent(&table).is_ok(), "{}", input);
+        }
+
+        assert_eq!(
+            table.to_regex(&['0']),
+            Err(ToRegexError::MissingCharacter(1))
+        );
+    }
+}
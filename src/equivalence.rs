@@ -0,0 +1,134 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+
+/// An input two tables disagree on, from [`TransitionTable::equivalent`] or
+/// [`TransitionTable::is_subset_of`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("The tables disagree on the input {input:?}")]
+pub struct Counterexample {
+    /// A shortest input exactly one of the tables accepts
+    pub input: Vec<usize>,
+
+    /// Whether the table the check was called on is the one accepting the input
+    pub accepted_by_self: bool,
+}
+
+/// A union-find over the states of two tables, with path halving and union by size
+struct Classes {
+    /// The parent of every state, where a root is its own parent
+    parent: Vec<usize>,
+
+    /// The number of states under every root
+    size: Vec<usize>,
+}
+
+impl Classes {
+    /// Put each of `len` states in a class of its own
+    fn new(len: usize) -> Self {
+        Classes {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    /// Get the root of a state's class
+    fn find(&mut self, mut state: usize) -> usize {
+        while self.parent[state] != state {
+            self.parent[state] = self.parent[self.parent[state]];
+            state = self.parent[state];
+        }
+        state
+    }
+
+    /// Merge the classes of two states, returning false if they were already the same
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.size[a] < self.size[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+        self.parent[b] = a;
+        self.size[a] += self.size[b];
+        true
+    }
+}
+
+impl TransitionTable {
+    /// Check whether two tables accept the same language
+    ///
+    /// Uses Hopcroft and Karp's algorithm: starting from the pair of starting states, pairs assumed
+    /// equivalent are merged in a union-find and their successors on every column are merged in
+    /// turn, which takes nearly linear time in the size of the tables. The tables differ exactly
+    /// when some merged pair disagrees on acceptance. Only then is the product searched
+    /// breadth-first for a shortest input exactly one of them accepts, since the merging order
+    /// doesn't give shortest inputs. Both tables are completed with their sinks, and tables of
+    /// different widths are compared as if the narrower one rejected the extra columns.
+    pub fn equivalent(&self, other: &TransitionTable) -> Result<(), Counterexample> {
+        let width = self.width().max(other.width());
+        let start = |table: &TransitionTable| {
+            table
+                .index_of(STARTING_STATE_ID)
+                .unwrap_or(table.rows.len())
+        };
+        let accepting = |table: &TransitionTable, index: usize| {
+            table.rows.get(index).is_some_and(|row| row.accepting)
+        };
+
+        // States of `other` come after those of `self`, each table followed by its sink
+        let offset = self.rows.len() + 1;
+        let mut classes = Classes::new(offset + other.rows.len() + 1);
+        let start = (start(self), start(other));
+        classes.union(start.0, offset + start.1);
+        let mut pending = vec![start];
+        while let Some((a, b)) = pending.pop() {
+            if accepting(self, a) != accepting(other, b) {
+                let input = TransitionTable::shortest_divergence(self, other)
+                    .expect("tables that disagree on a pair have a divergence");
+                let accepted_by_self = self.accepts(&input);
+                return Err(Counterexample {
+                    input,
+                    accepted_by_self,
+                });
+            }
+
+            for symbol in 0..width {
+                let next = (self.delta(a, symbol), other.delta(b, symbol));
+                if classes.union(next.0, offset + next.1) {
+                    pending.push(next);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equivalent() {
+        // Inputs over a and b with an even number of b, written two ways
+        let reference = TransitionTable::parse("+ 0 0 1\n- 1 1 0\n").unwrap();
+        let redundant = TransitionTable::parse("+ 0 2 1\n- 1 1 0\n+ 2 2 1\n").unwrap();
+        assert_eq!(reference.equivalent(&redundant), Ok(()));
+
+        // A submission that forgets about b after an a
+        let submission = TransitionTable::parse("+ 0 2 1\n- 1 1 0\n+ 2 2 2\n").unwrap();
+        let counterexample = reference.equivalent(&submission).unwrap_err();
+        assert_eq!(counterexample.input, [0, 1]);
+        assert!(!counterexample.accepted_by_self);
+        assert!(
+            submission
+                .equivalent(&reference)
+                .unwrap_err()
+                .accepted_by_self
+        );
+        assert_eq!(
+            counterexample.to_string(),
+            "The tables disagree on the input [0, 1]"
+        );
+    }
+}
@@ -0,0 +1,518 @@
+use crate::binary::read_u64s;
+use crate::transition_table::{alphabet_line, doc_line, line_span, meta_line, parse_row};
+use crate::{
+    Alphabet, ParseError, ParseSerializeError, TransitionTable, TransitionTableRow,
+    STARTING_STATE_ID,
+};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// The default memory budget for external minimization, in bytes
+pub const DEFAULT_MEMORY_BUDGET: usize = 64 * 1024 * 1024;
+
+/// The marker for an error transition in the spilled transition file
+const SPILLED_DEAD: u64 = u64::MAX;
+
+/// Options for [`TransitionTable::minimize_external`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalMinimizeOptions {
+    /// The approximate number of bytes of transitions and signatures held in memory at once
+    pub memory_budget: usize,
+
+    /// The directory for temporary files, or `None` for the system temporary directory
+    pub temp_dir: Option<PathBuf>,
+}
+
+impl Default for ExternalMinimizeOptions {
+    fn default() -> Self {
+        ExternalMinimizeOptions {
+            memory_budget: DEFAULT_MEMORY_BUDGET,
+            temp_dir: None,
+        }
+    }
+}
+
+/// Errors that can occur during external minimization
+#[derive(Debug, thiserror::Error)]
+pub enum ExternalMinimizeError {
+    /// Reading the input or using the temporary files failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// A row of the input isn't valid
+    #[error("{0}")]
+    Syntax(#[from] ParseError),
+
+    /// The input isn't a valid transition table
+    #[error("{0}")]
+    Parse(#[from] ParseSerializeError),
+}
+
+impl TransitionTable {
+    /// Minimize the table stored in a file without loading its transitions into memory
+    ///
+    /// The transitions are spilled to a temporary binary file and the partition is refined with
+    /// Moore's algorithm, sorting each round's state signatures on disk in runs that fit in
+    /// `options.memory_budget`. A few words per state for the state IDs and the current partition,
+    /// plus the minimized result, are kept in memory regardless of the budget, as are the table's
+    /// header, metadata, comment, and any docs and labels of its rows. The file must be sorted by
+    /// state ID. The result is identical to [`TransitionTable::minimize`] on the parsed file.
+    pub fn minimize_external(
+        path: &Path,
+        options: &ExternalMinimizeOptions,
+    ) -> Result<TransitionTable, ExternalMinimizeError> {
+        let temp = TempDir::new(options.temp_dir.as_deref())?;
+        let spilled = Spilled::new(path, &temp)?;
+
+        let total = spilled.ids.len() + 1;
+        let stride = spilled.width + 1;
+        let chunk_states = (options.memory_budget / (2 * 8 * (stride + 1))).max(1);
+
+        // Start with the accepting and non-accepting states, with the sink not accepting
+        let mut block_of = (0..total)
+            .map(|index| u64::from(index < spilled.ids.len() && spilled.accepting[index]))
+            .collect::<Vec<_>>();
+        let mut block_count = 0;
+
+        loop {
+            // Write sorted runs of (signature, state) records
+            let mut runs = Vec::new();
+            let mut records = Vec::new();
+            let mut transitions = spilled.rows()?;
+            for index in 0..total {
+                let mut signature = Vec::with_capacity(stride);
+                signature.push(block_of[index]);
+                match transitions.next().transpose()? {
+                    Some(targets) => signature.extend(targets.iter().map(|target| {
+                        block_of[if *target == SPILLED_DEAD {
+                            total - 1
+                        } else {
+                            *target as usize
+                        }]
+                    })),
+                    None => signature.extend(std::iter::repeat_n(block_of[total - 1], stride - 1)),
+                }
+                records.push((signature, index as u64));
+
+                if records.len() == chunk_states || index == total - 1 {
+                    records.sort_unstable();
+                    runs.push(temp.write_run(runs.len(), &records)?);
+                    records.clear();
+                }
+            }
+
+            // Merge the runs, numbering the distinct signatures in sorted order
+            let mut readers = runs
+                .iter()
+                .map(|path| Ok(RunReader::new(File::open(path)?, stride)))
+                .collect::<Result<Vec<_>, std::io::Error>>()?;
+            let mut heap = BinaryHeap::new();
+            for (run, reader) in readers.iter_mut().enumerate() {
+                if let Some((signature, state)) = reader.next()? {
+                    heap.push(Reverse((signature, state, run)));
+                }
+            }
+
+            let mut next_block_of = vec![0; total];
+            let mut next_count = 0;
+            let mut previous: Option<Vec<u64>> = None;
+            while let Some(Reverse((signature, state, run))) = heap.pop() {
+                if previous.as_ref() != Some(&signature) {
+                    next_count += 1;
+                    previous = Some(signature);
+                }
+                next_block_of[state as usize] = next_count - 1;
+
+                if let Some((signature, state)) = readers[run].next()? {
+                    heap.push(Reverse((signature, state, run)));
+                }
+            }
+            for run in runs {
+                std::fs::remove_file(run)?;
+            }
+
+            // Refinement only ever splits blocks, so an unchanged count means a stable partition
+            block_of = next_block_of;
+            if next_count == block_count {
+                break;
+            }
+            block_count = next_count;
+        }
+
+        // Keep one representative row per block to build the result from
+        let mut seen = vec![false; block_count as usize];
+        let mut representatives = Vec::new();
+        for (index, targets) in spilled.rows()?.enumerate() {
+            let targets = targets?;
+            let block = block_of[index] as usize;
+            if !seen[block] {
+                seen[block] = true;
+                representatives.push((index, targets));
+            }
+        }
+
+        Ok(spilled.quotient(&representatives, &block_of))
+    }
+}
+
+/// A table spilled to a binary file of row-major transition targets, by row index
+struct Spilled {
+    /// The path of the transition file
+    path: PathBuf,
+
+    /// The state ID of every row, sorted
+    ids: Vec<usize>,
+
+    /// Whether every row is accepting
+    accepting: Vec<bool>,
+
+    /// The number of columns
+    width: usize,
+
+    /// The table's header
+    alphabet: Option<Alphabet>,
+
+    /// The table's metadata
+    metadata: BTreeMap<String, String>,
+
+    /// The table's comment
+    comment: Option<String>,
+
+    /// The doc and label of every row that has either, by row index
+    notes: BTreeMap<usize, (Option<String>, Option<String>)>,
+}
+
+impl Spilled {
+    /// Parse a table file into a transition file in `temp`
+    ///
+    /// The file is read twice: once to find the state IDs and everything but the transitions,
+    /// then once to write the transitions by row index.
+    fn new(path: &Path, temp: &TempDir) -> Result<Self, ExternalMinimizeError> {
+        let mut ids = Vec::new();
+        let mut accepting = Vec::new();
+        let mut expected_columns = None;
+        let mut doc = Vec::new();
+        let mut alphabet = None;
+        let mut metadata = BTreeMap::new();
+        let mut comment = None;
+        let mut notes = BTreeMap::new();
+        for (line_index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if let Some(text) = doc_line(&line) {
+                doc.push(text.to_string());
+                continue;
+            }
+
+            // Handle the lines between rows like `TransitionTable::parse`
+            if line.trim().is_empty() {
+                if !doc.is_empty() && expected_columns.is_none() && comment.is_none() {
+                    comment = Some(doc.join("\n"));
+                }
+                doc.clear();
+                continue;
+            }
+            if let Some(entry) = meta_line(&line) {
+                let (key, value) = entry.ok_or_else(|| ParseError::InvalidMeta {
+                    span: line_span(&line, line_index),
+                })?;
+                metadata.insert(key.to_string(), value.to_string());
+                continue;
+            }
+            if let Some(header) = alphabet_line(&line) {
+                let span = line_span(&line, line_index);
+                if alphabet.is_some() || expected_columns.is_some() {
+                    return Err(ParseError::MisplacedAlphabet { span }.into());
+                }
+                let parsed =
+                    Alphabet::parse(header).map_err(|error| ParseError::InvalidAlphabet {
+                        span,
+                        message: error.message,
+                    })?;
+                alphabet = Some((parsed, span));
+                continue;
+            }
+            let row = parse_row(&line, line_index, &mut expected_columns)?;
+            if ids.last().is_some_and(|last| *last >= row.id) {
+                return Err(ParseSerializeError {
+                    message: format!(
+                        "Line {} is not sorted by state ID, which external minimization requires",
+                        line_index + 1
+                    ),
+                }
+                .into());
+            }
+            let row_doc = (!doc.is_empty()).then(|| doc.join("\n"));
+            doc.clear();
+            if row_doc.is_some() || row.label.is_some() {
+                notes.insert(ids.len(), (row_doc, row.label));
+            }
+            ids.push(row.id);
+            accepting.push(row.accepting);
+        }
+        let width = expected_columns.map_or(0, |columns| columns - 2);
+        if let (Some((header, span)), Some(_)) = (&alphabet, expected_columns) {
+            if header.len() != width {
+                return Err(ParseError::AlphabetLength {
+                    span: *span,
+                    expected: width,
+                    found: header.len(),
+                }
+                .into());
+            }
+        }
+
+        let spilled_path = temp.path.join("transitions");
+        let mut writer = BufWriter::new(File::create(&spilled_path)?);
+        let mut expected_columns = None;
+        for (line_index, line) in BufReader::new(File::open(path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty()
+                || doc_line(&line).is_some()
+                || alphabet_line(&line).is_some()
+                || meta_line(&line).is_some()
+            {
+                continue;
+            }
+            let row = parse_row(&line, line_index, &mut expected_columns)?;
+            for transition in row.transitions {
+                let target = transition
+                    .and_then(|target| ids.binary_search(&target).ok())
+                    .map_or(SPILLED_DEAD, |index| index as u64);
+                writer.write_all(&target.to_le_bytes())?;
+            }
+        }
+        writer.flush()?;
+
+        Ok(Spilled {
+            path: spilled_path,
+            ids,
+            accepting,
+            width,
+            alphabet: alphabet.map(|(alphabet, _)| alphabet),
+            metadata,
+            comment,
+            notes,
+        })
+    }
+
+    /// Read the targets of every row in order
+    fn rows(&self) -> std::io::Result<impl Iterator<Item = std::io::Result<Vec<u64>>>> {
+        let mut reader = BufReader::new(File::open(&self.path)?);
+        let width = self.width;
+        let mut remaining = self.ids.len();
+
+        Ok(std::iter::from_fn(move || {
+            if remaining == 0 {
+                return None;
+            }
+            remaining -= 1;
+            Some(read_u64s(&mut reader, width))
+        }))
+    }
+
+    /// Build the minimized table from one representative row of each block
+    fn quotient(&self, representatives: &[(usize, Vec<u64>)], block_of: &[u64]) -> TransitionTable {
+        let sink = self.ids.len();
+        let sink_block = block_of[sink] as usize;
+        let start = self
+            .ids
+            .binary_search(&STARTING_STATE_ID)
+            .ok()
+            .map(|index| block_of[index] as usize)
+            .filter(|block| *block != sink_block);
+        let Some(start) = start else {
+            return TransitionTable {
+                alphabet: self.alphabet.clone(),
+                metadata: self.metadata.clone(),
+                comment: self.comment.clone(),
+                ..TransitionTable::empty_language(self.width)
+            };
+        };
+
+        // Number the states by block, with transitions to the sink's block as errors, keeping
+        // the doc and label of blocks with a single state
+        let mut sizes = vec![0; block_of.len()];
+        for block in &block_of[..sink] {
+            sizes[*block as usize] += 1;
+        }
+        let mut rows = representatives
+            .iter()
+            .map(|(index, targets)| {
+                let (doc, label) = self
+                    .notes
+                    .get(index)
+                    .filter(|_| sizes[block_of[*index] as usize] == 1)
+                    .cloned()
+                    .unwrap_or_default();
+                TransitionTableRow {
+                    accepting: self.accepting[*index],
+                    id: block_of[*index] as usize,
+                    transitions: targets
+                        .iter()
+                        .map(|target| {
+                            let target = if *target == SPILLED_DEAD {
+                                sink
+                            } else {
+                                *target as usize
+                            };
+                            Some(block_of[target] as usize).filter(|block| *block != sink_block)
+                        })
+                        .collect(),
+                    doc,
+                    label,
+                }
+            })
+            .filter(|row| row.id != sink_block)
+            .collect::<Vec<_>>();
+        rows.sort_by_key(|row| row.id);
+
+        TransitionTable {
+            rows,
+            alphabet: self.alphabet.clone(),
+            metadata: self.metadata.clone(),
+            comment: self.comment.clone(),
+        }
+        .renumber_reachable(start)
+        .0
+    }
+}
+
+/// A temporary directory, removed with its contents when dropped
+struct TempDir {
+    /// The directory's path
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create a uniquely named directory inside `parent`, or the system temporary directory
+    fn new(parent: Option<&Path>) -> std::io::Result<Self> {
+        let parent = parent.map_or_else(std::env::temp_dir, Path::to_path_buf);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.subsec_nanos());
+
+        for attempt in 0.. {
+            let path = parent.join(format!(
+                "transition-tables-{}-{}-{}",
+                std::process::id(),
+                nanos,
+                attempt
+            ));
+            match std::fs::create_dir(&path) {
+                Ok(()) => return Ok(TempDir { path }),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        unreachable!()
+    }
+
+    /// Write a sorted run of signature records to a new file
+    fn write_run(&self, run: usize, records: &[(Vec<u64>, u64)]) -> std::io::Result<PathBuf> {
+        let path = self.path.join(format!("run-{}", run));
+        let mut writer = BufWriter::new(File::create(&path)?);
+        for (signature, state) in records {
+            for word in signature.iter().chain([state]) {
+                writer.write_all(&word.to_le_bytes())?;
+            }
+        }
+        writer.flush()?;
+        Ok(path)
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A reader of the records in a sorted run
+struct RunReader {
+    /// The run file
+    reader: BufReader<File>,
+
+    /// The number of words in a signature
+    stride: usize,
+}
+
+impl RunReader {
+    fn new(file: File, stride: usize) -> Self {
+        RunReader {
+            reader: BufReader::new(file),
+            stride,
+        }
+    }
+
+    /// Read the next record, or `None` at the end of the run
+    fn next(&mut self) -> std::io::Result<Option<(Vec<u64>, u64)>> {
+        if self.reader.fill_buf()?.is_empty() {
+            return Ok(None);
+        }
+
+        let mut words = read_u64s(&mut self.reader, self.stride + 1)?;
+        let state = words.pop().unwrap();
+        Ok(Some((words, state)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimize_external() -> Result<(), ExternalMinimizeError> {
+        let input = "- 0 1 2\n- 1 3 4\n- 2 3 4\n+ 3 3 3\n- 4 4 4\n+ 5 0 0\n- 6 6 0\n";
+        let temp = TempDir::new(None)?;
+        let path = temp.path.join("table.tt");
+        std::fs::write(&path, input)?;
+
+        let expected = TransitionTable::parse(input)?.minimize();
+        for memory_budget in [0, 100, DEFAULT_MEMORY_BUDGET] {
+            let options = ExternalMinimizeOptions {
+                memory_budget,
+                temp_dir: Some(temp.path.clone()),
+            };
+            assert_eq!(
+                TransitionTable::minimize_external(&path, &options)?,
+                expected
+            );
+        }
+
+        // Only the table file is left behind
+        assert_eq!(std::fs::read_dir(&temp.path)?.count(), 1);
+
+        // The header, metadata, comment, and unmerged states' docs and labels are kept
+        let input =
+            "# A table\n\n%meta author someone\nalphabet: [a] [b]\n# Start\n- 0 start 1 2\n\
+                     - 1 one 3 3\n- 2 3 3\n# End\n+ 3 end 3 3\n";
+        std::fs::write(&path, input)?;
+        let minimized =
+            TransitionTable::minimize_external(&path, &ExternalMinimizeOptions::default())?;
+        assert_eq!(minimized, TransitionTable::parse(input)?.minimize());
+        assert_eq!(
+            minimized.serialize(),
+            "# A table\n\n%meta author someone\nalphabet: [a] [b]\n# Start\n- 0 start 1 1\n\
+             - 1 2 2\n# End\n+ 2 end 2 2\n"
+        );
+
+        // So is a header on the empty language
+        std::fs::write(&path, "alphabet: [a]\n- 0 0\n")?;
+        assert_eq!(
+            TransitionTable::minimize_external(&path, &ExternalMinimizeOptions::default())?
+                .serialize(),
+            "alphabet: [a]\n- 0 E\n"
+        );
+
+        std::fs::write(&path, "- 1 0\n- 0 1\n")?;
+        assert!(matches!(
+            TransitionTable::minimize_external(&path, &ExternalMinimizeOptions::default()),
+            Err(ExternalMinimizeError::Parse(_))
+        ));
+
+        Ok(())
+    }
+}
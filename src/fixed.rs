@@ -0,0 +1,177 @@
+use crate::{Automaton, TransitionTable, TransitionTableRow};
+use std::borrow::Cow;
+
+/// A row of a [`FixedTable`] with `W` columns
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedRow<const W: usize> {
+    /// Whether the row is accepting
+    pub accepting: bool,
+
+    /// The target row index of each column, or `None` for an error transition
+    pub transitions: [Option<usize>; W],
+}
+
+/// A table whose width is the compile-time constant `W`
+///
+/// Rows are arrays, so a table can be written as a constant and embedded in a `static`, with its
+/// targets checked at compile time:
+///
+/// ```
+/// use transition_tables::{Automaton, FixedRow, FixedTable};
+///
+/// static EVEN_ONES: FixedTable<'static, 2> = FixedTable::from_rows(&[
+///     FixedRow { accepting: true, transitions: [Some(0), Some(1)] },
+///     FixedRow { accepting: false, transitions: [Some(1), Some(0)] },
+/// ]);
+///
+/// assert!(EVEN_ONES.accepts(&[1, 0, 1]));
+/// ```
+///
+/// States are row indices, and row 0 is the starting state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FixedTable<'a, const W: usize> {
+    /// The rows, by state ID
+    rows: Cow<'a, [FixedRow<W>]>,
+}
+
+impl<'a, const W: usize> FixedTable<'a, W> {
+    /// Create a table from borrowed rows
+    ///
+    /// Panics if a target is not a row index, which fails compilation when used in a constant.
+    pub const fn from_rows(rows: &'a [FixedRow<W>]) -> Self {
+        let mut index = 0;
+        while index < rows.len() {
+            let mut symbol = 0;
+            while symbol < W {
+                if let Some(target) = rows[index].transitions[symbol] {
+                    assert!(
+                        target < rows.len(),
+                        "transition to a row that does not exist"
+                    );
+                }
+                symbol += 1;
+            }
+            index += 1;
+        }
+
+        FixedTable {
+            rows: Cow::Borrowed(rows),
+        }
+    }
+
+    /// Get the rows
+    pub fn rows(&self) -> &[FixedRow<W>] {
+        &self.rows
+    }
+
+    /// Get the state reached from a row index on a column
+    ///
+    /// Panics if the state does not exist.
+    #[inline]
+    pub fn next(&self, state: usize, symbol: usize) -> Option<usize> {
+        *self.rows[state].transitions.get(symbol)?
+    }
+
+    /// Copy the table into a [`TransitionTable`] whose state IDs are the row indices
+    pub fn to_table(&self) -> TransitionTable {
+        let rows = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(id, row)| TransitionTableRow {
+                accepting: row.accepting,
+                id,
+                transitions: row.transitions.to_vec(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+}
+
+impl<const W: usize> FixedTable<'static, W> {
+    /// Convert a table with at most `W` columns, renumbering states by row index
+    ///
+    /// Narrower rows are padded with error transitions, and transitions to states that do not
+    /// exist become error transitions. Returns `None` if the table is too wide or the starting
+    /// state does not exist.
+    pub fn from_table(table: &TransitionTable) -> Option<Self> {
+        if table.width() > W || table.index_of(crate::STARTING_STATE_ID) != Some(0) {
+            return None;
+        }
+
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| {
+                let mut transitions = [None; W];
+                for (slot, target) in transitions.iter_mut().zip(&row.transitions) {
+                    *slot = target.and_then(|target| table.index_of(target));
+                }
+                FixedRow {
+                    accepting: row.accepting,
+                    transitions,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Some(FixedTable {
+            rows: Cow::Owned(rows),
+        })
+    }
+}
+
+impl<const W: usize> Automaton for FixedTable<'_, W> {
+    /// The row index
+    type State = usize;
+
+    fn start(&self) -> Option<usize> {
+        (!self.rows.is_empty()).then_some(0)
+    }
+
+    fn is_accepting(&self, state: usize) -> bool {
+        self.rows[state].accepting
+    }
+
+    #[inline]
+    fn next_state(&self, state: usize, symbol: usize) -> Option<usize> {
+        self.next(state, symbol)
+    }
+
+    fn width(&self) -> usize {
+        W
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn fixed_table() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 4 4 E\n- 1 E 4\n")?;
+        let fixed = FixedTable::<3>::from_table(&table).unwrap();
+
+        assert_eq!(fixed.rows().len(), 3);
+        assert_eq!(fixed.next(0, 0), Some(1));
+        assert_eq!(fixed.next(0, 3), None);
+        assert!(fixed.accepts([0, 1, 0]));
+        assert_eq!(
+            fixed.to_table(),
+            TransitionTable::parse("- 0 1 E E\n- 1 E 2 E\n+ 2 2 E E\n")?
+        );
+
+        assert!(FixedTable::<1>::from_table(&table).is_none());
+        assert!(FixedTable::<2>::from_table(&TransitionTable::parse("+ 1 1\n")?).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{Alphabet, TransitionTable, STARTING_STATE_ID};
+
+/// The index used for error transitions in a [`FlatTable`]
+pub(crate) const DEAD: usize = usize::MAX;
+
+/// A transition table stored as one contiguous array indexed by row index and column
+///
+/// States are identified by row index rather than ID, and error transitions, including those to
+/// states that don't exist, enter [`DEAD`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct FlatTable {
+    /// The target of every transition, in row-major order
+    pub transitions: Vec<usize>,
+
+    /// Whether each row is accepting
+    pub accepting: Vec<bool>,
+
+    /// The number of columns
+    pub width: usize,
+
+    /// The index of the starting state, or [`DEAD`] if it doesn't exist
+    pub start: usize,
+}
+
+impl FlatTable {
+    /// Flatten a table
+    pub fn new(table: &TransitionTable) -> Self {
+        let width = table.width();
+        let mut transitions = Vec::with_capacity(width * table.rows.len());
+        for row in &table.rows {
+            transitions.extend((0..width).map(|symbol| {
+                row.transitions
+                    .get(symbol)
+                    .copied()
+                    .flatten()
+                    .and_then(|target| table.index_of(target))
+                    .unwrap_or(DEAD)
+            }));
+        }
+
+        FlatTable {
+            transitions,
+            accepting: table.rows.iter().map(|row| row.accepting).collect(),
+            width,
+            start: table.index_of(STARTING_STATE_ID).unwrap_or(DEAD),
+        }
+    }
+
+    /// Get the index reached from a row index on a column
+    #[inline]
+    pub fn next(&self, index: usize, symbol: usize) -> usize {
+        if index == DEAD || symbol >= self.width {
+            return DEAD;
+        }
+        self.transitions[index * self.width + symbol]
+    }
+
+    /// Check whether a row index is accepting
+    #[inline]
+    pub fn is_accepting(&self, index: usize) -> bool {
+        index != DEAD && self.accepting[index]
+    }
+}
+
+/// A cache of the columns of ASCII characters, falling back to the alphabet for others
+pub(crate) struct AsciiClassifier<'a> {
+    /// The column of every ASCII character
+    ascii: [Option<usize>; 128],
+
+    /// The alphabet for non-ASCII characters
+    alphabet: &'a Alphabet,
+}
+
+impl<'a> AsciiClassifier<'a> {
+    /// Build the cache for an alphabet
+    pub fn new(alphabet: &'a Alphabet) -> Self {
+        let mut ascii = [None; 128];
+        for (byte, column) in ascii.iter_mut().enumerate() {
+            *column = alphabet.classify(byte as u8 as char);
+        }
+
+        AsciiClassifier { ascii, alphabet }
+    }
+
+    /// Get the column of a character
+    #[inline]
+    pub fn classify(&self, c: char) -> Option<usize> {
+        match self.ascii.get(c as usize) {
+            Some(column) => *column,
+            None => self.alphabet.classify(c),
+        }
+    }
+}
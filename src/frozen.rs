@@ -0,0 +1,604 @@
+use crate::binary::{BinaryFormatError, Header, BINARY_DEAD, HEADER_SIZE};
+use crate::{BitSet, BuildError, TransitionTable, BINARY_MAGIC, BINARY_VERSION, STARTING_STATE_ID};
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// An immutable table in the binary format, either owned or memory-mapped from a file
+///
+/// The in-memory layout is exactly the layout written by [`TransitionTable::write_binary`], so a
+/// table file can be used in place: loading it is a memory map plus validation of the header and
+/// file size, taking constant time however large the table is. States are addressed by row index
+/// for matching, with the starting state at [`FrozenTable::start`].
+///
+/// Rows that are not accepting and can never reach an accepting state are traps, which
+/// [`FrozenTable::accepts`] stops at as soon as it enters one, rather than reading the rest of the
+/// input.
+#[derive(Debug)]
+pub struct FrozenTable {
+    /// The words of the binary format
+    storage: Storage,
+
+    /// The file's header
+    header: Header,
+
+    /// The number of rows
+    row_count: usize,
+
+    /// The number of columns
+    width: usize,
+
+    /// The flags of every row, found when the table is frozen, or on first use for a
+    /// memory-mapped table
+    flags: OnceLock<RowFlags>,
+}
+
+/// Bit sets of row indices, letting hot loops test a row with a single bit test
+#[derive(Debug)]
+struct RowFlags {
+    /// The accepting rows
+    accepting: BitSet,
+
+    /// The trap rows
+    traps: BitSet,
+
+    /// Whether the state IDs are strictly increasing, so lookups can binary search them
+    sorted: bool,
+
+    /// The row index of the starting state, if it exists
+    start: Option<usize>,
+}
+
+impl RowFlags {
+    /// Test a bit of a bit set whose padding bits are clear, so rows past the end aren't set
+    #[inline]
+    fn test(bits: &BitSet, index: usize) -> bool {
+        let word = bits.as_words().get(index / 64).copied().unwrap_or(0);
+        word >> (index % 64) & 1 != 0
+    }
+}
+
+/// The memory holding a [`FrozenTable`]
+#[derive(Debug)]
+enum Storage {
+    /// Words owned by the table
+    Owned(Vec<u64>),
+
+    /// A read-only memory map of a file
+    #[cfg(all(unix, target_pointer_width = "64", target_endian = "little"))]
+    Mapped(mmap::Mmap),
+}
+
+impl Storage {
+    fn words(&self) -> &[u64] {
+        match self {
+            Storage::Owned(words) => words,
+            #[cfg(all(unix, target_pointer_width = "64", target_endian = "little"))]
+            Storage::Mapped(map) => map.words(),
+        }
+    }
+}
+
+impl FrozenTable {
+    /// Freeze a table into the binary layout in memory
+    pub fn from_table(table: &TransitionTable) -> Self {
+        let mut bytes = Vec::new();
+        table.write_binary(&mut bytes).unwrap();
+        FrozenTable::from_bytes(&bytes).unwrap()
+    }
+
+    /// Decode a table from bytes in the binary format, copying them
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, BinaryFormatError> {
+        let words = bytes
+            .chunks(8)
+            .map(|chunk| {
+                let mut word = [0; 8];
+                word[..chunk.len()].copy_from_slice(chunk);
+                u64::from_le_bytes(word)
+            })
+            .collect::<Vec<_>>();
+        FrozenTable::new(Storage::Owned(words), bytes)
+    }
+
+    /// Read a binary table file into memory
+    pub fn read(path: &Path) -> Result<Self, BinaryFormatError> {
+        FrozenTable::from_bytes(&std::fs::read(path)?)
+    }
+
+    /// Memory-map a binary table file
+    ///
+    /// Only the header and file size are checked, so this takes constant time, and trap rows are
+    /// found on first use instead. So is whether the state IDs are sorted, which
+    /// [`FrozenTable::validate`] reports; until then lookups in a file with unsorted IDs fall back
+    /// to linear scans rather than giving wrong rows. On platforms without memory mapping support,
+    /// this reads the file like [`FrozenTable::read`].
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified while the table is alive, since that would change memory the
+    /// table is reading from.
+    pub unsafe fn load(path: &Path) -> Result<Self, BinaryFormatError> {
+        #[cfg(all(unix, target_pointer_width = "64", target_endian = "little"))]
+        {
+            let map = mmap::Mmap::open(path)?;
+            let bytes = map.bytes();
+            let header = Header::parse(bytes)?;
+            if header.file_size() != Some(bytes.len() as u64) {
+                return Err(BinaryFormatError::Invalid(
+                    "file size doesn't match header".to_string(),
+                ));
+            }
+            Ok(FrozenTable::with_header(Storage::Mapped(map), header))
+        }
+
+        #[cfg(not(all(unix, target_pointer_width = "64", target_endian = "little")))]
+        FrozenTable::read(path)
+    }
+
+    /// Validate the header of the bytes a table's words were decoded from
+    fn new(storage: Storage, bytes: &[u8]) -> Result<Self, BinaryFormatError> {
+        let header = Header::parse(bytes)?;
+        if header.file_size() != Some(bytes.len() as u64) {
+            return Err(BinaryFormatError::Invalid(
+                "size doesn't match header".to_string(),
+            ));
+        }
+        let table = FrozenTable::with_header(storage, header);
+        table.validate()?;
+        Ok(table)
+    }
+
+    /// Create a table from storage of the size `header` describes
+    fn with_header(storage: Storage, header: Header) -> Self {
+        FrozenTable {
+            storage,
+            header,
+            row_count: header.row_count as usize,
+            width: header.width as usize,
+            flags: OnceLock::new(),
+        }
+    }
+
+    /// Check that the state IDs are sorted, like [`TransitionTable::read_binary`] does
+    ///
+    /// Tables read into memory are checked when they are created, but memory-mapped tables are
+    /// only checked on first use, so this reports a bad file loaded by [`FrozenTable::load`].
+    pub fn validate(&self) -> Result<(), BinaryFormatError> {
+        if !self.flags().sorted {
+            return Err(BinaryFormatError::Invalid(
+                "state IDs are not sorted".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Get the number of columns
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Get the number of rows
+    pub fn row_count(&self) -> usize {
+        self.row_count
+    }
+
+    /// Get the row index of the starting state, if it exists
+    pub fn start(&self) -> Option<usize> {
+        self.flags().start
+    }
+
+    /// Get the state ID of every row
+    pub fn ids(&self) -> &[u64] {
+        let start = HEADER_SIZE as usize / 8;
+        &self.storage.words()[start..start + self.row_count]
+    }
+
+    /// Get the state ID of a row
+    pub fn id(&self, index: usize) -> Option<usize> {
+        self.ids().get(index).map(|id| *id as usize)
+    }
+
+    /// Get the row index of the state with the given ID
+    pub fn index_of(&self, id: usize) -> Option<usize> {
+        find_id(self.ids(), self.flags().sorted, id)
+    }
+
+    /// Check whether a row is accepting
+    #[inline]
+    pub fn is_accepting(&self, index: usize) -> bool {
+        RowFlags::test(&self.flags().accepting, index)
+    }
+
+    /// Check whether a row is a trap, which is not accepting and can never reach an accepting row
+    #[inline]
+    pub fn is_trap(&self, index: usize) -> bool {
+        RowFlags::test(&self.flags().traps, index)
+    }
+
+    /// Get the flags of every row, finding them if this is the first use
+    fn flags(&self) -> &RowFlags {
+        self.flags.get_or_init(|| {
+            let start = HEADER_SIZE as usize / 8 + self.row_count;
+            let accepting: BitSet = self.storage.words()[start..start + self.row_count]
+                .iter()
+                .map(|accepting| *accepting != 0)
+                .collect();
+
+            // The rows entering each row
+            let mut inverse = vec![Vec::new(); self.row_count];
+            for index in 0..self.row_count {
+                for symbol in 0..self.width {
+                    if let Some(target) = self.next(index, symbol) {
+                        inverse[target].push(index);
+                    }
+                }
+            }
+
+            let mut live = accepting.clone();
+            let mut pending: Vec<usize> = accepting.iter_ones().collect();
+            while let Some(index) = pending.pop() {
+                for source in &inverse[index] {
+                    if !live.get(*source) {
+                        live.set(*source, true);
+                        pending.push(*source);
+                    }
+                }
+            }
+            let traps = (0..self.row_count).map(|index| !live.get(index)).collect();
+
+            let ids = self.ids();
+            let sorted = ids.windows(2).all(|pair| pair[0] < pair[1]);
+            RowFlags {
+                accepting,
+                traps,
+                sorted,
+                start: find_id(ids, sorted, STARTING_STATE_ID),
+            }
+        })
+    }
+
+    /// Get the row index reached from a row index on the given column
+    ///
+    /// Returns `None` for error transitions, and when the row or column does not exist.
+    #[inline]
+    pub fn next(&self, index: usize, symbol: usize) -> Option<usize> {
+        if index >= self.row_count || symbol >= self.width {
+            return None;
+        }
+
+        let offset = (self.header.transitions_offset() / 8) as usize;
+        match self.storage.words()[offset + index * self.width + symbol] {
+            BINARY_DEAD => None,
+            target if (target as usize) < self.row_count => Some(target as usize),
+            _ => None,
+        }
+    }
+
+    /// Check whether the table accepts a sequence of columns
+    ///
+    /// Stops as soon as the run enters a trap row.
+    pub fn accepts(&self, input: &[usize]) -> bool {
+        let flags = self.flags();
+        let Some(mut index) = flags.start else {
+            return false;
+        };
+        for symbol in input {
+            match self.next(index, *symbol) {
+                Some(next) if !RowFlags::test(&flags.traps, next) => index = next,
+                _ => return false,
+            }
+        }
+        RowFlags::test(&flags.accepting, index)
+    }
+
+    /// Copy the table into an editable [`TransitionTable`]
+    pub fn to_table(&self) -> TransitionTable {
+        let rows = (0..self.row_count)
+            .map(|index| crate::TransitionTableRow {
+                accepting: self.is_accepting(index),
+                id: self.id(index).unwrap(),
+                transitions: (0..self.width)
+                    .map(|symbol| self.next(index, symbol).and_then(|target| self.id(target)))
+                    .collect(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+}
+
+/// Find the row index of a state ID, binary searching the IDs only if they are sorted
+fn find_id(ids: &[u64], sorted: bool, id: usize) -> Option<usize> {
+    let id = id as u64;
+    if sorted {
+        ids.binary_search(&id).ok()
+    } else {
+        ids.iter().position(|other| *other == id)
+    }
+}
+
+/// A bulk builder writing rows straight into the flat layout of a [`FrozenTable`]
+///
+/// Rows are appended in order and their state IDs are their row indices, so building a table
+/// with millions of rows takes three growing buffers rather than an allocation per row. Reserve
+/// them up front with [`FrozenTableBuilder::with_capacity`] to avoid reallocation entirely.
+#[derive(Clone, Debug, Default)]
+pub struct FrozenTableBuilder {
+    /// The number of columns
+    width: usize,
+
+    /// Whether each row is accepting, as a word
+    accepting: Vec<u64>,
+
+    /// The target row index of every transition, in row-major order
+    transitions: Vec<u64>,
+}
+
+impl FrozenTableBuilder {
+    /// Create a builder for rows with the given number of columns
+    pub fn new(width: usize) -> Self {
+        FrozenTableBuilder::with_capacity(width, 0)
+    }
+
+    /// Create a builder with room for `rows` rows of `width` columns
+    pub fn with_capacity(width: usize, rows: usize) -> Self {
+        FrozenTableBuilder {
+            width,
+            accepting: Vec::with_capacity(rows),
+            transitions: Vec::with_capacity(rows * width),
+        }
+    }
+
+    /// Get the number of rows added
+    pub fn len(&self) -> usize {
+        self.accepting.len()
+    }
+
+    /// Check whether no rows have been added
+    pub fn is_empty(&self) -> bool {
+        self.accepting.is_empty()
+    }
+
+    /// Append a row, returning its row index, which is also its state ID
+    ///
+    /// Targets are row indices and may refer to rows not added yet. Missing trailing transitions
+    /// are error transitions. Fails without adding the row if it has more than `width` columns.
+    pub fn push_row(
+        &mut self,
+        accepting: bool,
+        transitions: impl IntoIterator<Item = Option<usize>>,
+    ) -> Result<usize, BuildError> {
+        let start = self.transitions.len();
+        for (symbol, target) in transitions.into_iter().enumerate() {
+            if symbol >= self.width {
+                self.transitions.truncate(start);
+                return Err(BuildError::ColumnOutOfRange {
+                    symbol,
+                    width: self.width,
+                });
+            }
+            self.transitions
+                .push(target.map_or(BINARY_DEAD, |target| target as u64));
+        }
+        self.transitions.resize(start + self.width, BINARY_DEAD);
+
+        self.accepting.push(u64::from(accepting));
+        Ok(self.accepting.len() - 1)
+    }
+
+    /// Check that every target exists and assemble the table
+    ///
+    /// The starting state is the first row, if there is one.
+    pub fn finish(self) -> Result<FrozenTable, BuildError> {
+        let row_count = self.accepting.len();
+        if let Some(missing) = self
+            .transitions
+            .iter()
+            .find(|target| **target != BINARY_DEAD && **target >= row_count as u64)
+        {
+            return Err(BuildError::MissingState(*missing as usize));
+        }
+
+        let mut magic = [0; 8];
+        magic[..4].copy_from_slice(&BINARY_MAGIC);
+        magic[4..].copy_from_slice(&BINARY_VERSION.to_le_bytes());
+
+        let header = Header {
+            width: self.width as u64,
+            row_count: row_count as u64,
+        };
+        let mut words =
+            Vec::with_capacity(HEADER_SIZE as usize / 8 + 2 * row_count + self.transitions.len());
+        words.extend([u64::from_le_bytes(magic), header.width, header.row_count]);
+        words.extend(0..row_count as u64);
+        words.extend(self.accepting);
+        words.extend(self.transitions);
+
+        let table = FrozenTable::with_header(Storage::Owned(words), header);
+        table.flags();
+        Ok(table)
+    }
+}
+
+/// Read-only memory maps through the C library, which the standard library already links
+#[cfg(all(unix, target_pointer_width = "64", target_endian = "little"))]
+mod mmap {
+    use std::ffi::c_void;
+    use std::os::fd::AsRawFd;
+    use std::path::Path;
+
+    /// Map pages for reading
+    const PROT_READ: i32 = 1;
+
+    /// Keep changes to the mapping private
+    const MAP_PRIVATE: i32 = 2;
+
+    extern "C" {
+        fn mmap(
+            addr: *mut c_void,
+            length: usize,
+            prot: i32,
+            flags: i32,
+            fd: i32,
+            offset: i64,
+        ) -> *mut c_void;
+        fn munmap(addr: *mut c_void, length: usize) -> i32;
+    }
+
+    /// A read-only memory map of a whole file
+    #[derive(Debug)]
+    pub struct Mmap {
+        /// The start of the mapping, which is page-aligned
+        pointer: *mut c_void,
+
+        /// The length of the mapping in bytes
+        length: usize,
+    }
+
+    // The mapping is read-only, so sharing it across threads is like sharing a `&[u8]`
+    unsafe impl Send for Mmap {}
+    unsafe impl Sync for Mmap {}
+
+    impl Mmap {
+        /// Map a file
+        pub fn open(path: &Path) -> std::io::Result<Self> {
+            let file = std::fs::File::open(path)?;
+            let length = usize::try_from(file.metadata()?.len()).map_err(std::io::Error::other)?;
+            if length == 0 {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "can't map an empty file",
+                ));
+            }
+
+            // SAFETY: the arguments describe a valid read-only mapping of an open file, and the
+            // result is checked for failure before use
+            let pointer = unsafe {
+                mmap(
+                    std::ptr::null_mut(),
+                    length,
+                    PROT_READ,
+                    MAP_PRIVATE,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            if pointer as isize == -1 {
+                return Err(std::io::Error::last_os_error());
+            }
+
+            Ok(Mmap { pointer, length })
+        }
+
+        /// Get the mapped bytes
+        pub fn bytes(&self) -> &[u8] {
+            // SAFETY: the mapping is `length` readable bytes for as long as `self` is alive
+            unsafe { std::slice::from_raw_parts(self.pointer as *const u8, self.length) }
+        }
+
+        /// Get the mapped bytes as whole words, ignoring any trailing partial word
+        pub fn words(&self) -> &[u64] {
+            // SAFETY: mappings are page-aligned, so the pointer is aligned for `u64`, and the
+            // length is rounded down to whole words
+            unsafe { std::slice::from_raw_parts(self.pointer as *const u64, self.length / 8) }
+        }
+    }
+
+    impl Drop for Mmap {
+        fn drop(&mut self) {
+            // SAFETY: the mapping was created by `mmap` with this length and is unmapped once
+            unsafe {
+                munmap(self.pointer, self.length);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frozen_table() -> Result<(), BinaryFormatError> {
+        let table = TransitionTable::parse("- 3 0 E\n- 0 3 5\n+ 5 5 E\n").unwrap();
+        let frozen = FrozenTable::from_table(&table);
+
+        assert_eq!(frozen.row_count(), 3);
+        assert_eq!(frozen.width(), 2);
+        assert_eq!(frozen.start(), Some(0));
+        assert!(frozen.accepts(&[1, 0]));
+        assert!(frozen.accepts(&[0, 0, 1]));
+        assert!(!frozen.accepts(&[0, 1]));
+        assert!(frozen.is_accepting(2));
+        assert!(!frozen.is_accepting(3) && !frozen.is_accepting(64));
+        assert_eq!(frozen.to_table(), table);
+
+        let path = std::env::temp_dir().join(format!("frozen-table-{}.ttb", std::process::id()));
+        table.write_binary(&mut std::fs::File::create(&path)?)?;
+
+        // SAFETY: the file isn't modified until the table is dropped
+        let loaded = unsafe { FrozenTable::load(&path)? };
+        assert_eq!(loaded.to_table(), table);
+        assert!(loaded.accepts(&[1, 0, 0]));
+        drop(loaded);
+
+        // Unsorted state IDs are rejected, or found on first use of a mapped table
+        let mut bytes = Vec::new();
+        table.write_binary(&mut bytes)?;
+        let ids = HEADER_SIZE as usize..HEADER_SIZE as usize + 16;
+        bytes[ids].rotate_left(8);
+        assert!(matches!(
+            FrozenTable::from_bytes(&bytes),
+            Err(BinaryFormatError::Invalid(_))
+        ));
+        std::fs::write(&path, &bytes)?;
+        let unsorted = unsafe { FrozenTable::load(&path)? };
+        assert!(unsorted.validate().is_err());
+        assert_eq!(unsorted.index_of(3), Some(0));
+        assert_eq!(unsorted.start(), Some(1));
+        drop(unsorted);
+
+        std::fs::write(&path, b"TTBL\x01\0\0\0")?;
+        assert!(unsafe { FrozenTable::load(&path) }.is_err());
+        std::fs::remove_file(&path)?;
+
+        // Build the flat layout directly
+        let mut builder = FrozenTableBuilder::with_capacity(2, 3);
+        builder.push_row(false, [Some(1), None]).unwrap();
+        builder.push_row(false, [Some(0), Some(2)]).unwrap();
+        builder.push_row(true, [Some(2)]).unwrap();
+        assert!(builder.push_row(true, [None; 3]).is_err());
+        assert_eq!(builder.len(), 3);
+        let built = builder.finish().unwrap();
+        assert_eq!(
+            built.to_table(),
+            TransitionTable::parse("- 0 1 E\n- 1 0 2\n+ 2 2 E\n").unwrap()
+        );
+        assert!(built.accepts(&[0, 1]));
+
+        // Row 1 and the rows it enters can never accept
+        let trapped = FrozenTable::from_table(
+            &TransitionTable::parse("- 0 1 3\n- 1 2 1\n- 2 1 E\n+ 3 0 E\n").unwrap(),
+        );
+        assert_eq!(
+            (0..4)
+                .map(|index| trapped.is_trap(index))
+                .collect::<Vec<_>>(),
+            vec![false, true, true, false]
+        );
+        assert!(!trapped.accepts(&[0; 1000]));
+        assert!(trapped.accepts(&[1, 0, 1]));
+
+        let mut dangling = FrozenTableBuilder::new(1);
+        dangling.push_row(true, [Some(1)]).unwrap();
+        assert_eq!(dangling.finish().unwrap_err(), BuildError::MissingState(1));
+
+        Ok(())
+    }
+}
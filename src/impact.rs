@@ -0,0 +1,243 @@
+use crate::{BuildError, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::{HashMap, VecDeque};
+
+/// An edit to a table, for previewing with [`TransitionTable::impact_of`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TableEdit {
+    /// Set whether a state is accepting
+    SetAccepting { state: usize, accepting: bool },
+
+    /// Set the transition leaving a state on a column, where `None` is an error transition
+    SetTransition {
+        state: usize,
+        symbol: usize,
+        target: Option<usize>,
+    },
+
+    /// Add a state with the next unused ID and only error transitions
+    AddState { accepting: bool },
+
+    /// Remove a state, turning the transitions entering it into error transitions
+    RemoveState { state: usize },
+}
+
+/// What an edit would change about a table's language, from [`TransitionTable::impact_of`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImpactReport {
+    /// The table with the edit made
+    pub edited: TransitionTable,
+
+    /// Inputs the edited table accepts and the original rejects, shortest first
+    pub newly_accepted: Vec<Vec<usize>>,
+
+    /// Inputs the original table accepts and the edited table rejects, shortest first
+    pub newly_rejected: Vec<Vec<usize>>,
+}
+
+impl ImpactReport {
+    /// Check whether the search found no change to the language
+    pub fn is_empty(&self) -> bool {
+        self.newly_accepted.is_empty() && self.newly_rejected.is_empty()
+    }
+}
+
+impl TransitionTable {
+    /// Make an edit, returning the edited table
+    pub fn apply_edit(&self, edit: &TableEdit) -> Result<TransitionTable, BuildError> {
+        let mut edited = self.clone();
+        let index = |state: usize| self.index_of(state).ok_or(BuildError::MissingState(state));
+
+        match *edit {
+            TableEdit::SetAccepting { state, accepting } => {
+                edited.rows[index(state)?].accepting = accepting;
+            }
+            TableEdit::SetTransition {
+                state,
+                symbol,
+                target,
+            } => {
+                let width = self.width();
+                let row = &mut edited.rows[index(state)?];
+                if symbol >= width {
+                    return Err(BuildError::ColumnOutOfRange { symbol, width });
+                }
+                row.transitions.resize(width, None);
+                row.transitions[symbol] = target;
+            }
+            TableEdit::AddState { accepting } => {
+                let id = self.rows.last().map_or(STARTING_STATE_ID, |row| row.id + 1);
+                edited.rows.push(TransitionTableRow {
+                    accepting,
+                    id,
+                    transitions: vec![None; self.width()],
+                    doc: None,
+                    label: None,
+                });
+            }
+            TableEdit::RemoveState { state } => {
+                edited.rows.remove(index(state)?);
+                for target in edited.rows.iter_mut().flat_map(|row| &mut row.transitions) {
+                    if *target == Some(state) {
+                        *target = None;
+                    }
+                }
+            }
+        }
+
+        Ok(edited)
+    }
+
+    /// Preview an edit without making it, listing example inputs whose answer it changes
+    ///
+    /// Finds up to `limit` inputs of at most `max_len` columns each way, in shortlex order, by
+    /// searching the original and edited tables together. Only prefixes that can still reach a
+    /// disagreement within `max_len` are extended, so the search costs little beyond the examples
+    /// it returns. An empty report means the edit changes nothing up to `max_len`.
+    pub fn impact_of(
+        &self,
+        edit: &TableEdit,
+        max_len: usize,
+        limit: usize,
+    ) -> Result<ImpactReport, BuildError> {
+        let edited = self.apply_edit(edit)?;
+        let (before, after) = (self, &edited);
+        let width = before.width().max(after.width());
+        let start = |table: &TransitionTable| {
+            table
+                .index_of(STARTING_STATE_ID)
+                .unwrap_or(table.rows.len())
+        };
+        let accepting = |table: &TransitionTable, index: usize| {
+            table.rows.get(index).is_some_and(|row| row.accepting)
+        };
+        let step = |(a, b): (usize, usize), symbol: usize| {
+            (before.delta(a, symbol), after.delta(b, symbol))
+        };
+
+        // Find the reachable pairs of states and the pairs entering each one
+        let start = (start(before), start(after));
+        let mut ids = HashMap::from([(start, 0)]);
+        let mut pairs = vec![start];
+        let mut inverse: Vec<Vec<usize>> = vec![Vec::new()];
+        let mut next = 0;
+        while let Some(pair) = pairs.get(next).copied() {
+            for symbol in 0..width {
+                let target = step(pair, symbol);
+                let id = *ids.entry(target).or_insert_with(|| {
+                    pairs.push(target);
+                    inverse.push(Vec::new());
+                    pairs.len() - 1
+                });
+                inverse[id].push(next);
+            }
+            next += 1;
+        }
+
+        // Find how far each pair is from a pair where the answer is gained, and one where it is
+        // lost
+        let distances = |changed: &dyn Fn((usize, usize)) -> bool| {
+            let mut distance = vec![usize::MAX; pairs.len()];
+            let mut queue = VecDeque::new();
+            for (id, pair) in pairs.iter().enumerate() {
+                if changed(*pair) {
+                    distance[id] = 0;
+                    queue.push_back(id);
+                }
+            }
+            while let Some(id) = queue.pop_front() {
+                for source in &inverse[id] {
+                    if distance[*source] == usize::MAX {
+                        distance[*source] = distance[id] + 1;
+                        queue.push_back(*source);
+                    }
+                }
+            }
+            distance
+        };
+        let gained = distances(&|(a, b)| !accepting(before, a) && accepting(after, b));
+        let lost = distances(&|(a, b)| accepting(before, a) && !accepting(after, b));
+
+        // Extend prefixes breadth-first in lexicographic order, so examples come out in shortlex
+        // order, keeping only prefixes that lead to an example that is still wanted
+        let mut report = ImpactReport {
+            edited: edited.clone(),
+            newly_accepted: Vec::new(),
+            newly_rejected: Vec::new(),
+        };
+        let wanted = |report: &ImpactReport, id: usize, remaining: usize| {
+            (report.newly_accepted.len() < limit && gained[id] <= remaining)
+                || (report.newly_rejected.len() < limit && lost[id] <= remaining)
+        };
+        let mut queue = VecDeque::from([(0, Vec::new())]);
+        while let Some((id, input)) = queue.pop_front() {
+            let remaining = max_len - input.len();
+            if !wanted(&report, id, remaining) {
+                continue;
+            }
+            if gained[id] == 0 && report.newly_accepted.len() < limit {
+                report.newly_accepted.push(input.clone());
+            } else if lost[id] == 0 && report.newly_rejected.len() < limit {
+                report.newly_rejected.push(input.clone());
+            }
+
+            if remaining > 0 {
+                for symbol in 0..width {
+                    let mut extended = input.clone();
+                    extended.push(symbol);
+                    queue.push_back((ids[&step(pairs[id], symbol)], extended));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn impact_of() -> Result<(), BuildError> {
+        // Inputs over a and b ending in a
+        let table = TransitionTable::parse("- 0 1 0\n+ 1 1 0\n").unwrap();
+
+        // Staying in state 1 on b accepts every input with an a
+        let report = table.impact_of(
+            &TableEdit::SetTransition {
+                state: 1,
+                symbol: 1,
+                target: Some(1),
+            },
+            3,
+            3,
+        )?;
+        assert_eq!(
+            report.newly_accepted,
+            [vec![0, 1], vec![0, 0, 1], vec![0, 1, 1]]
+        );
+        assert!(report.newly_rejected.is_empty());
+
+        let report = table.impact_of(
+            &TableEdit::SetAccepting {
+                state: 1,
+                accepting: false,
+            },
+            2,
+            10,
+        )?;
+        assert_eq!(report.newly_rejected, [vec![0], vec![0, 0], vec![1, 0]]);
+        assert!(!report.edited.rows[1].accepting);
+
+        // Adding a state nothing enters changes nothing
+        assert!(table
+            .impact_of(&TableEdit::AddState { accepting: true }, 5, 5)?
+            .is_empty());
+        assert_eq!(
+            table.impact_of(&TableEdit::RemoveState { state: 4 }, 5, 5),
+            Err(BuildError::MissingState(4))
+        );
+
+        Ok(())
+    }
+}
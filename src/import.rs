@@ -0,0 +1,17 @@
+use crate::{SymbolMap, TransitionTable};
+
+/// A table read from another tool's format, with the names it used for states and symbols
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImportedTable {
+    /// The table, with the starting state as state 0
+    pub table: TransitionTable,
+
+    /// The name of every column, in the order the names first appear
+    pub symbols: SymbolMap<String>,
+
+    /// The name of every state, indexed by state ID
+    pub states: Vec<String>,
+
+    /// The parts of the input that were ignored because they have no meaning for a table
+    pub warnings: Vec<String>,
+}
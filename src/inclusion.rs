@@ -0,0 +1,130 @@
+use crate::{Automaton, Counterexample, TransitionTable};
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// The answer of [`TransitionTable::check_inclusion`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Inclusion {
+    /// Every input the first automaton accepts, the second accepts too
+    Included,
+
+    /// A shortest input the first automaton accepts and the second rejects
+    NotIncluded(Vec<usize>),
+
+    /// The budget ran out before an answer was found, after exploring this many pairs of states
+    Unknown { explored: usize },
+}
+
+impl TransitionTable {
+    /// Check whether every input `sub` accepts is also accepted by `sup`, exploring at most
+    /// `budget` pairs of states
+    ///
+    /// Unlike building the product of two tables, pairs are only visited as the search reaches
+    /// them, each at most once, and the search stops at the first input `sub` accepts and `sup`
+    /// rejects. Pairs where `sub` is in a trap can't lead to such an input and aren't explored
+    /// further, so backends that know their traps skip whole regions of the product. Once `sup`
+    /// rejects a prefix, only `sub` is followed. Only columns `sub` has are tried, and `sup`
+    /// rejects columns past its own width.
+    pub fn check_inclusion<A, B>(sub: &A, sup: &B, budget: usize) -> Inclusion
+    where
+        A: Automaton,
+        B: Automaton,
+        A::State: Hash,
+        B::State: Hash,
+    {
+        let Some(start) = sub.start() else {
+            return Inclusion::Included;
+        };
+        let width = sub.width();
+        let sup_width = sup.width();
+        let start = (start, sup.start());
+
+        // The pair and column each pair was first reached from
+        let mut parents = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pair) = queue.pop_front() {
+            let (a, b) = pair;
+            if sub.is_accepting(a) && !b.is_some_and(|b| sup.is_accepting(b)) {
+                let mut input = Vec::new();
+                let mut current = pair;
+                while let Some((parent, symbol)) = parents[&current] {
+                    input.push(symbol);
+                    current = parent;
+                }
+                input.reverse();
+                return Inclusion::NotIncluded(input);
+            }
+            if sub.is_trap(a) {
+                continue;
+            }
+
+            for symbol in 0..width {
+                let Some(a_next) = sub.next_state(a, symbol) else {
+                    continue;
+                };
+                let b_next = b
+                    .filter(|_| symbol < sup_width)
+                    .and_then(|b| sup.next_state(b, symbol));
+                let next = (a_next, b_next);
+                if parents.contains_key(&next) {
+                    continue;
+                }
+                if parents.len() >= budget {
+                    return Inclusion::Unknown {
+                        explored: parents.len(),
+                    };
+                }
+                parents.insert(next, Some((pair, symbol)));
+                queue.push_back(next);
+            }
+        }
+
+        Inclusion::Included
+    }
+
+    /// Check whether every input this table accepts, `other` accepts too
+    ///
+    /// Fails with a shortest input this table accepts and `other` rejects, found like
+    /// [`TransitionTable::check_inclusion`] without a budget.
+    pub fn is_subset_of(&self, other: &TransitionTable) -> Result<(), Counterexample> {
+        match TransitionTable::check_inclusion(self, other, usize::MAX) {
+            Inclusion::NotIncluded(input) => Err(Counterexample {
+                input,
+                accepted_by_self: true,
+            }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrozenTable;
+
+    #[test]
+    fn check_inclusion() {
+        // Over a and b: inputs ending in a, and inputs containing an a
+        let ends_in_a = TransitionTable::parse("- 0 1 0\n+ 1 1 0\n").unwrap();
+        let has_a = TransitionTable::parse("- 0 1 0\n+ 1 1 1\n").unwrap();
+        assert_eq!(
+            TransitionTable::check_inclusion(&ends_in_a, &has_a, 100),
+            Inclusion::Included
+        );
+        assert_eq!(
+            TransitionTable::check_inclusion(&has_a, &FrozenTable::from_table(&ends_in_a), 100),
+            Inclusion::NotIncluded(vec![0, 1])
+        );
+
+        // The product has three reachable pairs
+        assert_eq!(
+            TransitionTable::check_inclusion(&ends_in_a, &has_a, 2),
+            Inclusion::Unknown { explored: 2 }
+        );
+
+        assert_eq!(ends_in_a.is_subset_of(&has_a), Ok(()));
+        let counterexample = has_a.is_subset_of(&ends_in_a).unwrap_err();
+        assert_eq!(counterexample.input, [0, 1]);
+        assert!(counterexample.accepted_by_self);
+    }
+}
@@ -0,0 +1,408 @@
+use crate::{BuildError, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::BTreeMap;
+
+/// A class of equivalent states kept by an [`IncrementalMinimizer`]
+#[derive(Clone, Debug, PartialEq)]
+struct Class {
+    /// Whether the class's states are accepting
+    accepting: bool,
+
+    /// The class entered on every column
+    transitions: Vec<usize>,
+}
+
+/// A table that keeps its minimal table up to date as it is edited
+///
+/// Every edit repairs the equivalence classes of only the states it affects, which are the states
+/// that can reach an edited state. The classes of all other states keep their languages, so they
+/// are reused as single states, and refinement runs on them plus the affected states instead of
+/// the whole table. The reverse transitions are kept up to date with the edits, so finding the
+/// affected states only visits them and their transitions, although renumbering the classes
+/// afterwards still takes time linear in the number of states. Edits deep in a table, which few
+/// states reach, then avoid refining the whole table again.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IncrementalMinimizer {
+    /// The edited table, with every row as wide as the table
+    table: TransitionTable,
+
+    /// The number of columns
+    width: usize,
+
+    /// The class of every row index, plus the sink
+    class_of: Vec<usize>,
+
+    /// The classes, which are pairwise inequivalent
+    classes: Vec<Class>,
+
+    /// The row indices with a transition into every row, once per transition
+    inverse: Vec<Vec<usize>>,
+
+    /// The row indices with a transition to every missing state ID, once per transition, which
+    /// become transitions into the state if it is added
+    dangling: BTreeMap<usize, Vec<usize>>,
+
+    /// The number of states the last edit affected
+    repaired: usize,
+}
+
+impl IncrementalMinimizer {
+    /// Start editing a table, minimizing it once
+    ///
+    /// Rows narrower than the table are padded with error transitions.
+    pub fn new(table: &TransitionTable) -> Self {
+        let mut table = table.clone();
+        let width = table.width();
+        for row in &mut table.rows {
+            row.transitions.resize(width, None);
+        }
+
+        let mut inverse = vec![Vec::new(); table.rows.len()];
+        let mut dangling: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (index, row) in table.rows.iter().enumerate() {
+            for target in row.transitions.iter().flatten() {
+                match table.index_of(*target) {
+                    Some(target) => inverse[target].push(index),
+                    None => dangling.entry(*target).or_default().push(index),
+                }
+            }
+        }
+
+        let (class_of, classes) = classes_of(&table, width);
+        IncrementalMinimizer {
+            repaired: table.rows.len(),
+            table,
+            width,
+            class_of,
+            classes,
+            inverse,
+            dangling,
+        }
+    }
+
+    /// Get the edited table
+    pub fn table(&self) -> &TransitionTable {
+        &self.table
+    }
+
+    /// Get the number of states the last edit had to reclassify
+    pub fn repaired(&self) -> usize {
+        self.repaired
+    }
+
+    /// Get the minimal table, equal to [`TransitionTable::minimize`] of the edited table
+    ///
+    /// Like [`TransitionTable::minimize`], the result keeps the table's header, metadata, and
+    /// comment, and the docs and labels of states that aren't merged with any other.
+    pub fn minimized(&self) -> TransitionTable {
+        let dead = self.class_of[self.table.rows.len()];
+        let start = match self.table.index_of(STARTING_STATE_ID) {
+            Some(start) if self.class_of[start] != dead => self.class_of[start],
+            _ => {
+                return TransitionTable {
+                    alphabet: self.table.alphabet.clone(),
+                    metadata: self.table.metadata.clone(),
+                    comment: self.table.comment.clone(),
+                    ..TransitionTable::empty_language(self.width)
+                }
+            }
+        };
+
+        // Count the members of every class, remembering one, to find the classes of a single state
+        let mut members = vec![(0, None); self.classes.len()];
+        for (index, class) in self.class_of[..self.table.rows.len()].iter().enumerate() {
+            members[*class].0 += 1;
+            members[*class].1 = Some(index);
+        }
+        let rows = self
+            .classes
+            .iter()
+            .enumerate()
+            .filter(|(id, _)| *id != dead)
+            .map(|(id, class)| {
+                let only = match members[id] {
+                    (1, Some(index)) => Some(&self.table.rows[index]),
+                    _ => None,
+                };
+                TransitionTableRow {
+                    accepting: class.accepting,
+                    id,
+                    transitions: class
+                        .transitions
+                        .iter()
+                        .map(|target| Some(*target).filter(|target| *target != dead))
+                        .collect(),
+                    doc: only.and_then(|row| row.doc.clone()),
+                    label: only.and_then(|row| row.label.clone()),
+                }
+            })
+            .collect();
+        TransitionTable {
+            rows,
+            alphabet: self.table.alphabet.clone(),
+            metadata: self.table.metadata.clone(),
+            comment: self.table.comment.clone(),
+        }
+        .renumber_reachable(start)
+        .0
+    }
+
+    /// Add a state with the next unused ID and only error transitions, returning its ID
+    pub fn add_state(&mut self, accepting: bool) -> usize {
+        let id = self
+            .table
+            .rows
+            .last()
+            .map_or(STARTING_STATE_ID, |row| row.id + 1);
+        self.table.rows.push(TransitionTableRow {
+            accepting,
+            id,
+            transitions: vec![None; self.width],
+            doc: None,
+            label: None,
+        });
+
+        // The new row takes the sink's old index, and any transitions to its ID now enter it
+        let sink = self.class_of[self.table.rows.len() - 1];
+        self.class_of.push(sink);
+        self.inverse
+            .push(self.dangling.remove(&id).unwrap_or_default());
+        self.repair(self.table.rows.len() - 1);
+        id
+    }
+
+    /// Set whether a state is accepting
+    pub fn set_accepting(&mut self, id: usize, accepting: bool) -> Result<(), BuildError> {
+        let index = self
+            .table
+            .index_of(id)
+            .ok_or(BuildError::MissingState(id))?;
+        self.table.rows[index].accepting = accepting;
+        self.repair(index);
+        Ok(())
+    }
+
+    /// Set the transition leaving a state on a column, where `None` is an error transition
+    ///
+    /// Unlike [`crate::TableBuilder::set_transition`], the target must already exist.
+    pub fn set_transition(
+        &mut self,
+        from: usize,
+        symbol: usize,
+        to: Option<usize>,
+    ) -> Result<(), BuildError> {
+        let index = self
+            .table
+            .index_of(from)
+            .ok_or(BuildError::MissingState(from))?;
+        if symbol >= self.width {
+            return Err(BuildError::ColumnOutOfRange {
+                symbol,
+                width: self.width,
+            });
+        }
+        if let Some(to) = to.filter(|to| self.table.index_of(*to).is_none()) {
+            return Err(BuildError::MissingState(to));
+        }
+
+        // Move the reverse transition from the old target to the new one
+        if let Some(old) = self.table.rows[index].transitions[symbol] {
+            let sources = match self.table.index_of(old) {
+                Some(old) => &mut self.inverse[old],
+                None => self.dangling.get_mut(&old).unwrap(),
+            };
+            let position = sources.iter().position(|source| *source == index).unwrap();
+            sources.swap_remove(position);
+        }
+        if let Some(to) = to {
+            self.inverse[self.table.index_of(to).unwrap()].push(index);
+        }
+
+        self.table.rows[index].transitions[symbol] = to;
+        self.repair(index);
+        Ok(())
+    }
+
+    /// Reclassify the states whose languages an edit to a row may have changed
+    fn repair(&mut self, edited: usize) {
+        let sink = self.table.rows.len();
+
+        // Find the states that can reach the edited state
+        let mut affected = vec![false; sink];
+        affected[edited] = true;
+        let mut pending = vec![edited];
+        while let Some(index) = pending.pop() {
+            for source in &self.inverse[index] {
+                if !affected[*source] {
+                    affected[*source] = true;
+                    pending.push(*source);
+                }
+            }
+        }
+
+        // Classes with an unaffected state keep their languages, and their transitions only enter
+        // other such classes, so each becomes one state of a smaller table
+        let mut kept = BTreeMap::new();
+        for index in (0..=sink).filter(|index| *index == sink || !affected[*index]) {
+            let next = kept.len();
+            kept.entry(self.class_of[index]).or_insert(next);
+        }
+        let mut position = vec![0; sink];
+        for (offset, index) in (0..sink).filter(|index| affected[*index]).enumerate() {
+            position[index] = kept.len() + offset;
+        }
+
+        let mut rows: Vec<TransitionTableRow> = kept
+            .iter()
+            .map(|(class, id)| TransitionTableRow {
+                accepting: self.classes[*class].accepting,
+                id: *id,
+                transitions: self.classes[*class]
+                    .transitions
+                    .iter()
+                    .map(|target| Some(kept[target]))
+                    .collect(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+        rows.sort_by_key(|row| row.id);
+        for index in (0..sink).filter(|index| affected[*index]) {
+            rows.push(TransitionTableRow {
+                accepting: self.table.rows[index].accepting,
+                id: position[index],
+                transitions: (0..self.width)
+                    .map(|symbol| {
+                        let target = self.table.delta(index, symbol);
+                        Some(if target != sink && affected[target] {
+                            position[target]
+                        } else {
+                            kept[&self.class_of[target]]
+                        })
+                    })
+                    .collect(),
+                doc: None,
+                label: None,
+            });
+        }
+        let reduced = TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        };
+
+        let (reduced_class_of, classes) = classes_of(&reduced, self.width);
+        self.class_of = (0..=sink)
+            .map(|index| {
+                if index != sink && affected[index] {
+                    reduced_class_of[position[index]]
+                } else {
+                    reduced_class_of[kept[&self.class_of[index]]]
+                }
+            })
+            .collect();
+        self.classes = classes;
+        self.repaired = affected.iter().filter(|affected| **affected).count();
+    }
+}
+
+/// Partition a table's row indices, plus the sink, into classes of equivalent states
+fn classes_of(table: &TransitionTable, width: usize) -> (Vec<usize>, Vec<Class>) {
+    let (class_of, count) = table.equivalence_classes();
+    let mut classes: Vec<Option<Class>> = vec![None; count];
+    for (index, class) in class_of.iter().enumerate() {
+        classes[*class].get_or_insert_with(|| Class {
+            accepting: table.rows.get(index).is_some_and(|row| row.accepting),
+            transitions: (0..width)
+                .map(|symbol| class_of[table.delta(index, symbol)])
+                .collect(),
+        });
+    }
+
+    (class_of, classes.into_iter().map(Option::unwrap).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::laws::ArbitraryTables;
+    use crate::rng::Rng;
+
+    #[test]
+    fn incremental_minimizer() -> Result<(), BuildError> {
+        // A chain of states where only the end accepts, followed by a self-loop
+        let table = TransitionTable::parse("- 0 1\n- 1 2\n- 2 3\n+ 3 3\n").unwrap();
+        let mut minimizer = IncrementalMinimizer::new(&table);
+        assert_eq!(minimizer.minimized(), table.minimize());
+
+        // Making state 1 accept leaves state 2 and 3 alone
+        minimizer.set_accepting(1, true)?;
+        assert_eq!(minimizer.repaired(), 2);
+        assert_eq!(minimizer.minimized(), minimizer.table().minimize());
+
+        let added = minimizer.add_state(true);
+        minimizer.set_transition(3, 0, Some(added))?;
+        assert_eq!(minimizer.minimized(), minimizer.table().minimize());
+
+        assert_eq!(
+            minimizer.set_transition(0, 1, None),
+            Err(BuildError::ColumnOutOfRange {
+                symbol: 1,
+                width: 1
+            })
+        );
+        assert_eq!(
+            minimizer.set_transition(0, 0, Some(9)),
+            Err(BuildError::MissingState(9))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn incremental_minimizer_keeps_annotations() -> Result<(), BuildError> {
+        let table = TransitionTable::parse(
+            "# Digits\n\n%meta generator test\nalphabet: [0-9]\n# Start\n- 0 start 1\n\
+             + 1 one 2\n+ 2 two 2\n",
+        )
+        .unwrap();
+        let mut minimizer = IncrementalMinimizer::new(&table);
+        assert_eq!(minimizer.minimized(), table.minimize());
+
+        // Merging states 1 and 2 drops their labels, and the empty language keeps the header
+        minimizer.set_transition(1, 0, Some(1))?;
+        assert_eq!(minimizer.minimized(), minimizer.table().minimize());
+        minimizer.set_accepting(1, false)?;
+        minimizer.set_accepting(2, false)?;
+        assert_eq!(minimizer.minimized(), minimizer.table().minimize());
+        assert_eq!(minimizer.minimized().rows.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn incremental_minimizer_matches_minimize() -> Result<(), BuildError> {
+        let mut rng = Rng::new(3);
+        for table in ArbitraryTables::new(5).take(40) {
+            let mut minimizer = IncrementalMinimizer::new(&table);
+            for _ in 0..10 {
+                let states = minimizer.table().rows.len();
+                let state = minimizer.table().rows[rng.below(states)].id;
+                match rng.below(4) {
+                    0 => minimizer.set_accepting(state, rng.below(2) == 0)?,
+                    1 => {
+                        minimizer.add_state(rng.below(2) == 0);
+                    }
+                    _ => {
+                        let target = minimizer.table().rows[rng.below(states)].id;
+                        let target = (rng.below(4) != 0).then_some(target);
+                        minimizer.set_transition(state, rng.below(2), target)?;
+                    }
+                }
+                assert_eq!(minimizer.minimized(), minimizer.table().minimize());
+            }
+        }
+
+        Ok(())
+    }
+}
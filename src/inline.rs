@@ -0,0 +1,155 @@
+use crate::{Automaton, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+
+/// The most columns an [`InlineTable`] can have
+pub const INLINE_WIDTH: usize = 8;
+
+/// The target used for error transitions in an [`InlineRow`]
+const INLINE_DEAD: u32 = u32::MAX;
+
+/// A row of an [`InlineTable`], with its transitions stored inline
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct InlineRow {
+    /// The row's state ID
+    id: usize,
+
+    /// Whether the row is accepting
+    accepting: bool,
+
+    /// The target row index of each column, or [`INLINE_DEAD`]
+    transitions: [u32; INLINE_WIDTH],
+}
+
+/// A table over at most [`INLINE_WIDTH`] columns whose rows store their transitions inline
+///
+/// Every row is a fixed-size value in one contiguous vector instead of owning a separate
+/// allocation, which suits the small alphabets of teaching examples and improves locality when
+/// running. States are addressed by row index when used as an [`Automaton`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlineTable {
+    /// The rows, sorted by state ID
+    rows: Vec<InlineRow>,
+
+    /// The number of columns
+    width: usize,
+
+    /// The row index of the starting state, if it exists
+    start: Option<usize>,
+}
+
+impl InlineTable {
+    /// Convert a table, returning `None` if it is wider than [`INLINE_WIDTH`] or too large to
+    /// address rows with 32 bits
+    ///
+    /// Transitions to states that do not exist become error transitions.
+    pub fn from_table(table: &TransitionTable) -> Option<Self> {
+        let width = table.width();
+        if width > INLINE_WIDTH || table.rows.len() >= INLINE_DEAD as usize {
+            return None;
+        }
+
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| {
+                let mut transitions = [INLINE_DEAD; INLINE_WIDTH];
+                for (slot, target) in transitions.iter_mut().zip(&row.transitions) {
+                    if let Some(index) = target.and_then(|target| table.index_of(target)) {
+                        *slot = index as u32;
+                    }
+                }
+
+                InlineRow {
+                    id: row.id,
+                    accepting: row.accepting,
+                    transitions,
+                }
+            })
+            .collect();
+
+        Some(InlineTable {
+            rows,
+            width,
+            start: table.index_of(STARTING_STATE_ID),
+        })
+    }
+
+    /// Copy the table into an editable [`TransitionTable`]
+    pub fn to_table(&self) -> TransitionTable {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| TransitionTableRow {
+                accepting: row.accepting,
+                id: row.id,
+                transitions: row.transitions[..self.width]
+                    .iter()
+                    .map(|target| (*target != INLINE_DEAD).then(|| self.rows[*target as usize].id))
+                    .collect(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+
+    /// Get the state ID of a row index
+    pub fn id(&self, index: usize) -> Option<usize> {
+        self.rows.get(index).map(|row| row.id)
+    }
+}
+
+impl Automaton for InlineTable {
+    /// The row index
+    type State = usize;
+
+    fn start(&self) -> Option<usize> {
+        self.start
+    }
+
+    fn is_accepting(&self, state: usize) -> bool {
+        self.rows[state].accepting
+    }
+
+    #[inline]
+    fn next_state(&self, state: usize, symbol: usize) -> Option<usize> {
+        let target = *self.rows[state].transitions.get(symbol)?;
+        (target != INLINE_DEAD).then_some(target as usize)
+    }
+
+    fn width(&self) -> usize {
+        self.width
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn inline_table() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 3 0 E\n- 0 3 5\n+ 5 5 7\n")?;
+        let inline = InlineTable::from_table(&table).unwrap();
+
+        assert_eq!(inline.start(), Some(0));
+        assert_eq!(inline.id(1), Some(3));
+        assert!(inline.accepts([1, 0]));
+        assert!(!inline.accepts([1, 1]));
+        assert!(!inline.accepts([1, 2]));
+        assert_eq!(
+            inline.to_table(),
+            TransitionTable::parse("- 3 0 E\n- 0 3 5\n+ 5 5 E\n")?
+        );
+
+        let wide = TransitionTable::parse(&format!("+ 0{}\n", " 0".repeat(INLINE_WIDTH + 1)))?;
+        assert!(InlineTable::from_table(&wide).is_none());
+
+        Ok(())
+    }
+}
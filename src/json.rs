@@ -0,0 +1,452 @@
+use crate::{ParseSerializeError, TransitionTable, TransitionTableRow};
+
+/// A field of a row in the JSON format, used to generate both the schema and the TypeScript types
+struct RowField {
+    /// The field's name
+    name: &'static str,
+
+    /// The field's JSON Schema
+    schema: &'static str,
+
+    /// The field's TypeScript type
+    typescript: &'static str,
+
+    /// What the field means
+    description: &'static str,
+}
+
+/// The fields of every row in the JSON format, in the order they are written
+const ROW_FIELDS: [RowField; 3] = [
+    RowField {
+        name: "accepting",
+        schema: r#"{ "type": "boolean" }"#,
+        typescript: "boolean",
+        description: "Whether the row is for an accepting state",
+    },
+    RowField {
+        name: "id",
+        schema: r#"{ "type": "integer", "minimum": 0 }"#,
+        typescript: "number",
+        description: "The row's state ID (0 means the starting state)",
+    },
+    RowField {
+        name: "transitions",
+        schema: r#"{ "type": "array", "items": { "type": ["integer", "null"], "minimum": 0 } }"#,
+        typescript: "(number | null)[]",
+        description:
+            "The state reached on each column, or null for an error transition; every row has the same number",
+    },
+];
+
+/// A parsed JSON value
+#[derive(Clone, Debug, PartialEq)]
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+/// A recursive descent parser for JSON documents
+struct JsonParser<'a> {
+    /// The document
+    input: &'a str,
+
+    /// The byte offset of the next character
+    position: usize,
+}
+
+impl JsonParser<'_> {
+    /// Create an error at the current position
+    fn error(&self, message: &str) -> ParseSerializeError {
+        ParseSerializeError {
+            message: format!("JSON {} at byte {}", message, self.position),
+        }
+    }
+
+    /// Get the next character without consuming it, after skipping whitespace
+    fn peek(&mut self) -> Option<char> {
+        let rest = &self.input[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+        self.input[self.position..].chars().next()
+    }
+
+    /// Consume `expected`, which must be the next character
+    fn expect(&mut self, expected: char) -> Result<(), ParseSerializeError> {
+        if self.peek() != Some(expected) {
+            return Err(self.error(&format!("expected {}", expected)));
+        }
+        self.position += expected.len_utf8();
+        Ok(())
+    }
+
+    /// Consume `literal` if the input continues with it
+    fn literal(&mut self, literal: &str) -> bool {
+        let matches = self.input[self.position..].starts_with(literal);
+        if matches {
+            self.position += literal.len();
+        }
+        matches
+    }
+
+    /// Parse a value
+    fn value(&mut self) -> Result<JsonValue, ParseSerializeError> {
+        match self.peek() {
+            Some('{') => {
+                self.position += 1;
+                let mut entries = Vec::new();
+                if self.peek() == Some('}') {
+                    self.position += 1;
+                    return Ok(JsonValue::Object(entries));
+                }
+                loop {
+                    if self.peek() != Some('"') {
+                        return Err(self.error("expected a key"));
+                    }
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    entries.push((key, self.value()?));
+                    match self.peek() {
+                        Some(',') => self.position += 1,
+                        Some('}') => break,
+                        _ => return Err(self.error("expected , or }")),
+                    }
+                }
+                self.position += 1;
+                Ok(JsonValue::Object(entries))
+            }
+            Some('[') => {
+                self.position += 1;
+                let mut items = Vec::new();
+                if self.peek() == Some(']') {
+                    self.position += 1;
+                    return Ok(JsonValue::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    match self.peek() {
+                        Some(',') => self.position += 1,
+                        Some(']') => break,
+                        _ => return Err(self.error("expected , or ]")),
+                    }
+                }
+                self.position += 1;
+                Ok(JsonValue::Array(items))
+            }
+            Some('"') => self.string().map(JsonValue::String),
+            Some('-' | '0'..='9') => {
+                let rest = &self.input[self.position..];
+                let length = rest
+                    .find(|c: char| !matches!(c, '-' | '+' | '.' | 'e' | 'E' | '0'..='9'))
+                    .unwrap_or(rest.len());
+                let number = rest[..length]
+                    .parse()
+                    .map_err(|_| self.error("has an invalid number"))?;
+                self.position += length;
+                Ok(JsonValue::Number(number))
+            }
+            _ if self.literal("null") => Ok(JsonValue::Null),
+            _ if self.literal("true") => Ok(JsonValue::Bool(true)),
+            _ if self.literal("false") => Ok(JsonValue::Bool(false)),
+            _ => Err(self.error("expected a value")),
+        }
+    }
+
+    /// Parse a string, starting at its opening quote
+    fn string(&mut self) -> Result<String, ParseSerializeError> {
+        self.position += 1;
+        let mut string = String::new();
+        let mut chars = self.input[self.position..].char_indices();
+
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += offset + 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            u32::from_str_radix(&hex, 16)
+                                .ok()
+                                .and_then(char::from_u32)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        _ => return Err(self.error("has an invalid escape")),
+                    };
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+
+        Err(self.error("has an unterminated string"))
+    }
+}
+
+/// Read a non-negative integer from a JSON value
+fn as_index(value: &JsonValue) -> Option<usize> {
+    match value {
+        JsonValue::Number(number) if number.fract() == 0.0 && *number >= 0.0 => {
+            Some(*number as usize)
+        }
+        _ => None,
+    }
+}
+
+impl TransitionTable {
+    /// Serialize the table to JSON
+    ///
+    /// The output is an object with a `rows` array, and every row is an object with the fields
+    /// described by [`TransitionTable::json_schema`].
+    pub fn to_json(&self) -> String {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                let transitions = row
+                    .transitions
+                    .iter()
+                    .map(|target| target.map_or_else(|| "null".to_string(), |t| t.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!(
+                    "    {{ \"accepting\": {}, \"id\": {}, \"transitions\": [{}] }}",
+                    row.accepting, row.id, transitions
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if rows.is_empty() {
+            "{ \"rows\": [] }\n".to_string()
+        } else {
+            format!("{{\n  \"rows\": [\n{}\n  ]\n}}\n", rows.join(",\n"))
+        }
+    }
+
+    /// Parse a table from JSON written by [`TransitionTable::to_json`] or any other producer
+    ///
+    /// Fields may come in any order, but unknown fields are an error, as they are in the schema.
+    /// Rows are sorted by state ID.
+    pub fn parse_json(input: &str) -> Result<Self, ParseSerializeError> {
+        let error = |message: String| ParseSerializeError { message };
+
+        let mut parser = JsonParser { input, position: 0 };
+        let document = parser.value()?;
+        if parser.peek().is_some() {
+            return Err(parser.error("has trailing characters"));
+        }
+
+        let rows = match document {
+            JsonValue::Object(fields) => match fields.as_slice() {
+                [(key, JsonValue::Array(rows))] if key == "rows" => rows.clone(),
+                _ => {
+                    return Err(error(
+                        "JSON table must be an object with only a rows array".to_string(),
+                    ))
+                }
+            },
+            _ => return Err(error("JSON table is not an object".to_string())),
+        };
+
+        let mut table = TransitionTable {
+            rows: Vec::new(),
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        };
+        for (index, row) in rows.iter().enumerate() {
+            let JsonValue::Object(fields) = row else {
+                return Err(error(format!("JSON row {} is not an object", index)));
+            };
+
+            let (mut accepting, mut id, mut transitions) = (None, None, None);
+            for (key, value) in fields {
+                match (key.as_str(), value) {
+                    ("accepting", JsonValue::Bool(flag)) => accepting = Some(*flag),
+                    ("id", value) if as_index(value).is_some() => id = as_index(value),
+                    ("transitions", JsonValue::Array(targets)) => {
+                        transitions = Some(
+                            targets
+                                .iter()
+                                .map(|target| match target {
+                                    JsonValue::Null => Ok(None),
+                                    target => as_index(target).map(Some).ok_or_else(|| {
+                                        error(format!("JSON row {} has an invalid target", index))
+                                    }),
+                                })
+                                .collect::<Result<Vec<_>, _>>()?,
+                        )
+                    }
+                    _ => {
+                        return Err(error(format!(
+                            "JSON row {} has an invalid field {}",
+                            index, key
+                        )))
+                    }
+                }
+            }
+
+            let missing = |field| error(format!("JSON row {} has no {}", index, field));
+            let row = TransitionTableRow {
+                accepting: accepting.ok_or_else(|| missing("accepting"))?,
+                id: id.ok_or_else(|| missing("id"))?,
+                transitions: transitions.ok_or_else(|| missing("transitions"))?,
+                doc: None,
+                label: None,
+            };
+            if table
+                .rows
+                .first()
+                .is_some_and(|first| first.transitions.len() != row.transitions.len())
+            {
+                return Err(error(format!(
+                    "JSON row {} has a different number of transitions than the previous rows",
+                    index
+                )));
+            }
+            table.rows.push(row);
+        }
+
+        table.rows.sort_by_key(|row| row.id);
+        Ok(table)
+    }
+
+    /// Generate a JSON Schema (draft 2020-12) for the format read by
+    /// [`TransitionTable::parse_json`]
+    ///
+    /// JSON Schema can't say that every row has the same number of transitions, so consumers
+    /// should check that themselves.
+    pub fn json_schema() -> String {
+        let properties = ROW_FIELDS
+            .iter()
+            .map(|field| {
+                let schema = field.schema.trim_end_matches('}').trim_end();
+                format!(
+                    "          \"{}\": {}, \"description\": \"{}\" }}",
+                    field.name, schema, field.description
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(",\n");
+        let required = ROW_FIELDS
+            .iter()
+            .map(|field| format!("\"{}\"", field.name))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!(
+            r#"{{
+  "$schema": "https://json-schema.org/draft/2020-12/schema",
+  "title": "TransitionTable",
+  "description": "A DFA transition table",
+  "type": "object",
+  "properties": {{
+    "rows": {{
+      "description": "The rows in the table, one per state",
+      "type": "array",
+      "items": {{
+        "type": "object",
+        "properties": {{
+{}
+        }},
+        "required": [{}],
+        "additionalProperties": false
+      }}
+    }}
+  }},
+  "required": ["rows"],
+  "additionalProperties": false
+}}
+"#,
+            properties, required
+        )
+    }
+
+    /// Generate TypeScript definitions for the format read by [`TransitionTable::parse_json`]
+    pub fn typescript_definitions() -> String {
+        let fields = ROW_FIELDS
+            .iter()
+            .map(|field| {
+                format!(
+                    "  /** {} */\n  {}: {};\n",
+                    field.description, field.name, field.typescript
+                )
+            })
+            .collect::<String>();
+
+        format!(
+            "/** A state (row) in the DFA transition table */\nexport interface TransitionTableRow {{\n{}}}\n\n/** A DFA transition table */\nexport interface TransitionTable {{\n  /** The rows in the table, one per state */\n  rows: TransitionTableRow[];\n}}\n",
+            fields
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n")?;
+        let json = table.to_json();
+        assert_eq!(
+            json,
+            "{\n  \"rows\": [\n    { \"accepting\": false, \"id\": 0, \"transitions\": [1, null] },\n    { \"accepting\": true, \"id\": 1, \"transitions\": [1, 0] }\n  ]\n}\n"
+        );
+        assert_eq!(TransitionTable::parse_json(&json)?, table);
+
+        // Fields can come in any order, and rows are sorted
+        let reordered = r#"{"rows": [{"transitions": [1, 0], "id": 1, "accepting": true},
+            {"id": 0, "accepting": false, "transitions": [1, null]}]}"#;
+        assert_eq!(TransitionTable::parse_json(reordered)?, table);
+
+        assert!(
+            TransitionTable::parse_json(r#"{"rows": [{"accepting": true, "id": 0}]}"#).is_err()
+        );
+        assert!(TransitionTable::parse_json(
+            r#"{"rows": [{"accepting": true, "id": 0, "transitions": [], "name": "a"}]}"#
+        )
+        .is_err());
+        assert!(TransitionTable::parse_json(
+            r#"{"rows": [{"accepting": true, "id": 0, "transitions": [0]},
+                {"accepting": true, "id": 1, "transitions": []}]}"#
+        )
+        .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn schema_parses_as_json() {
+        let mut parser = JsonParser {
+            input: &TransitionTable::json_schema(),
+            position: 0,
+        };
+        let JsonValue::Object(schema) = parser.value().unwrap() else {
+            panic!("schema is not an object");
+        };
+        assert_eq!(
+            schema[1],
+            (
+                "title".to_string(),
+                JsonValue::String("TransitionTable".to_string())
+            )
+        );
+
+        let typescript = TransitionTable::typescript_definitions();
+        assert!(typescript.contains("  transitions: (number | null)[];\n"));
+        assert!(typescript.contains("  rows: TransitionTableRow[];\n"));
+    }
+}
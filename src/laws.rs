@@ -0,0 +1,283 @@
+//! Reusable checks of the algebraic laws that table operations should satisfy
+//!
+//! Each law is a function returning a [`LawViolation`] naming the law and the tables that break
+//! it. Laws about operations take the operations as arguments, so any implementation can be
+//! checked against the same laws. Languages are compared by their minimized tables, which are
+//! equal exactly when the languages are.
+//!
+//! [`ArbitraryTables`] generates random tables to check the laws over:
+//!
+//! ```
+//! use transition_tables::laws::{self, ArbitraryTables};
+//!
+//! ArbitraryTables::new(7)
+//!     .take(100)
+//!     .try_for_each(|table| laws::minimize_idempotent(&table))
+//!     .unwrap();
+//! ```
+
+use crate::rng::Rng;
+use crate::{TransitionTable, TransitionTableRow};
+
+/// A table that breaks a law
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+#[error("{law} does not hold for {tables:?}")]
+pub struct LawViolation {
+    /// The name of the law
+    pub law: &'static str,
+
+    /// The tables the law was checked on
+    pub tables: Vec<TransitionTable>,
+}
+
+/// A deterministic generator of random tables, for checking laws
+///
+/// Tables have between 1 and `max_states` states numbered from 0, all `width` columns wide.
+/// About a quarter of transitions are error transitions and about half the states accept.
+#[derive(Clone, Debug)]
+pub struct ArbitraryTables {
+    /// The random number generator
+    rng: Rng,
+
+    /// The most states a table can have
+    max_states: usize,
+
+    /// The number of columns in every table
+    width: usize,
+}
+
+impl ArbitraryTables {
+    /// Create a generator of tables with up to 6 states and 2 columns from a seed
+    pub fn new(seed: u64) -> Self {
+        ArbitraryTables::with_size(seed, 6, 2)
+    }
+
+    /// Create a generator of tables with up to `max_states` states and `width` columns
+    pub fn with_size(seed: u64, max_states: usize, width: usize) -> Self {
+        ArbitraryTables::with_rng(Rng::new(seed), max_states, width)
+    }
+
+    /// Create a generator of tables with up to `max_states` states and `width` columns drawing
+    /// from a generator
+    pub fn with_rng(rng: Rng, max_states: usize, width: usize) -> Self {
+        ArbitraryTables {
+            rng,
+            max_states: max_states.max(1),
+            width,
+        }
+    }
+}
+
+impl Iterator for ArbitraryTables {
+    type Item = TransitionTable;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let states = self.rng.below(self.max_states) + 1;
+        let rows = (0..states)
+            .map(|id| TransitionTableRow {
+                accepting: self.rng.below(2) == 0,
+                id,
+                transitions: (0..self.width)
+                    .map(|_| (self.rng.below(4) != 0).then(|| self.rng.below(states)))
+                    .collect(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+
+        Some(TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        })
+    }
+}
+
+/// Check whether two tables accept the same language
+fn same_language(a: &TransitionTable, b: &TransitionTable) -> bool {
+    a.minimize() == b.minimize()
+}
+
+/// Check a law, reporting the tables it was checked on if it fails
+fn check(law: &'static str, holds: bool, tables: &[&TransitionTable]) -> Result<(), LawViolation> {
+    if holds {
+        Ok(())
+    } else {
+        Err(LawViolation {
+            law,
+            tables: tables.iter().map(|table| (*table).clone()).collect(),
+        })
+    }
+}
+
+/// Check that parsing a serialized table gives back the same table
+pub fn parse_serialize_identity(table: &TransitionTable) -> Result<(), LawViolation> {
+    let round_trip = TransitionTable::parse(&table.serialize());
+    check(
+        "parse(serialize(t)) = t",
+        round_trip.is_ok_and(|round_trip| round_trip == *table),
+        &[table],
+    )
+}
+
+/// Check that minimizing a minimized table changes nothing
+pub fn minimize_idempotent(table: &TransitionTable) -> Result<(), LawViolation> {
+    let minimized = table.minimize();
+    check(
+        "minimize(minimize(t)) = minimize(t)",
+        minimized.minimize() == minimized,
+        &[table],
+    )
+}
+
+/// Check that an operation is idempotent on a table's language
+pub fn idempotent(
+    table: &TransitionTable,
+    operation: impl Fn(&TransitionTable) -> TransitionTable,
+) -> Result<(), LawViolation> {
+    let once = operation(table);
+    check(
+        "op(op(t)) = op(t)",
+        same_language(&operation(&once), &once),
+        &[table],
+    )
+}
+
+/// Check that an operation preserves a table's language
+pub fn preserves_language(
+    table: &TransitionTable,
+    operation: impl Fn(&TransitionTable) -> TransitionTable,
+) -> Result<(), LawViolation> {
+    check(
+        "op(t) = t",
+        same_language(&operation(table), table),
+        &[table],
+    )
+}
+
+/// Check that complementing twice gives back the same language
+pub fn double_complement(
+    table: &TransitionTable,
+    complement: impl Fn(&TransitionTable) -> TransitionTable,
+) -> Result<(), LawViolation> {
+    check(
+        "!!a = a",
+        same_language(&complement(&complement(table)), table),
+        &[table],
+    )
+}
+
+/// Check both of De Morgan's laws for union, intersection and complement
+pub fn de_morgan(
+    a: &TransitionTable,
+    b: &TransitionTable,
+    union: impl Fn(&TransitionTable, &TransitionTable) -> TransitionTable,
+    intersection: impl Fn(&TransitionTable, &TransitionTable) -> TransitionTable,
+    complement: impl Fn(&TransitionTable) -> TransitionTable,
+) -> Result<(), LawViolation> {
+    let (not_a, not_b) = (complement(a), complement(b));
+    check(
+        "!(a | b) = !a & !b",
+        same_language(&complement(&union(a, b)), &intersection(&not_a, &not_b)),
+        &[a, b],
+    )?;
+    check(
+        "!(a & b) = !a | !b",
+        same_language(&complement(&intersection(a, b)), &union(&not_a, &not_b)),
+        &[a, b],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::STARTING_STATE_ID;
+
+    /// Combine tables of the same width with the product construction
+    fn product(
+        a: &TransitionTable,
+        b: &TransitionTable,
+        accepting: impl Fn(bool, bool) -> bool,
+    ) -> TransitionTable {
+        let width = a.width().max(b.width());
+        let (a_total, b_total) = (a.rows.len() + 1, b.rows.len() + 1);
+        let is_accepting = |table: &TransitionTable, index: usize| {
+            table.rows.get(index).is_some_and(|r| r.accepting)
+        };
+        let start = |table: &TransitionTable| {
+            table
+                .index_of(STARTING_STATE_ID)
+                .unwrap_or(table.rows.len())
+        };
+
+        let rows = (0..a_total * b_total)
+            .map(|id| {
+                let (i, j) = (id / b_total, id % b_total);
+                TransitionTableRow {
+                    accepting: accepting(is_accepting(a, i), is_accepting(b, j)),
+                    id,
+                    transitions: (0..width)
+                        .map(|symbol| Some(a.delta(i, symbol) * b_total + b.delta(j, symbol)))
+                        .collect(),
+                    doc: None,
+                    label: None,
+                }
+            })
+            .collect();
+
+        // Move the starting pair to ID 0
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+        .renumber_reachable(start(a) * b_total + start(b))
+        .0
+    }
+
+    #[test]
+    fn laws_hold() -> Result<(), LawViolation> {
+        let mut tables = ArbitraryTables::new(1);
+        for _ in 0..50 {
+            let (a, b) = (tables.next().unwrap(), tables.next().unwrap());
+
+            parse_serialize_identity(&a)?;
+            minimize_idempotent(&a)?;
+            preserves_language(&a, TransitionTable::minimize)?;
+            double_complement(&a, TransitionTable::complement)?;
+            de_morgan(
+                &a,
+                &b,
+                |a, b| product(a, b, |a, b| a || b),
+                |a, b| product(a, b, |a, b| a && b),
+                TransitionTable::complement,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn laws_catch_violations() {
+        // Union in place of intersection breaks De Morgan's laws for some pair
+        let mut tables = ArbitraryTables::new(2);
+        let broken = (0..50).any(|_| {
+            let (a, b) = (tables.next().unwrap(), tables.next().unwrap());
+            let union = |a: &TransitionTable, b: &TransitionTable| product(a, b, |a, b| a || b);
+            de_morgan(&a, &b, union, union, TransitionTable::complement).is_err()
+        });
+        assert!(broken);
+
+        // Flipping only the starting state is not a complement
+        let table = TransitionTable::parse("- 0 1\n+ 1 1\n").unwrap();
+        let violation = double_complement(&table, |table| {
+            let mut flipped = table.clone();
+            flipped.rows[0].accepting = true;
+            flipped
+        })
+        .unwrap_err();
+        assert_eq!(violation.law, "!!a = a");
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+use std::collections::{BTreeSet, VecDeque};
+
+/// The distance between neighbouring layers in a layout
+pub const LAYER_SPACING: f64 = 120.0;
+
+/// The distance between neighbouring states within a layer
+pub const NODE_SPACING: f64 = 80.0;
+
+/// The number of ordering passes used to reduce edge crossings
+const ORDERING_PASSES: usize = 4;
+
+/// The position of a state in a layout
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NodePosition {
+    /// The state ID
+    pub id: usize,
+
+    /// The horizontal coordinate of the state's center
+    pub x: f64,
+
+    /// The vertical coordinate of the state's center
+    pub y: f64,
+}
+
+/// Coordinates for drawing a transition table as a graph
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    /// The position of every state, sorted by state ID
+    pub positions: Vec<NodePosition>,
+
+    /// The width of the bounding box of all positions
+    pub width: f64,
+
+    /// The height of the bounding box of all positions
+    pub height: f64,
+}
+
+impl Layout {
+    /// Get the position of the state with the given ID, if it exists
+    pub fn position(&self, id: usize) -> Option<&NodePosition> {
+        self.positions
+            .binary_search_by_key(&id, |position| position.id)
+            .ok()
+            .map(|index| &self.positions[index])
+    }
+}
+
+impl TransitionTable {
+    /// Compute a layered left-to-right layout of the table
+    ///
+    /// States are placed in layers by their breadth-first distance from the starting state, and the
+    /// states within each layer are ordered by the average position of their predecessors to
+    /// reduce crossings. States that are unreachable from the starting state are laid out after the
+    /// reachable ones.
+    pub fn layout(&self) -> Layout {
+        let mut layers = self.layers();
+
+        // Collect the predecessors of every state, by row index
+        let mut predecessors = vec![BTreeSet::new(); self.rows.len()];
+        for (index, row) in self.rows.iter().enumerate() {
+            for target in row.transitions.iter().flatten() {
+                if let Some(target) = self.index_of(*target) {
+                    predecessors[target].insert(index);
+                }
+            }
+        }
+
+        // Order the layers by the barycenter heuristic
+        let mut slot = vec![0.0; self.rows.len()];
+        for _ in 0..ORDERING_PASSES {
+            for layer in &mut layers {
+                for (position, index) in layer.iter().enumerate() {
+                    slot[*index] = position as f64;
+                }
+            }
+
+            for layer in layers.iter_mut().skip(1) {
+                let barycenter = |index: &usize| {
+                    let slots = predecessors[*index]
+                        .iter()
+                        .filter(|predecessor| *predecessor != index)
+                        .map(|predecessor| slot[*predecessor])
+                        .collect::<Vec<_>>();
+                    if slots.is_empty() {
+                        slot[*index]
+                    } else {
+                        slots.iter().sum::<f64>() / slots.len() as f64
+                    }
+                };
+                layer.sort_by(|a, b| barycenter(a).total_cmp(&barycenter(b)));
+                for (position, index) in layer.iter().enumerate() {
+                    slot[*index] = position as f64;
+                }
+            }
+        }
+
+        // Center every layer vertically around the tallest one
+        let tallest = layers.iter().map(Vec::len).max().unwrap_or(0);
+        let mut positions = Vec::with_capacity(self.rows.len());
+        for (layer_index, layer) in layers.iter().enumerate() {
+            let offset = (tallest - layer.len()) as f64 * NODE_SPACING / 2.0;
+            for (position, index) in layer.iter().enumerate() {
+                positions.push(NodePosition {
+                    id: self.rows[*index].id,
+                    x: layer_index as f64 * LAYER_SPACING,
+                    y: offset + position as f64 * NODE_SPACING,
+                });
+            }
+        }
+        positions.sort_by_key(|position| position.id);
+
+        Layout {
+            positions,
+            width: layers.len().saturating_sub(1) as f64 * LAYER_SPACING,
+            height: tallest.saturating_sub(1) as f64 * NODE_SPACING,
+        }
+    }
+
+    /// Assign every row index to a layer by breadth-first distance
+    ///
+    /// The search starts at the starting state, then continues from the first unvisited row until
+    /// every row is in a layer.
+    fn layers(&self) -> Vec<Vec<usize>> {
+        let mut layer_of = vec![None; self.rows.len()];
+        let mut layers: Vec<Vec<usize>> = Vec::new();
+
+        let roots = self
+            .index_of(STARTING_STATE_ID)
+            .into_iter()
+            .chain(0..self.rows.len());
+        for root in roots {
+            if layer_of[root].is_some() {
+                continue;
+            }
+
+            // Start unreachable parts in a fresh layer after everything laid out so far
+            let base = layers.len();
+            let mut queue = VecDeque::from([root]);
+            layer_of[root] = Some(base);
+
+            while let Some(index) = queue.pop_front() {
+                let layer = layer_of[index].unwrap();
+                if layers.len() <= layer {
+                    layers.push(Vec::new());
+                }
+                layers[layer].push(index);
+
+                for target in self.rows[index].transitions.iter().flatten() {
+                    if let Some(target) = self.index_of(*target) {
+                        if layer_of[target].is_none() {
+                            layer_of[target] = Some(layer + 1);
+                            queue.push_back(target);
+                        }
+                    }
+                }
+            }
+        }
+
+        layers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn layout() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 2\n- 1 3 E\n- 2 3 E\n+ 3 E 3\n- 4 E 0\n")?;
+
+        let layout = table.layout();
+        assert_eq!(layout.positions.len(), 5);
+
+        let x = |id| layout.position(id).unwrap().x;
+        assert_eq!(x(0), 0.0);
+        assert_eq!(x(1), LAYER_SPACING);
+        assert_eq!(x(2), LAYER_SPACING);
+        assert_eq!(x(3), 2.0 * LAYER_SPACING);
+        assert_eq!(x(4), 3.0 * LAYER_SPACING);
+        assert_ne!(layout.position(1).unwrap().y, layout.position(2).unwrap().y);
+        assert_eq!(layout.width, 3.0 * LAYER_SPACING);
+        assert_eq!(layout.height, NODE_SPACING);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+use crate::binary::{read_u64s, BinaryFormatError, Header, BINARY_DEAD};
+use crate::TransitionTableRow;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::rc::Rc;
+
+/// A binary table file whose rows are read on demand
+///
+/// Only the state IDs and acceptance of every row are kept in memory. Rows are read from the file
+/// when first needed and kept in a least-recently-used cache of a fixed number of rows, which suits
+/// huge tables that are only touched sparsely.
+#[derive(Debug)]
+pub struct LazyTable {
+    /// The open table file
+    file: File,
+
+    /// The file's header
+    header: Header,
+
+    /// The state ID of every row, sorted
+    ids: Vec<usize>,
+
+    /// Whether every row is accepting
+    accepting: Vec<bool>,
+
+    /// The cached rows by row index, with the tick they were last used
+    cache: HashMap<usize, (Rc<TransitionTableRow>, u64)>,
+
+    /// The cached row indices by the tick they were last used
+    recency: BTreeMap<u64, usize>,
+
+    /// The maximum number of cached rows
+    capacity: usize,
+
+    /// A counter incremented on every cache access
+    tick: u64,
+}
+
+impl LazyTable {
+    /// Open a file written by [`crate::TransitionTable::write_binary`], caching up to `capacity`
+    /// rows
+    pub fn open(path: &Path, capacity: usize) -> Result<Self, BinaryFormatError> {
+        let mut file = File::open(path)?;
+        let header = Header::read(&mut file)?;
+        let row_count = usize::try_from(header.row_count)
+            .map_err(|_| BinaryFormatError::Invalid("too many rows".to_string()))?;
+        if header.file_size() != Some(file.metadata()?.len()) {
+            return Err(BinaryFormatError::Invalid(
+                "file size doesn't match header".to_string(),
+            ));
+        }
+
+        let mut reader = std::io::BufReader::new(&mut file);
+        let ids = read_u64s(&mut reader, row_count)?
+            .into_iter()
+            .map(|id| id as usize)
+            .collect::<Vec<_>>();
+        if ids.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(BinaryFormatError::Invalid(
+                "state IDs are not sorted".to_string(),
+            ));
+        }
+        let accepting = read_u64s(&mut reader, row_count)?
+            .into_iter()
+            .map(|accepting| accepting != 0)
+            .collect();
+
+        Ok(LazyTable {
+            file,
+            header,
+            ids,
+            accepting,
+            cache: HashMap::new(),
+            recency: BTreeMap::new(),
+            capacity: capacity.max(1),
+            tick: 0,
+        })
+    }
+
+    /// Get the state IDs of all rows, sorted
+    pub fn ids(&self) -> &[usize] {
+        &self.ids
+    }
+
+    /// Check whether the state with the given ID is accepting, without reading its row
+    pub fn is_accepting(&self, id: usize) -> bool {
+        self.ids
+            .binary_search(&id)
+            .is_ok_and(|index| self.accepting[index])
+    }
+
+    /// Get the number of rows currently cached
+    pub fn cached_rows(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Get the row with the given state ID, reading it from the file if it isn't cached
+    pub fn row(&mut self, id: usize) -> Result<Option<Rc<TransitionTableRow>>, BinaryFormatError> {
+        let Ok(index) = self.ids.binary_search(&id) else {
+            return Ok(None);
+        };
+        self.tick += 1;
+
+        if let Some((row, last_used)) = self.cache.get_mut(&index) {
+            self.recency.remove(last_used);
+            self.recency.insert(self.tick, index);
+            *last_used = self.tick;
+            return Ok(Some(row.clone()));
+        }
+
+        let row = Rc::new(self.read_row(index)?);
+        if self.cache.len() == self.capacity {
+            let (_, evicted) = self.recency.pop_first().unwrap();
+            self.cache.remove(&evicted);
+        }
+        self.cache.insert(index, (row.clone(), self.tick));
+        self.recency.insert(self.tick, index);
+
+        Ok(Some(row))
+    }
+
+    /// Get the state reached from `state` on the given column
+    ///
+    /// Returns `None` for error transitions, and when the state or column does not exist.
+    pub fn next_state(
+        &mut self,
+        state: usize,
+        symbol: usize,
+    ) -> Result<Option<usize>, BinaryFormatError> {
+        Ok(self
+            .row(state)?
+            .and_then(|row| row.transitions.get(symbol).copied().flatten()))
+    }
+
+    /// Read a row from the file by row index
+    fn read_row(&mut self, index: usize) -> Result<TransitionTableRow, BinaryFormatError> {
+        let width = self.header.width as usize;
+        let offset = self.header.transitions_offset() + (index * width * 8) as u64;
+        self.file.seek(SeekFrom::Start(offset))?;
+
+        let mut bytes = vec![0; width * 8];
+        self.file.read_exact(&mut bytes)?;
+        let transitions = bytes
+            .chunks_exact(8)
+            .map(
+                |chunk| match u64::from_le_bytes(chunk.try_into().unwrap()) {
+                    BINARY_DEAD => Ok(None),
+                    target => self
+                        .ids
+                        .get(target as usize)
+                        .map(|id| Some(*id))
+                        .ok_or_else(|| {
+                            BinaryFormatError::Invalid(format!("row index {} out of range", target))
+                        }),
+                },
+            )
+            .collect::<Result<_, _>>()?;
+
+        Ok(TransitionTableRow {
+            accepting: self.accepting[index],
+            id: self.ids[index],
+            transitions,
+            doc: None,
+            label: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransitionTable;
+
+    #[test]
+    fn lazy_table() -> Result<(), BinaryFormatError> {
+        let table = TransitionTable::parse("- 0 1 E\n- 1 2 0\n+ 2 0 2\n").unwrap();
+        let path = std::env::temp_dir().join(format!("lazy-table-{}.ttb", std::process::id()));
+        table.write_binary(&mut File::create(&path)?)?;
+
+        let mut lazy = LazyTable::open(&path, 2)?;
+        assert_eq!(lazy.ids(), &[0, 1, 2]);
+        assert!(lazy.is_accepting(2));
+        assert_eq!(lazy.cached_rows(), 0);
+
+        assert_eq!(lazy.next_state(0, 0)?, Some(1));
+        assert_eq!(lazy.next_state(1, 0)?, Some(2));
+        assert_eq!(lazy.cached_rows(), 2);
+
+        // Reading a third row evicts the least recently used one
+        assert_eq!(lazy.next_state(0, 1)?, None);
+        assert_eq!(lazy.next_state(2, 1)?, Some(2));
+        assert_eq!(lazy.cached_rows(), 2);
+        assert!(lazy.cache.contains_key(&0));
+        assert!(!lazy.cache.contains_key(&1));
+
+        assert_eq!(*lazy.row(1)?.unwrap(), table.rows[1]);
+        assert!(lazy.row(5)?.is_none());
+
+        // Unsorted state IDs are rejected, since rows are found by binary search
+        let mut bytes = std::fs::read(&path)?;
+        let ids = crate::binary::HEADER_SIZE as usize..crate::binary::HEADER_SIZE as usize + 16;
+        bytes[ids].rotate_left(8);
+        std::fs::write(&path, &bytes)?;
+        assert!(matches!(
+            LazyTable::open(&path, 2),
+            Err(BinaryFormatError::Invalid(_))
+        ));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
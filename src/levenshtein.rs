@@ -0,0 +1,150 @@
+use crate::{Alphabet, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::{HashMap, VecDeque};
+
+impl TransitionTable {
+    /// Build the DFA accepting every string within edit distance `k` of `word`
+    ///
+    /// Characters are compared by their columns in `alphabet`, and each insertion, deletion, or
+    /// substitution of a column costs 1. States are the distances between the input so far and
+    /// every prefix of the word, capped at `k + 1`, so the table is as small as the construction
+    /// allows without minimizing. Returns `None` if a character of the word has no column.
+    pub fn levenshtein(word: &str, k: usize, alphabet: &Alphabet) -> Option<TransitionTable> {
+        let word: Vec<usize> = word
+            .chars()
+            .map(|c| alphabet.classify(c))
+            .collect::<Option<_>>()?;
+        let cap = k + 1;
+
+        // Reading nothing, reaching each prefix of the word takes one deletion per character
+        let start: Vec<usize> = (0..=word.len()).map(|i| i.min(cap)).collect();
+        let step = |distances: &[usize], symbol: usize| {
+            let mut next = Vec::with_capacity(distances.len());
+            next.push((distances[0] + 1).min(cap));
+            for i in 1..distances.len() {
+                let substitute = distances[i - 1] + usize::from(word[i - 1] != symbol);
+                let insert = distances[i] + 1;
+                let delete = next[i - 1] + 1;
+                next.push(substitute.min(insert).min(delete).min(cap));
+            }
+            next
+        };
+
+        let mut ids = HashMap::from([(start.clone(), STARTING_STATE_ID)]);
+        let mut queue = VecDeque::from([start]);
+        let mut rows = Vec::new();
+        while let Some(distances) = queue.pop_front() {
+            let transitions = (0..alphabet.len())
+                .map(|symbol| {
+                    let next = step(&distances, symbol);
+                    if next.iter().all(|distance| *distance > k) {
+                        return None;
+                    }
+
+                    let next_id = ids.len();
+                    Some(*ids.entry(next.clone()).or_insert_with(|| {
+                        queue.push_back(next);
+                        next_id
+                    }))
+                })
+                .collect();
+
+            rows.push(TransitionTableRow {
+                accepting: distances[word.len()] <= k,
+                id: ids[&distances],
+                transitions,
+                doc: None,
+                label: None,
+            });
+        }
+
+        Some(TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        })
+    }
+
+    /// Find the shortest string the table accepts within edit distance `k` of `word`
+    ///
+    /// Searches the intersection of the table with [`TransitionTable::levenshtein`] breadth-first,
+    /// returning the columns of the string found, or `None` if there is none or a character of the
+    /// word has no column.
+    pub fn fuzzy_lookup(&self, word: &str, k: usize, alphabet: &Alphabet) -> Option<Vec<usize>> {
+        let fuzzy = TransitionTable::levenshtein(word, k, alphabet)?;
+        let start = (self.row(STARTING_STATE_ID)?.id, STARTING_STATE_ID);
+
+        // The pair of states each pair was first reached from, with the column taken
+        type Pair = (usize, usize);
+        let mut parents: HashMap<Pair, Option<(Pair, usize)>> = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(pair) = queue.pop_front() {
+            let (state, distances) = pair;
+            if self.row(state).unwrap().accepting && fuzzy.row(distances).unwrap().accepting {
+                let mut symbols = Vec::new();
+                let mut current = pair;
+                while let Some((parent, symbol)) = parents[&current] {
+                    symbols.push(symbol);
+                    current = parent;
+                }
+                symbols.reverse();
+                return Some(symbols);
+            }
+
+            for symbol in 0..alphabet.len() {
+                let next = self
+                    .next_state(state, symbol)
+                    .zip(fuzzy.next_state(distances, symbol));
+                if let Some(next) = next {
+                    parents.entry(next).or_insert_with(|| {
+                        queue.push_back(next);
+                        Some((pair, symbol))
+                    });
+                }
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn levenshtein() {
+        let alphabet = Alphabet::from_chars("abc".chars());
+        let table = TransitionTable::levenshtein("abc", 1, &alphabet).unwrap();
+        let accepts = |s: &str| {
+            table
+                .run_str(&alphabet, s)
+                .is_some_and(|state| table.row(state).unwrap().accepting)
+        };
+
+        for s in ["abc", "ab", "bc", "abcc", "aabc", "abb", "xabc"] {
+            assert_eq!(accepts(s), s != "xabc", "{}", s);
+        }
+        for s in ["", "a", "cba", "aabbc", "acb"] {
+            assert!(!accepts(s), "{}", s);
+        }
+
+        assert!(TransitionTable::levenshtein("abd", 1, &alphabet).is_none());
+        assert_eq!(table.minimize(), table.minimize().minimize());
+    }
+
+    #[test]
+    fn fuzzy_lookup() -> Result<(), ParseSerializeError> {
+        let alphabet = Alphabet::from_chars("abc".chars());
+
+        // Strings of one or more a's followed by c
+        let table = TransitionTable::parse("- 0 1 E E\n- 1 1 E 2\n+ 2 E E E\n")?;
+
+        assert_eq!(table.fuzzy_lookup("aac", 0, &alphabet), Some(vec![0, 0, 2]));
+        assert_eq!(table.fuzzy_lookup("abc", 1, &alphabet), Some(vec![0, 2]));
+        assert_eq!(table.fuzzy_lookup("bbb", 2, &alphabet), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+use crate::{Alphabet, TransitionTable, STARTING_STATE_ID};
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// A change of mode made when a [`ModedTable`] accepts in a given state
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ModeSwitch {
+    /// Enter a mode, returning to the current one on [`ModeSwitch::Pop`]
+    Push(usize),
+
+    /// Return to the mode that pushed the current one, doing nothing in the outermost mode
+    Pop,
+
+    /// Replace the current mode
+    Goto(usize),
+}
+
+/// One table of a [`ModedTable`] and the mode switches made by its accepting states
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mode {
+    /// The table matching lexemes in the mode
+    pub table: TransitionTable,
+
+    /// The alphabet classifying characters into the table's columns
+    pub alphabet: Alphabet,
+
+    /// The switch made after a lexeme ends in each state, by state ID
+    pub switches: HashMap<usize, ModeSwitch>,
+}
+
+/// Several tables with switches between them, for lexers whose rules depend on context
+///
+/// Scanning starts in mode 0 and repeatedly takes the longest lexeme the current mode's table
+/// accepts. When a lexeme ends in a state with a switch, the switch takes effect for the next
+/// lexeme, such as entering a string mode on an opening quote and leaving it on the closing one.
+/// Modes form a stack, so nested modes return to the mode they were entered from.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModedTable {
+    /// The modes, by index
+    pub modes: Vec<Mode>,
+}
+
+/// A lexeme found by a [`Scanner`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lexeme {
+    /// The mode the lexeme was matched in
+    pub mode: usize,
+
+    /// The accepting state the lexeme ended in
+    pub state: usize,
+
+    /// The byte range of the lexeme in the input
+    pub span: Range<usize>,
+}
+
+/// An error from a [`Scanner`] when no non-empty lexeme starts at a position
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("no lexeme in mode {mode} starts at byte {position}")]
+pub struct ScanError {
+    /// The mode being scanned in
+    pub mode: usize,
+
+    /// The byte offset where scanning failed
+    pub position: usize,
+}
+
+impl ModedTable {
+    /// Create a moded table with no modes
+    pub fn new() -> Self {
+        ModedTable::default()
+    }
+
+    /// Add a mode without switches, returning its index
+    pub fn add_mode(&mut self, table: TransitionTable, alphabet: Alphabet) -> usize {
+        self.modes.push(Mode {
+            table,
+            alphabet,
+            switches: HashMap::new(),
+        });
+        self.modes.len() - 1
+    }
+
+    /// Make lexemes ending in a state of a mode switch modes
+    ///
+    /// Panics if the mode does not exist.
+    pub fn set_switch(&mut self, mode: usize, state: usize, switch: ModeSwitch) {
+        self.modes[mode].switches.insert(state, switch);
+    }
+
+    /// Scan a string into lexemes, starting in mode 0
+    pub fn scan<'a>(&'a self, input: &'a str) -> Scanner<'a> {
+        Scanner {
+            table: self,
+            input,
+            position: 0,
+            stack: vec![0],
+            failed: false,
+        }
+    }
+}
+
+/// An iterator over the lexemes of a string, created by [`ModedTable::scan`]
+///
+/// After an error, the iterator ends.
+#[derive(Clone, Debug)]
+pub struct Scanner<'a> {
+    /// The modes being scanned with
+    table: &'a ModedTable,
+
+    /// The string being scanned
+    input: &'a str,
+
+    /// The byte offset of the next lexeme
+    position: usize,
+
+    /// The stack of modes, with the current mode on top
+    stack: Vec<usize>,
+
+    /// Whether scanning has failed
+    failed: bool,
+}
+
+impl Scanner<'_> {
+    /// Get the current mode
+    pub fn mode(&self) -> usize {
+        *self.stack.last().unwrap()
+    }
+
+    /// Get the byte offset of the next lexeme
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl Iterator for Scanner<'_> {
+    type Item = Result<Lexeme, ScanError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.position == self.input.len() {
+            return None;
+        }
+
+        let mode = self.mode();
+        let error = ScanError {
+            mode,
+            position: self.position,
+        };
+        let Some(Mode {
+            table,
+            alphabet,
+            switches,
+        }) = self.table.modes.get(mode)
+        else {
+            self.failed = true;
+            return Some(Err(error));
+        };
+
+        // Find the longest non-empty lexeme and the state it ends in
+        let mut longest = None;
+        let mut state = STARTING_STATE_ID;
+        for (offset, c) in self.input[self.position..].char_indices() {
+            let next = alphabet
+                .classify(c)
+                .and_then(|symbol| table.next_state(state, symbol));
+            let Some(next) = next else {
+                break;
+            };
+
+            state = next;
+            if table.row(state).is_some_and(|row| row.accepting) {
+                longest = Some((self.position + offset + c.len_utf8(), state));
+            }
+        }
+        let Some((end, state)) = longest else {
+            self.failed = true;
+            return Some(Err(error));
+        };
+
+        match switches.get(&state) {
+            Some(ModeSwitch::Push(mode)) => self.stack.push(*mode),
+            Some(ModeSwitch::Pop) if self.stack.len() > 1 => {
+                self.stack.pop();
+            }
+            Some(ModeSwitch::Pop) | None => {}
+            Some(ModeSwitch::Goto(mode)) => *self.stack.last_mut().unwrap() = *mode,
+        }
+
+        let span = self.position..end;
+        self.position = end;
+        Some(Ok(Lexeme { mode, state, span }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharSet, ParseSerializeError};
+
+    #[test]
+    fn moded_scanner() -> Result<(), ParseSerializeError> {
+        // Code is words, spaces, or a quote entering string mode
+        let code_alphabet = Alphabet::new(vec![
+            CharSet::new([('a', 'z')]),
+            CharSet::single(' '),
+            CharSet::single('"'),
+        ]);
+        let code = TransitionTable::parse("- 0 1 2 3\n+ 1 1 E E\n+ 2 E 2 E\n+ 3 E E E\n")?;
+
+        // Strings are runs of anything but quotes, or the closing quote
+        let string_alphabet = Alphabet::new(vec![
+            CharSet::single('"').complement(),
+            CharSet::single('"'),
+        ]);
+        let string = TransitionTable::parse("- 0 1 2\n+ 1 1 E\n+ 2 E E\n")?;
+
+        let mut lexer = ModedTable::new();
+        let code_mode = lexer.add_mode(code, code_alphabet);
+        let string_mode = lexer.add_mode(string, string_alphabet);
+        lexer.set_switch(code_mode, 3, ModeSwitch::Push(string_mode));
+        lexer.set_switch(string_mode, 2, ModeSwitch::Pop);
+
+        let lexemes = lexer
+            .scan("say \"hi there\" ok")
+            .map(|lexeme| lexeme.map(|lexeme| (lexeme.mode, lexeme.state, lexeme.span)))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            lexemes,
+            vec![
+                (0, 1, 0..3),
+                (0, 2, 3..4),
+                (0, 3, 4..5),
+                (1, 1, 5..13),
+                (1, 2, 13..14),
+                (0, 2, 14..15),
+                (0, 1, 15..17),
+            ]
+        );
+
+        let mut scanner = lexer.scan("ok!");
+        assert!(scanner.next().unwrap().is_ok());
+        assert_eq!(
+            scanner.next(),
+            Some(Err(ScanError {
+                mode: 0,
+                position: 2
+            }))
+        );
+        assert_eq!(scanner.next(), None);
+
+        Ok(())
+    }
+}
@@ -1,3 +1,143 @@
+// Lets the derive macro's `::transition_tables` paths resolve inside the crate
+extern crate self as transition_tables;
+
+mod alphabet;
+mod analysis;
+mod approximate;
+mod automaton;
+mod batch;
+#[cfg(feature = "bdd")]
+mod bdd;
+#[cfg(feature = "bench")]
+pub mod bench;
+mod binary;
+mod bit_set;
+mod builder;
+mod cache;
+mod certificate;
+mod char_set;
+mod codegen;
+mod column_merge;
+mod combinator;
+mod complement;
+mod completion;
+mod corpus;
+mod debugger;
+mod decompose;
+mod determinize;
+mod divergence;
+mod dot;
+mod edges;
+mod elimination;
+mod equivalence;
+mod external;
+mod fixed;
+mod flat;
+mod frozen;
+mod impact;
+mod import;
+mod inclusion;
+mod incremental;
+mod inline;
+mod json;
+pub mod laws;
+mod layout;
+mod lazy;
+mod levenshtein;
+mod lexer;
+mod markdown;
+mod matrix;
+mod merge;
+mod mermaid;
+mod metadata;
+mod minimize;
+mod monitor;
+mod nfa;
+mod optimize;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod parse_error;
+mod product;
+mod regex;
+mod renumber;
+mod rng;
+mod run_config;
+mod runner;
+mod safety;
+mod sample;
+mod search;
+mod session;
+mod state_machine;
+mod stream;
+mod svg;
+mod symbol_map;
+mod symbolic;
+pub mod testing;
+mod tikz;
+mod token;
+mod trace;
 mod transition_table;
+mod union;
+mod utf8;
+mod validate;
+mod yaml;
 
+pub use alphabet::*;
+pub use approximate::*;
+pub use automaton::*;
+#[cfg(feature = "bdd")]
+pub use bdd::*;
+pub use binary::*;
+pub use bit_set::*;
+pub use builder::*;
+pub use cache::*;
+pub use certificate::*;
+pub use char_set::*;
+pub use codegen::*;
+pub use combinator::*;
+pub use completion::*;
+pub use corpus::*;
+pub use debugger::*;
+pub use decompose::*;
+pub use elimination::*;
+pub use equivalence::*;
+pub use external::*;
+pub use fixed::*;
+pub use frozen::*;
+pub use impact::*;
+pub use import::*;
+pub use inclusion::*;
+pub use incremental::*;
+pub use inline::*;
+pub use layout::*;
+pub use lazy::*;
+pub use lexer::*;
+pub use matrix::*;
+pub use merge::*;
+pub use metadata::*;
+pub use monitor::*;
+pub use nfa::*;
+pub use optimize::*;
+pub use parse_error::*;
+pub use regex::*;
+pub use renumber::*;
+pub use rng::*;
+pub use run_config::*;
+pub use runner::*;
+pub use safety::*;
+pub use sample::*;
+pub use search::*;
+pub use session::*;
+pub use state_machine::*;
+pub use stream::*;
+pub use svg::*;
+pub use symbol_map::*;
+pub use symbolic::*;
+pub use token::*;
+pub use trace::*;
 pub use transition_table::*;
+#[cfg(feature = "derive")]
+pub use transition_tables_derive::StateMachine;
+pub use union::*;
+pub use utf8::*;
+pub use validate::*;
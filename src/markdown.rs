@@ -0,0 +1,100 @@
+use crate::{Alphabet, TransitionTable};
+use std::fmt::Write;
+
+impl TransitionTable {
+    /// Export the table as a Markdown table, with a column per table column labeled by its index
+    pub fn to_markdown(&self) -> String {
+        let labels: Vec<String> = (0..self.width()).map(|column| column.to_string()).collect();
+        self.to_markdown_labeled(&labels, &[])
+    }
+
+    /// Export the table as a Markdown table, labeling columns with their character sets
+    ///
+    /// If any column has a description, a second table after it lists the described columns.
+    pub fn to_markdown_with_alphabet(&self, alphabet: &Alphabet) -> String {
+        let labels: Vec<String> = (0..self.width())
+            .map(|column| {
+                if column < alphabet.len() {
+                    alphabet.label(column)
+                } else {
+                    column.to_string()
+                }
+            })
+            .collect();
+        self.to_markdown_labeled(&labels, &alphabet.legend())
+    }
+
+    /// Export the table as a Markdown table with a label for every column, followed by a legend
+    /// of column labels and descriptions if there are any
+    ///
+    /// Transitions are written as in the text format, with `E` for error transitions.
+    fn to_markdown_labeled(&self, labels: &[String], legend: &[(String, &str)]) -> String {
+        let mut output = String::from("| | State |");
+        for label in labels {
+            write!(output, " {} |", code(label)).unwrap();
+        }
+        output.push_str("\n| --- | --- |");
+        output.push_str(&" --- |".repeat(labels.len()));
+        output.push('\n');
+
+        for row in &self.rows {
+            let marker = if row.accepting { '+' } else { '-' };
+            write!(output, "| {} | {} |", marker, row.id).unwrap();
+            for column in 0..labels.len() {
+                match row.transitions.get(column).copied().flatten() {
+                    Some(target) => write!(output, " {} |", target).unwrap(),
+                    None => output.push_str(" E |"),
+                }
+            }
+            output.push('\n');
+        }
+
+        if !legend.is_empty() {
+            output.push_str("\n| Column | Description |\n| --- | --- |\n");
+            for (label, description) in legend {
+                writeln!(
+                    output,
+                    "| {} | {} |",
+                    code(label),
+                    description.replace('|', "\\|")
+                )
+                .unwrap();
+            }
+        }
+
+        output
+    }
+}
+
+/// Write a label as a code span that is safe inside a table cell
+fn code(label: &str) -> String {
+    let label = label.replace('|', "\\|");
+    if label.contains('`') {
+        format!("`` {} ``", label)
+    } else {
+        format!("`{}`", label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alphabet, TransitionTable};
+
+    #[test]
+    fn to_markdown() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n").unwrap();
+        assert_eq!(
+            table.to_markdown(),
+            "| | State | `0` | `1` |\n| --- | --- | --- | --- |\n| - | 0 | 1 | E |\n\
+             | + | 1 | 1 | 0 |\n"
+        );
+
+        let alphabet = Alphabet::parse(r#"[0-9]="digit" [|]="pipe | bar""#).unwrap();
+        assert_eq!(
+            table.to_markdown_with_alphabet(&alphabet),
+            "| | State | `[0-9]` | `[\\|]` |\n| --- | --- | --- | --- |\n| - | 0 | 1 | E |\n\
+             | + | 1 | 1 | 0 |\n\n| Column | Description |\n| --- | --- |\n\
+             | `[0-9]` | digit |\n| `[\\|]` | pipe \\| bar |\n"
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use crate::TransitionTable;
+use std::ops::{Index, IndexMut};
+
+/// A dense row-major matrix
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix<T> {
+    /// The number of rows
+    rows: usize,
+
+    /// The number of columns
+    columns: usize,
+
+    /// The entries, one row after another
+    data: Vec<T>,
+}
+
+impl<T: Clone + Default> Matrix<T> {
+    /// Create a matrix filled with the default value
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Matrix {
+            rows,
+            columns,
+            data: vec![T::default(); rows * columns],
+        }
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Get the number of rows
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Get the number of columns
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Get the entries one row after another
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    /// Get a row of entries
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.columns..(row + 1) * self.columns]
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (row, column): (usize, usize)) -> &T {
+        assert!(column < self.columns, "column out of bounds");
+        &self.data[row * self.columns + column]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (row, column): (usize, usize)) -> &mut T {
+        assert!(column < self.columns, "column out of bounds");
+        &mut self.data[row * self.columns + column]
+    }
+}
+
+impl TransitionTable {
+    /// Get the adjacency matrix of the table's rows, indexed by row index rather than state ID
+    ///
+    /// Each entry counts the columns taking one state to another, so the entries of its `n`th
+    /// power count the strings of length `n` between states. Error transitions and transitions to
+    /// missing states are left out.
+    pub fn to_adjacency_matrix(&self) -> Matrix<u64> {
+        let mut matrix = Matrix::new(self.rows.len(), self.rows.len());
+        for (from, (_, to)) in self.indexed_transitions() {
+            matrix[(from, to)] += 1;
+        }
+        matrix
+    }
+
+    /// Get an adjacency matrix for each column, indexed by row index rather than state ID
+    ///
+    /// An entry is 1 when the column takes one state to the other, so the matrices sum to
+    /// [`TransitionTable::to_adjacency_matrix`].
+    pub fn to_symbol_matrices(&self) -> Vec<Matrix<u64>> {
+        let mut matrices = vec![Matrix::new(self.rows.len(), self.rows.len()); self.width()];
+        for (from, (symbol, to)) in self.indexed_transitions() {
+            matrices[symbol][(from, to)] = 1;
+        }
+        matrices
+    }
+
+    /// Iterate over the transitions as a row index paired with the column and target row index
+    fn indexed_transitions(&self) -> impl Iterator<Item = (usize, (usize, usize))> + '_ {
+        self.rows.iter().enumerate().flat_map(move |(from, row)| {
+            row.transitions
+                .iter()
+                .enumerate()
+                .filter_map(move |(symbol, target)| {
+                    Some((from, (symbol, self.index_of((*target)?)?)))
+                })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn adjacency_matrices() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 1 E\n+ 1 1 0 7\n")?;
+
+        let adjacency = table.to_adjacency_matrix();
+        assert_eq!((adjacency.rows(), adjacency.columns()), (2, 2));
+        assert_eq!(adjacency.as_slice(), &[0, 2, 1, 1]);
+
+        let matrices = table.to_symbol_matrices();
+        assert_eq!(matrices.len(), 3);
+        assert_eq!(matrices[0].as_slice(), &[0, 1, 0, 1]);
+        assert_eq!(matrices[1].row(1), &[1, 0]);
+        assert_eq!(matrices[2].as_slice(), &[0, 0, 0, 0]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,82 @@
+use crate::TransitionTable;
+
+/// What merging two states would do, from [`TransitionTable::preview_merge`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergePreview {
+    /// The table with the states merged
+    pub merged: TransitionTable,
+
+    /// A shortest input the merged table gives a different answer for, if there is one
+    pub counterexample: Option<Vec<usize>>,
+}
+
+impl MergePreview {
+    /// Check whether the merge keeps the table's language
+    pub fn preserves_language(&self) -> bool {
+        self.counterexample.is_none()
+    }
+}
+
+impl TransitionTable {
+    /// Merge two states into the one with the smaller ID
+    ///
+    /// The merged state keeps that state's acceptance and transitions, and transitions into the
+    /// other state are redirected to it. Returns `None` if either state does not exist.
+    pub fn merge_states(&self, a: usize, b: usize) -> Option<TransitionTable> {
+        self.row(a)?;
+        self.row(b)?;
+        let (kept, removed) = (a.min(b), a.max(b));
+
+        let mut merged = self.clone();
+        merged
+            .rows
+            .retain(|row| row.id != removed || removed == kept);
+        for row in &mut merged.rows {
+            for target in row.transitions.iter_mut().flatten() {
+                if *target == removed {
+                    *target = kept;
+                }
+            }
+        }
+
+        Some(merged)
+    }
+
+    /// Preview merging two states with [`TransitionTable::merge_states`], checking whether the
+    /// merge keeps the language
+    ///
+    /// When it doesn't, the preview has a shortest input the original and merged tables disagree
+    /// on. Returns `None` if either state does not exist.
+    pub fn preview_merge(&self, a: usize, b: usize) -> Option<MergePreview> {
+        let merged = self.merge_states(a, b)?;
+        let counterexample = TransitionTable::shortest_divergence(self, &merged);
+        Some(MergePreview {
+            merged,
+            counterexample,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn preview_merge() -> Result<(), ParseSerializeError> {
+        // States 1 and 2 are equivalent, but 3 accepts
+        let table = TransitionTable::parse("- 0 1 2\n- 1 3 E\n- 2 3 E\n+ 3 E 3\n")?;
+
+        let safe = table.preview_merge(2, 1).unwrap();
+        assert!(safe.preserves_language());
+        assert_eq!(safe.merged.serialize(), "- 0 1 1\n- 1 3 E\n+ 3 E 3\n");
+
+        let unsafe_merge = table.preview_merge(1, 3).unwrap();
+        assert!(!unsafe_merge.preserves_language());
+        assert_eq!(unsafe_merge.counterexample, Some(vec![0, 0]));
+
+        assert!(table.preview_merge(0, 7).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+impl TransitionTable {
+    /// Export the table as a Mermaid state diagram, labeling transitions with the character sets
+    /// of the table's [`TransitionTable::alphabet`], or with column indices if it has none
+    ///
+    /// Every state is shown by its [`TransitionTableRow::name`](crate::TransitionTableRow::name),
+    /// so labeled states show their labels. The starting state is entered from `[*]`, accepting
+    /// states lead to `[*]`, and transitions between the same pair of states are one arrow
+    /// labeled with all of their symbols.
+    pub fn to_mermaid(&self) -> String {
+        let mut output = String::from("stateDiagram-v2\n");
+        for row in &self.rows {
+            writeln!(
+                output,
+                "    state \"{}\" as s{}",
+                escape(&row.name()),
+                row.id
+            )
+            .unwrap();
+        }
+        if self.row(STARTING_STATE_ID).is_some() {
+            writeln!(output, "    [*] --> s{}", STARTING_STATE_ID).unwrap();
+        }
+
+        for row in &self.rows {
+            let mut edges: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+            for (column, target) in row.transitions.iter().enumerate() {
+                if let Some(target) = target {
+                    let symbol = match &self.alphabet {
+                        Some(alphabet) if column < alphabet.len() => alphabet.label(column),
+                        _ => column.to_string(),
+                    };
+                    edges.entry(*target).or_default().push(symbol);
+                }
+            }
+            for (target, symbols) in edges {
+                let label = escape(&symbols.join(", "));
+                writeln!(output, "    s{} --> s{} : {}", row.id, target, label).unwrap();
+            }
+            if row.accepting {
+                writeln!(output, "    s{} --> [*]", row.id).unwrap();
+            }
+        }
+
+        output
+    }
+}
+
+/// Escape text for Mermaid, which reads `#...;` as an entity code and `"` as the end of a name
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '#' => escaped.push_str("#35;"),
+            ';' => escaped.push_str("#59;"),
+            '"' => escaped.push_str("#quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseSerializeError, TransitionTable};
+
+    #[test]
+    fn to_mermaid() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("alphabet: [a] [;]\n- 0 1 1\n+ 1 accept 1 E\n")?;
+        assert_eq!(
+            table.to_mermaid(),
+            "stateDiagram-v2\n    state \"0\" as s0\n    state \"1 (accept)\" as s1\n    \
+             [*] --> s0\n    s0 --> s1 : [a], [#59;]\n    s1 --> s1 : [a]\n    s1 --> [*]\n"
+        );
+        Ok(())
+    }
+}
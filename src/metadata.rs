@@ -0,0 +1,104 @@
+use crate::TransitionTable;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The metadata key naming the tool that generated a table
+pub const META_GENERATOR: &str = "generator";
+
+/// The metadata key for when a table was generated, in seconds since the Unix epoch
+pub const META_TIMESTAMP: &str = "timestamp";
+
+/// The metadata key for the [`source_hash`] of the regex or grammar a table was generated from
+pub const META_SOURCE_HASH: &str = "source-hash";
+
+impl TransitionTable {
+    /// Get the value of a metadata key, if the table has it
+    pub fn meta(&self, key: &str) -> Option<&str> {
+        self.metadata.get(key).map(String::as_str)
+    }
+
+    /// Set the value of a metadata key
+    ///
+    /// Keys can't contain whitespace and values can't contain newlines, or the serialized table
+    /// won't parse back the same.
+    pub fn with_meta(mut self, key: &str, value: &str) -> Self {
+        self.metadata.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    /// Record the generator of the table and the hash of its source
+    ///
+    /// No timestamp is recorded, so generating a table twice from the same source gives equal
+    /// tables and identical serialized output.
+    pub fn with_provenance(self, generator: &str, source: &str) -> Self {
+        self.with_meta(META_GENERATOR, generator)
+            .with_meta(META_SOURCE_HASH, &source_hash(source))
+    }
+
+    /// Record the provenance like [`TransitionTable::with_provenance`], along with the time the
+    /// table was generated, such as [`SystemTime::now`]
+    pub fn with_provenance_at(self, generator: &str, source: &str, time: SystemTime) -> Self {
+        let timestamp = time
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        self.with_provenance(generator, source)
+            .with_meta(META_TIMESTAMP, &timestamp.to_string())
+    }
+
+    /// Check whether the table records being generated from a source, by its hash
+    ///
+    /// A table without a source hash wasn't generated from anything as far as it knows.
+    pub fn was_generated_from(&self, source: &str) -> bool {
+        self.meta(META_SOURCE_HASH) == Some(source_hash(source).as_str())
+    }
+}
+
+/// Hash the source of a table for [`META_SOURCE_HASH`]
+///
+/// The hash is 64-bit FNV-1a in 16 hexadecimal digits, which is stable across platforms and
+/// versions, unlike the standard library's hashers.
+pub fn source_hash(source: &str) -> String {
+    format!("{:016x}", fnv1a(source.as_bytes()))
+}
+
+/// Hash bytes with 64-bit FNV-1a
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ParseError, ParseSerializeError};
+
+    #[test]
+    fn provenance() -> Result<(), ParseSerializeError> {
+        let (table, _) = TransitionTable::from_regex("a+")?;
+        assert_eq!(table.meta(META_GENERATOR), Some("transition-tables regex"));
+        assert_eq!(table.meta(META_TIMESTAMP), None);
+        assert_eq!(table, TransitionTable::from_regex("a+")?.0);
+        assert!(table.was_generated_from("a+"));
+        assert!(!table.was_generated_from("a*"));
+        assert_eq!(source_hash(""), "cbf29ce484222325");
+
+        // Metadata survives serialization and transformations
+        let time = UNIX_EPOCH + std::time::Duration::from_secs(1_700_000_000);
+        let table = table.with_provenance_at("transition-tables regex", "a+", time);
+        let serialized = table.serialize();
+        assert_eq!(
+            serialized,
+            "%meta generator transition-tables regex\n%meta source-hash 089bfd07b5451fc5\n\
+             %meta timestamp 1700000000\n- 0 1\n+ 1 1\n"
+        );
+        assert_eq!(TransitionTable::parse(&serialized)?, table);
+        assert_eq!(table.minimize().metadata, table.metadata);
+
+        assert!(matches!(
+            TransitionTable::parse("%meta\n- 0\n"),
+            Err(ParseError::InvalidMeta { .. })
+        ));
+
+        Ok(())
+    }
+}
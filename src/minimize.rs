@@ -0,0 +1,331 @@
+use crate::{Renumbering, TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+
+impl TransitionTable {
+    /// Compute the minimal DFA accepting the same language
+    ///
+    /// Uses Hopcroft's partition refinement algorithm. Error transitions are treated as transitions
+    /// to an implicit dead state, so states that can never reach an accepting state are removed
+    /// and transitions to them become error transitions. Unreachable states are dropped, and the
+    /// result is renumbered in breadth-first order from the starting state, so two tables accept
+    /// the same language exactly when their minimized tables are equal.
+    ///
+    /// States that are not merged with any other keep their docs and labels, while merged states
+    /// drop them, since the docs of equivalent states may disagree.
+    pub fn minimize(&self) -> TransitionTable {
+        self.minimize_with(Renumbering::BreadthFirst)
+    }
+
+    /// Compute the minimal DFA like [`TransitionTable::minimize`], numbering its states with a
+    /// strategy
+    ///
+    /// Minimized tables only compare equal for equal languages when numbered by a strategy other
+    /// than [`Renumbering::Preserve`].
+    pub fn minimize_with(&self, renumbering: Renumbering) -> TransitionTable {
+        let (block_of, block_count) = self.equivalence_classes();
+        self.quotient(&block_of, block_count, renumbering)
+    }
+
+    /// Merge the states in each block of a partition of the row indices, plus the sink
+    ///
+    /// Blocks must only contain equivalent states. The result is trimmed like
+    /// [`TransitionTable::minimize`], and renumbered with the given strategy.
+    pub(crate) fn quotient(
+        &self,
+        block_of: &[usize],
+        block_count: usize,
+        renumbering: Renumbering,
+    ) -> TransitionTable {
+        self.quotient_blocks(block_of, block_count, renumbering).0
+    }
+
+    /// Like [`TransitionTable::quotient`], also returning the block of each row of the result
+    ///
+    /// The blocks are empty when the result is the empty language.
+    pub(crate) fn quotient_blocks(
+        &self,
+        block_of: &[usize],
+        block_count: usize,
+        renumbering: Renumbering,
+    ) -> (TransitionTable, Vec<usize>) {
+        let width = self.width();
+        let empty = || TransitionTable {
+            alphabet: self.alphabet.clone(),
+            metadata: self.metadata.clone(),
+            comment: self.comment.clone(),
+            ..TransitionTable::empty_language(width)
+        };
+        let Some(start) = self.index_of(STARTING_STATE_ID) else {
+            return (empty(), Vec::new());
+        };
+
+        let sink = block_of[self.rows.len()];
+        if block_of[start] == sink {
+            return (empty(), Vec::new());
+        }
+
+        // Build the quotient table with one state per block, using its first member's transitions
+        // and ID, which is the smallest. Blocks of a single state also keep its doc and label
+        let mut representatives = vec![None; block_count];
+        let mut sizes = vec![0; block_count];
+        for (index, block) in block_of.iter().enumerate().take(self.rows.len()) {
+            representatives[*block].get_or_insert(index);
+            sizes[*block] += 1;
+        }
+        let label = |block: usize| self.rows[representatives[block].unwrap()].id;
+        let rows = representatives
+            .iter()
+            .enumerate()
+            .filter(|(block, _)| *block != sink)
+            .map(|(block, representative)| {
+                let row = &self.rows[representative.unwrap()];
+                let unmerged = sizes[block] == 1;
+                TransitionTableRow {
+                    accepting: row.accepting,
+                    id: row.id,
+                    transitions: (0..width)
+                        .map(|symbol| {
+                            Some(block_of[self.delta(representative.unwrap(), symbol)])
+                                .filter(|target| *target != sink)
+                                .map(label)
+                        })
+                        .collect(),
+                    doc: row.doc.clone().filter(|_| unmerged),
+                    label: row.label.clone().filter(|_| unmerged),
+                }
+            })
+            .collect();
+
+        let mut quotient = TransitionTable {
+            rows,
+            alphabet: self.alphabet.clone(),
+            metadata: self.metadata.clone(),
+            comment: self.comment.clone(),
+        };
+        quotient.rows.sort_by_key(|row| row.id);
+        let (table, labels) = quotient.renumber_reachable_with(label(block_of[start]), renumbering);
+        let blocks = labels
+            .iter()
+            .map(|id| block_of[self.index_of(*id).unwrap()])
+            .collect();
+
+        (table, blocks)
+    }
+
+    /// Get the number of columns in the widest row
+    pub fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.transitions.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Create a table with a single non-accepting state and only error transitions
+    pub(crate) fn empty_language(width: usize) -> TransitionTable {
+        TransitionTable {
+            rows: vec![TransitionTableRow {
+                accepting: false,
+                id: STARTING_STATE_ID,
+                transitions: vec![None; width],
+                doc: None,
+                label: None,
+            }],
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+
+    /// Get the row index reached from a row index on a column, completing the table with a sink
+    ///
+    /// The sink has index `rows.len()`, and every error transition enters it.
+    pub(crate) fn delta(&self, index: usize, symbol: usize) -> usize {
+        let sink = self.rows.len();
+        if index == sink {
+            return sink;
+        }
+
+        self.rows[index]
+            .transitions
+            .get(symbol)
+            .copied()
+            .flatten()
+            .and_then(|target| self.index_of(target))
+            .unwrap_or(sink)
+    }
+
+    /// Partition the row indices, plus the sink, into blocks of equivalent states
+    ///
+    /// Returns the block of every index and the number of blocks.
+    pub(crate) fn equivalence_classes(&self) -> (Vec<usize>, usize) {
+        let width = self.width();
+        let total = self.rows.len() + 1;
+        let accepting = |index: usize| index < self.rows.len() && self.rows[index].accepting;
+
+        // The states entering each state on each column
+        let mut inverse = vec![Vec::new(); width * total];
+        for index in 0..total {
+            for symbol in 0..width {
+                inverse[symbol * total + self.delta(index, symbol)].push(index);
+            }
+        }
+
+        // Start with the accepting and non-accepting states
+        let mut blocks: Vec<Vec<usize>> = Vec::new();
+        let (accepting_states, rejecting_states): (Vec<_>, Vec<_>) =
+            (0..total).partition(|index| accepting(*index));
+        for block in [accepting_states, rejecting_states] {
+            if !block.is_empty() {
+                blocks.push(block);
+            }
+        }
+        let mut block_of = vec![0; total];
+        for (block, members) in blocks.iter().enumerate() {
+            for member in members {
+                block_of[*member] = block;
+            }
+        }
+
+        // Only the smaller initial block needs to be a splitter
+        let mut worklist = Vec::new();
+        let mut in_worklist = vec![false; width * blocks.len()];
+        if let Some(block) = (0..blocks.len()).min_by_key(|block| blocks[*block].len()) {
+            for symbol in 0..width {
+                worklist.push((block, symbol));
+                in_worklist[block * width + symbol] = true;
+            }
+        }
+
+        // Buffers reused by every splitter, so each split only costs the states it touches
+        let mut in_splitter = vec![false; total];
+        let mut inside: Vec<Vec<usize>> = vec![Vec::new(); blocks.len()];
+        let mut touched: Vec<usize> = Vec::new();
+        while let Some((splitter, symbol)) = worklist.pop() {
+            in_worklist[splitter * width + symbol] = false;
+
+            // Find the states entering the splitter on the column
+            let mut entering = Vec::new();
+            for target in &blocks[splitter] {
+                for source in &inverse[symbol * total + target] {
+                    if !in_splitter[*source] {
+                        in_splitter[*source] = true;
+                        entering.push(*source);
+                    }
+                }
+            }
+
+            // Group them by block
+            for source in &entering {
+                let block = block_of[*source];
+                if inside[block].is_empty() {
+                    touched.push(block);
+                }
+                inside[block].push(*source);
+            }
+
+            // Split every block that is partly inside
+            for block in touched.drain(..) {
+                if inside[block].len() == blocks[block].len() {
+                    inside[block].clear();
+                    continue;
+                }
+
+                let new_block = blocks.len();
+                let outside = blocks[block]
+                    .iter()
+                    .copied()
+                    .filter(|member| !in_splitter[*member])
+                    .collect();
+                blocks[block] = outside;
+                for member in &inside[block] {
+                    block_of[*member] = new_block;
+                }
+                blocks.push(std::mem::take(&mut inside[block]));
+                inside.push(Vec::new());
+                in_worklist.resize(width * blocks.len(), false);
+
+                for symbol in 0..width {
+                    let added = if in_worklist[block * width + symbol]
+                        || blocks[new_block].len() <= blocks[block].len()
+                    {
+                        new_block
+                    } else {
+                        block
+                    };
+                    in_worklist[added * width + symbol] = true;
+                    worklist.push((added, symbol));
+                }
+            }
+
+            for source in entering {
+                in_splitter[source] = false;
+            }
+        }
+
+        (block_of, blocks.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn minimize() -> Result<(), ParseSerializeError> {
+        // States 1 and 2 are equivalent, state 4 is dead, and state 5 is unreachable
+        let table =
+            TransitionTable::parse("- 0 1 2\n- 1 3 4\n- 2 3 4\n+ 3 3 3\n- 4 4 4\n+ 5 0 0\n")?;
+
+        assert_eq!(table.minimize().serialize(), "- 0 1 1\n- 1 2 E\n+ 2 2 2\n");
+        assert_eq!(table.minimize(), table.minimize().minimize());
+
+        // Merged states keep the smallest of their IDs
+        assert_eq!(
+            table.minimize_with(Renumbering::Preserve).serialize(),
+            "- 0 1 1\n- 1 3 E\n+ 3 3 3\n"
+        );
+
+        let empty = TransitionTable::parse("- 0 1 0\n- 1 0 1\n")?;
+        assert_eq!(empty.minimize().serialize(), "- 0 E E\n");
+
+        // Only unmerged states keep their labels
+        let labeled =
+            TransitionTable::parse("- 0 start 1 2\n- 1 one 3 3\n- 2 two 3 3\n+ 3 end 3 3\n")?;
+        assert_eq!(
+            labeled.minimize().serialize(),
+            "- 0 start 1 1\n- 1 2 2\n+ 2 end 2 2\n"
+        );
+
+        // The empty language keeps the table's header and comment
+        let empty = TransitionTable::parse("# loops\n\nalphabet: [a] [b]\n- 0 1 0\n- 1 0 1\n")?;
+        assert_eq!(
+            empty.minimize().serialize(),
+            "# loops\n\nalphabet: [a] [b]\n- 0 E E\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn minimize_is_minimal() {
+        for table in crate::laws::ArbitraryTables::with_size(5, 8, 3).take(100) {
+            let minimized = table.minimize();
+            assert_eq!(
+                TransitionTable::shortest_divergence(&table, &minimized),
+                None
+            );
+
+            // No two states of the result accept the same language
+            for a in &minimized.rows {
+                for b in minimized.rows.iter().filter(|b| b.id > a.id) {
+                    let (a, b) = (
+                        minimized.sub_automaton(a.id).unwrap(),
+                        minimized.sub_automaton(b.id).unwrap(),
+                    );
+                    assert!(TransitionTable::shortest_divergence(&a, &b).is_some());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+
+/// One column consumed by a [`Monitor`], with the states both tables entered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MonitorStep {
+    /// The column consumed
+    pub symbol: usize,
+
+    /// The system's state after the column, or `None` once it has taken an error transition
+    pub system: Option<usize>,
+
+    /// The property's state after the column, or `None` once it has taken an error transition
+    pub property: Option<usize>,
+}
+
+/// The first point where a system breaks a property, found by a [`Monitor`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("The property is violated after {position} columns")]
+pub struct MonitorViolation {
+    /// The number of columns consumed when the property was violated
+    pub position: usize,
+
+    /// Every step up to and including the violating one
+    pub trace: Vec<MonitorStep>,
+}
+
+/// Runs a system table and a property table in lockstep, checking the property after every column
+///
+/// The property is a safety property over the system's columns: it holds for a prefix when the
+/// property table is in an accepting state after reading it. It is checked for every prefix the
+/// system can run, including the empty one, so the first violation is reported as soon as it
+/// happens. Once the system takes an error transition the input is no run of the system, so
+/// nothing more is reported.
+#[derive(Clone, Debug)]
+pub struct Monitor<'a> {
+    /// The table being monitored
+    system: &'a TransitionTable,
+
+    /// The table accepting the prefixes that keep the property
+    property: &'a TransitionTable,
+
+    /// Every step taken so far
+    trace: Vec<MonitorStep>,
+}
+
+impl<'a> Monitor<'a> {
+    /// Create a monitor at the starting states of a system and a property
+    pub fn new(system: &'a TransitionTable, property: &'a TransitionTable) -> Self {
+        Monitor {
+            system,
+            property,
+            trace: Vec::new(),
+        }
+    }
+
+    /// Get the system's current state, or `None` once it has taken an error transition
+    pub fn system_state(&self) -> Option<usize> {
+        match self.trace.last() {
+            Some(step) => step.system,
+            None => self.system.row(STARTING_STATE_ID).map(|row| row.id),
+        }
+    }
+
+    /// Get the property's current state, or `None` once it has taken an error transition
+    pub fn property_state(&self) -> Option<usize> {
+        match self.trace.last() {
+            Some(step) => step.property,
+            None => self.property.row(STARTING_STATE_ID).map(|row| row.id),
+        }
+    }
+
+    /// Get every step taken so far
+    pub fn trace(&self) -> &[MonitorStep] {
+        &self.trace
+    }
+
+    /// Check the property for the columns consumed so far
+    pub fn check(&self) -> Result<(), MonitorViolation> {
+        let holds = self
+            .property_state()
+            .is_some_and(|state| self.property.row(state).is_some_and(|row| row.accepting));
+        if self.system_state().is_none() || holds {
+            return Ok(());
+        }
+
+        Err(MonitorViolation {
+            position: self.trace.len(),
+            trace: self.trace.clone(),
+        })
+    }
+
+    /// Consume a column in both tables, then check the property
+    pub fn step(&mut self, symbol: usize) -> Result<(), MonitorViolation> {
+        let system = self
+            .system_state()
+            .and_then(|state| self.system.next_state(state, symbol));
+        let property = self
+            .property_state()
+            .and_then(|state| self.property.next_state(state, symbol));
+        self.trace.push(MonitorStep {
+            symbol,
+            system,
+            property,
+        });
+        self.check()
+    }
+
+    /// Check the property before and after every column of an input, stopping at the first
+    /// violation
+    pub fn run(&mut self, input: &[usize]) -> Result<(), MonitorViolation> {
+        self.check()?;
+        input.iter().try_for_each(|symbol| self.step(*symbol))
+    }
+
+    /// Go back to the starting states, forgetting the trace
+    pub fn reset(&mut self) {
+        self.trace.clear();
+    }
+}
+
+impl TransitionTable {
+    /// Create a [`Monitor`] checking a safety property while the table runs
+    pub fn monitor<'a>(&'a self, property: &'a TransitionTable) -> Monitor<'a> {
+        Monitor::new(self, property)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monitor() {
+        // A system over lock, unlock, and write, and the property that no write happens while
+        // locked
+        let system = TransitionTable::parse("+ 0 1 E 0\n+ 1 E 0 1\n").unwrap();
+        let property = TransitionTable::parse("+ 0 1 0 0\n+ 1 1 0 2\n- 2 2 2 2\n").unwrap();
+
+        let mut monitor = system.monitor(&property);
+        assert_eq!(monitor.run(&[2, 0, 1, 2]), Ok(()));
+
+        monitor.reset();
+        let violation = monitor.run(&[0, 1, 0, 2, 1]).unwrap_err();
+        assert_eq!(violation.position, 4);
+        assert_eq!(
+            violation.trace.last(),
+            Some(&MonitorStep {
+                symbol: 2,
+                system: Some(1),
+                property: Some(2)
+            })
+        );
+        assert_eq!(monitor.trace().len(), 4);
+
+        // Inputs the system rejects aren't runs of it, so break no property
+        monitor.reset();
+        assert_eq!(monitor.run(&[1, 2]), Ok(()));
+        assert_eq!(monitor.system_state(), None);
+    }
+}
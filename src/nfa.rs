@@ -0,0 +1,327 @@
+use crate::transition_table::{cells, parse_transition, ERROR_SYMBOL};
+use crate::{ParseError, Span, TransitionTable, STARTING_STATE_ID};
+use std::borrow::Borrow;
+use std::collections::BTreeSet;
+use std::num::ParseIntError;
+
+/// A state (row) in an NFA
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NfaRow {
+    /// Whether the row is for an accepting state (+) or not (-)
+    pub accepting: bool,
+
+    /// The row's state ID (0 means the starting state)
+    pub id: usize,
+
+    /// The states entered on every column, which are empty for an error transition
+    pub transitions: Vec<BTreeSet<usize>>,
+
+    /// The states entered without reading a column
+    pub epsilon: BTreeSet<usize>,
+}
+
+/// A nondeterministic transition table, where a cell can enter several states at once
+///
+/// The text format is the one [`TransitionTable::parse`] reads, with one more column at the end of
+/// every row for the epsilon transitions. A cell is `E` or `{}` for no states, a state ID or `.`
+/// for one state, or a set like `{1,3}` without spaces. Fallback targets aren't supported.
+///
+/// ```text
+/// - 0 {0,1} 0 E
+/// - 1 E 2 {3}
+/// + 2 E E E
+/// + 3 . . E
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Nfa {
+    /// The rows in the NFA, sorted by state ID
+    pub rows: Vec<NfaRow>,
+}
+
+impl Nfa {
+    /// Parse an NFA from a string
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut rows = Vec::new();
+        let mut expected_columns = None;
+
+        for (line_index, line) in input.lines().enumerate() {
+            let span = |column: usize, cell: &str| Span {
+                line: line_index + 1,
+                column,
+                length: cell.chars().count(),
+            };
+
+            // Check that there are a marker, an ID, and an epsilon column, and that the number of
+            // columns is consistent
+            let columns = cells(line, 1);
+            if columns.len() < 3 {
+                return Err(ParseError::TooFewColumns {
+                    span: span(1, line),
+                });
+            }
+            match expected_columns {
+                Some(expected) if expected != columns.len() => {
+                    return Err(ParseError::InconsistentColumnCount {
+                        span: span(1, line),
+                        expected,
+                        found: columns.len(),
+                    })
+                }
+                Some(_) => {}
+                None => expected_columns = Some(columns.len()),
+            }
+
+            let (column, marker) = columns[0];
+            let accepting = match marker.chars().next().unwrap() {
+                '+' => true,
+                '-' => false,
+                _ => {
+                    return Err(ParseError::InvalidAcceptingMarker {
+                        span: span(column, marker),
+                    })
+                }
+            };
+            let (id_column, id) = columns[1];
+            let id_span = span(id_column, id);
+            let id = id.parse().map_err(|source| ParseError::InvalidStateId {
+                span: id_span,
+                source,
+            })?;
+
+            let mut transitions = columns[2..]
+                .iter()
+                .map(|(column, cell)| {
+                    parse_set(cell, id).map_err(|source| ParseError::InvalidTransition {
+                        span: span(*column, cell),
+                        source,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let epsilon = transitions.pop().unwrap();
+
+            rows.push((
+                NfaRow {
+                    accepting,
+                    id,
+                    transitions,
+                    epsilon,
+                },
+                id_span,
+            ));
+        }
+
+        // Sort the rows by state ID, which keeps repeated IDs in line order
+        rows.sort_by_key(|(row, _)| row.id);
+        if let Some(pair) = rows.windows(2).find(|pair| pair[0].0.id == pair[1].0.id) {
+            let first = rows.iter().find(|(row, _)| row.id == pair[1].0.id).unwrap();
+            return Err(ParseError::DuplicateStateId {
+                span: pair[1].1,
+                id: pair[1].0.id,
+                first_line: first.1.line,
+            });
+        }
+
+        Ok(Nfa {
+            rows: rows.into_iter().map(|(row, _)| row).collect(),
+        })
+    }
+
+    /// Serialize the NFA to a string
+    pub fn serialize(&self) -> String {
+        let mut output = String::new();
+        for row in &self.rows {
+            output.push(if row.accepting { '+' } else { '-' });
+            output.push(' ');
+            output.push_str(&row.id.to_string());
+            for set in row.transitions.iter().chain([&row.epsilon]) {
+                output.push(' ');
+                output.push_str(&serialize_set(set));
+            }
+            output.push('\n');
+        }
+
+        output
+    }
+
+    /// Get the row with the given state ID, if it exists
+    pub fn row(&self, id: usize) -> Option<&NfaRow> {
+        self.rows
+            .binary_search_by_key(&id, |row| row.id)
+            .ok()
+            .map(|index| &self.rows[index])
+    }
+
+    /// Get the number of columns, not counting the epsilon column, which is that of the widest row
+    pub fn width(&self) -> usize {
+        self.rows
+            .iter()
+            .map(|row| row.transitions.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Get the states reachable from some states by epsilon transitions alone, including
+    /// themselves
+    ///
+    /// State IDs without a row are left out.
+    pub fn epsilon_closure(&self, states: impl IntoIterator<Item = usize>) -> BTreeSet<usize> {
+        let mut closure = BTreeSet::new();
+        let mut pending: Vec<usize> = states.into_iter().collect();
+        while let Some(state) = pending.pop() {
+            if let Some(row) = self.row(state) {
+                if closure.insert(state) {
+                    pending.extend(row.epsilon.iter().copied());
+                }
+            }
+        }
+
+        closure
+    }
+
+    /// Get the states the NFA can be in before reading anything, which are empty if there is no
+    /// starting state
+    pub fn start_states(&self) -> BTreeSet<usize> {
+        self.epsilon_closure([STARTING_STATE_ID])
+    }
+
+    /// Get the states reachable from some states on a column, followed by epsilon transitions
+    pub fn step(&self, states: &BTreeSet<usize>, symbol: usize) -> BTreeSet<usize> {
+        let targets = states
+            .iter()
+            .filter_map(|state| self.row(*state)?.transitions.get(symbol))
+            .flatten()
+            .copied();
+        self.epsilon_closure(targets.collect::<Vec<_>>())
+    }
+
+    /// Check whether some run of the NFA on a sequence of columns ends in an accepting state
+    pub fn accepts<I>(&self, input: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        let mut states = self.start_states();
+        for symbol in input {
+            if states.is_empty() {
+                return false;
+            }
+            states = self.step(&states, *symbol.borrow());
+        }
+
+        states
+            .iter()
+            .any(|state| self.row(*state).is_some_and(|row| row.accepting))
+    }
+}
+
+impl From<&TransitionTable> for Nfa {
+    /// Turn every transition of a table into a set of one state, without epsilon transitions
+    fn from(table: &TransitionTable) -> Self {
+        Nfa {
+            rows: table
+                .rows
+                .iter()
+                .map(|row| NfaRow {
+                    accepting: row.accepting,
+                    id: row.id,
+                    transitions: row
+                        .transitions
+                        .iter()
+                        .map(|target| target.iter().copied().collect())
+                        .collect(),
+                    epsilon: BTreeSet::new(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl std::str::FromStr for Nfa {
+    type Err = ParseError;
+
+    /// Parse an NFA like [`Nfa::parse`]
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Nfa::parse(input)
+    }
+}
+
+impl std::fmt::Display for Nfa {
+    /// Write the NFA like [`Nfa::serialize`]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.serialize())
+    }
+}
+
+/// Parse a cell of an NFA row from the state `id`, which is a transition or a set of them in braces
+fn parse_set(cell: &str, id: usize) -> Result<BTreeSet<usize>, ParseIntError> {
+    match cell
+        .strip_prefix('{')
+        .and_then(|cell| cell.strip_suffix('}'))
+    {
+        Some("") => Ok(BTreeSet::new()),
+        Some(inner) => inner
+            .split(',')
+            .filter_map(|target| parse_transition(target, id, ERROR_SYMBOL).transpose())
+            .collect(),
+        None => Ok(parse_transition(cell, id, ERROR_SYMBOL)?
+            .into_iter()
+            .collect()),
+    }
+}
+
+/// Write a set of states as `E`, a state ID, or a set in braces
+fn serialize_set(set: &BTreeSet<usize>) -> String {
+    match set.len() {
+        0 => ERROR_SYMBOL.to_string(),
+        1 => set.first().unwrap().to_string(),
+        _ => {
+            let targets: Vec<String> = set.iter().map(usize::to_string).collect();
+            format!("{{{}}}", targets.join(","))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nfa_parse_serialize() -> Result<(), ParseError> {
+        // Over a and b: inputs ending in "ab", or "a" followed by anything
+        let input = "- 0 {0,1} 0 E\n- 1 E 2 {3}\n+ 2 E E E\n+ 3 . . {}\n";
+        let nfa = Nfa::parse(input)?;
+        assert_eq!(nfa.width(), 2);
+        assert_eq!(nfa.rows[0].transitions[0], BTreeSet::from([0, 1]));
+        assert_eq!(nfa.rows[3].transitions[1], BTreeSet::from([3]));
+        assert_eq!(
+            nfa.serialize(),
+            "- 0 {0,1} 0 E\n- 1 E 2 3\n+ 2 E E E\n+ 3 3 3 E\n"
+        );
+        assert_eq!(nfa.serialize().parse::<Nfa>()?, nfa);
+
+        assert_eq!(nfa.step(&nfa.start_states(), 0), BTreeSet::from([0, 1, 3]));
+        assert!(nfa.accepts([1, 0]));
+        assert!(nfa.accepts([1, 1, 0, 1]));
+        assert!(!nfa.accepts([1, 1]));
+
+        let error = Nfa::parse("- 0 {1,x} E\n").unwrap_err();
+        assert!(matches!(error, ParseError::InvalidTransition { .. }));
+        assert_eq!(error.span().column, 5);
+        assert!(matches!(
+            Nfa::parse("- 0\n"),
+            Err(ParseError::TooFewColumns { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn nfa_from_table() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n").unwrap();
+        let nfa = Nfa::from(&table);
+        assert_eq!(nfa.serialize(), "- 0 1 E E\n+ 1 1 0 E\n");
+        for input in [&[][..], &[0], &[0, 1], &[0, 0, 1, 0]] {
+            assert_eq!(nfa.accepts(input), table.accepts(input));
+        }
+    }
+}
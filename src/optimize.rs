@@ -0,0 +1,155 @@
+use crate::{Renumbering, TransitionTable, STARTING_STATE_ID};
+use std::collections::HashMap;
+
+/// What [`TransitionTable::optimize`] changed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OptimizeReport {
+    /// The number of transitions into dead states that became error transitions
+    pub dead_transitions: usize,
+
+    /// The IDs of the dead states removed, in order
+    pub removed_states: Vec<usize>,
+
+    /// The duplicate rows removed, as the ID of each and the ID of the row now used in its place
+    pub merged_states: Vec<(usize, usize)>,
+}
+
+impl OptimizeReport {
+    /// Check whether the pass changed nothing
+    pub fn is_empty(&self) -> bool {
+        self.dead_transitions == 0
+            && self.removed_states.is_empty()
+            && self.merged_states.is_empty()
+    }
+}
+
+impl TransitionTable {
+    /// Drop states unreachable from the starting state and states that can never reach an
+    /// accepting state, keeping the IDs of the rest
+    ///
+    /// Transitions to dropped states become error transitions. The starting state is kept even
+    /// if it can never accept.
+    pub fn trim(&self) -> TransitionTable {
+        self.trim_with(Renumbering::Preserve)
+    }
+
+    /// Trim the table like [`TransitionTable::trim`], renumbering the remaining states
+    pub fn trim_with(&self, renumbering: Renumbering) -> TransitionTable {
+        let live = self.live_states();
+        let mut trimmed = self.clone();
+        for row in &mut trimmed.rows {
+            for transition in &mut row.transitions {
+                if transition.is_some_and(|id| self.index_of(id).is_none_or(|index| !live[index])) {
+                    *transition = None;
+                }
+            }
+        }
+
+        trimmed.canonicalize(renumbering)
+    }
+
+    /// Clean up a table without fully minimizing it, reporting what changed
+    ///
+    /// Transitions into states that can never reach an accepting state become error transitions,
+    /// and those states are removed. Rows with the same acceptance and transitions are then merged
+    /// into the one with the smallest ID, repeating until no rows are identical. Surviving states
+    /// keep their IDs, and the table accepts the same language.
+    pub fn optimize(&self) -> (TransitionTable, OptimizeReport) {
+        let mut report = OptimizeReport::default();
+        let live = self.live_states();
+        let is_dead = |id: usize| self.index_of(id).is_some_and(|index| !live[index]);
+
+        let mut table = self.clone();
+        for row in &mut table.rows {
+            for transition in &mut row.transitions {
+                if transition.is_some_and(is_dead) {
+                    *transition = None;
+                    report.dead_transitions += 1;
+                }
+            }
+        }
+        table.rows.retain(|row| {
+            let keep = !is_dead(row.id) || row.id == STARTING_STATE_ID;
+            if !keep {
+                report.removed_states.push(row.id);
+            }
+            keep
+        });
+
+        loop {
+            // Rows are sorted by ID, so the first of each group has the smallest
+            let mut first_with = HashMap::new();
+            let mut replacements = HashMap::new();
+            for row in &table.rows {
+                let key = (row.accepting, &row.transitions);
+                let kept = *first_with.entry(key).or_insert(row.id);
+                if kept != row.id {
+                    replacements.insert(row.id, kept);
+                    report.merged_states.push((row.id, kept));
+                }
+            }
+            if replacements.is_empty() {
+                break;
+            }
+
+            table.rows.retain(|row| !replacements.contains_key(&row.id));
+            for row in &mut table.rows {
+                for target in row.transitions.iter_mut().flatten() {
+                    if let Some(kept) = replacements.get(target) {
+                        *target = *kept;
+                    }
+                }
+            }
+        }
+
+        (table, report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn optimize() -> Result<(), ParseSerializeError> {
+        // States 3 and 4 are dead, 1 and 2 are identical, which then makes 0 and 5 identical
+        let table =
+            TransitionTable::parse("- 0 1 3\n+ 1 1 E\n+ 2 1 E\n- 3 4 3\n- 4 3 E\n- 5 2 4\n")?;
+        let (optimized, report) = table.optimize();
+
+        assert_eq!(optimized, TransitionTable::parse("- 0 1 E\n+ 1 1 E\n")?);
+        assert_eq!(
+            report,
+            OptimizeReport {
+                dead_transitions: 5,
+                removed_states: vec![3, 4],
+                merged_states: vec![(2, 1), (5, 0)],
+            }
+        );
+        assert_eq!(optimized.minimize(), table.minimize());
+
+        // An optimized table has nothing left to clean up
+        assert!(optimized.optimize().1.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn trim() -> Result<(), ParseSerializeError> {
+        // State 3 is dead, state 4 only reachable from it, and state 5 unreachable
+        let table = TransitionTable::parse("- 0 2 3\n+ 2 2 7\n- 3 4 3\n- 4 3 E\n+ 5 0 0\n")?;
+
+        assert_eq!(table.trim().serialize(), "- 0 2 E\n+ 2 2 E\n");
+        assert_eq!(
+            table.trim_with(Renumbering::BreadthFirst).serialize(),
+            "- 0 1 E\n+ 1 1 E\n"
+        );
+        assert_eq!(
+            TransitionTable::parse("- 0 0 E\n")?.trim().serialize(),
+            "- 0 E E\n"
+        );
+
+        Ok(())
+    }
+}
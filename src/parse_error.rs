@@ -0,0 +1,246 @@
+use crate::ParseSerializeError;
+use std::num::ParseIntError;
+
+/// Where a problem is in the input, in characters
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Span {
+    /// The line, counting from 1
+    pub line: usize,
+
+    /// The column of the first character, counting from 1
+    pub column: usize,
+
+    /// The number of characters
+    pub length: usize,
+}
+
+/// Errors that can occur when parsing a transition table with [`crate::TransitionTable::parse`] or
+/// an NFA with [`crate::Nfa::parse`]
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ParseError {
+    /// A row has no ID, spanning the whole line
+    #[error("Line {} has too few columns", span.line)]
+    TooFewColumns { span: Span },
+
+    /// A row has a different number of columns than the rows before it, spanning the whole line
+    #[error(
+        "Line {} has {found} columns, but the previous lines have {expected}",
+        span.line
+    )]
+    InconsistentColumnCount {
+        span: Span,
+        expected: usize,
+        found: usize,
+    },
+
+    /// A row's first column is neither an accepting nor a rejecting marker, which are `+` and `-`
+    /// by default
+    #[error("Line {} has an invalid accepting state", span.line)]
+    InvalidAcceptingMarker { span: Span },
+
+    /// A row's ID isn't a number
+    #[error("Line {} has an invalid state ID: {source}", span.line)]
+    InvalidStateId { span: Span, source: ParseIntError },
+
+    /// A transition is neither a state ID nor a shorthand
+    #[error(
+        "Line {} column {} has an invalid transition: {source}",
+        span.line,
+        span.column
+    )]
+    InvalidTransition { span: Span, source: ParseIntError },
+
+    /// A row's fallback target, after the `|`, is neither a state ID nor a shorthand
+    #[error("Line {} has an invalid fallback target: {source}", span.line)]
+    InvalidFallback { span: Span, source: ParseIntError },
+
+    /// A transition is `_`, but the row has no fallback target
+    #[error(
+        "Line {} column {} uses the fallback target, but the row has none",
+        span.line,
+        span.column
+    )]
+    MissingFallback { span: Span },
+
+    /// A row has more cells after its fallback target's `|`, spanning them
+    #[error(
+        "Line {} column {} has cells after the fallback target",
+        span.line,
+        span.column
+    )]
+    ExtraFallbackCells { span: Span },
+
+    /// An `alphabet:` header comes after a row or another header, spanning the whole line
+    #[error("Line {} has an alphabet header after the first row or another header", span.line)]
+    MisplacedAlphabet { span: Span },
+
+    /// An `alphabet:` header isn't in the format of [`crate::Alphabet::parse`], spanning the
+    /// whole line
+    #[error("Line {} has an invalid alphabet header: {message}", span.line)]
+    InvalidAlphabet { span: Span, message: String },
+
+    /// An `alphabet:` header names a different number of symbols than the rows have columns,
+    /// spanning the whole line
+    #[error(
+        "Line {} names {found} symbols, but the rows have {expected} columns",
+        span.line
+    )]
+    AlphabetLength {
+        span: Span,
+        expected: usize,
+        found: usize,
+    },
+
+    /// A `%meta` line has no key, spanning the whole line
+    #[error("Line {} has a %meta line without a key", span.line)]
+    InvalidMeta { span: Span },
+
+    /// A row has the same ID as an earlier row, spanning the ID
+    #[error("Line {} repeats state ID {id} from line {first_line}", span.line)]
+    DuplicateStateId {
+        span: Span,
+        id: usize,
+        first_line: usize,
+    },
+
+    /// A [`crate::ParseOptions`] symbol or marker already means something in the format,
+    /// spanning nothing at the start of the input
+    #[error("The parse option symbol {symbol:?} is reserved by the table format")]
+    ReservedSymbol { span: Span, symbol: String },
+
+    /// A row's ID skips the next ID, when IDs must be contiguous, spanning the ID
+    #[error("Line {} has state ID {id}, but the next state ID is {expected}", span.line)]
+    NonContiguousId {
+        span: Span,
+        id: usize,
+        expected: usize,
+    },
+}
+
+impl ParseError {
+    /// Get where the problem is in the input
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::TooFewColumns { span }
+            | ParseError::InconsistentColumnCount { span, .. }
+            | ParseError::InvalidAcceptingMarker { span }
+            | ParseError::InvalidStateId { span, .. }
+            | ParseError::InvalidTransition { span, .. }
+            | ParseError::InvalidFallback { span, .. }
+            | ParseError::MissingFallback { span }
+            | ParseError::ExtraFallbackCells { span }
+            | ParseError::MisplacedAlphabet { span }
+            | ParseError::InvalidAlphabet { span, .. }
+            | ParseError::AlphabetLength { span, .. }
+            | ParseError::InvalidMeta { span }
+            | ParseError::DuplicateStateId { span, .. }
+            | ParseError::NonContiguousId { span, .. }
+            | ParseError::ReservedSymbol { span, .. } => *span,
+        }
+    }
+}
+
+impl From<ParseError> for ParseSerializeError {
+    fn from(error: ParseError) -> Self {
+        ParseSerializeError {
+            message: error.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ParseError, Span, TransitionTable};
+    use std::error::Error;
+
+    #[test]
+    fn parse_errors() {
+        let error = TransitionTable::parse("- 0 1 E\n+ 1 0\n").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::InconsistentColumnCount {
+                span: Span {
+                    line: 2,
+                    column: 1,
+                    length: 5
+                },
+                expected: 4,
+                found: 3,
+            }
+        );
+
+        let error = TransitionTable::parse("- 0 1  x2\n").unwrap_err();
+        assert!(matches!(error, ParseError::InvalidTransition { .. }));
+        assert_eq!(
+            error.span(),
+            Span {
+                line: 1,
+                column: 8,
+                length: 2
+            }
+        );
+        assert!(error.source().is_some());
+
+        let error = TransitionTable::parse("- 0 1\n* 1 0\n").unwrap_err();
+        assert!(matches!(error, ParseError::InvalidAcceptingMarker { .. }));
+
+        let error = TransitionTable::parse("- 0 1\n+ 1 0\n- 0 0\n").unwrap_err();
+        assert!(matches!(
+            error,
+            ParseError::DuplicateStateId {
+                id: 0,
+                first_line: 1,
+                ..
+            }
+        ));
+        assert_eq!(error.to_string(), "Line 3 repeats state ID 0 from line 1");
+
+        let error = TransitionTable::parse("- 0 _ | 0 7  8\n").unwrap_err();
+        assert_eq!(
+            error,
+            ParseError::ExtraFallbackCells {
+                span: Span {
+                    line: 1,
+                    column: 11,
+                    length: 4
+                }
+            }
+        );
+    }
+
+    #[test]
+    fn parse_all_errors() {
+        let input = "* 0 x _ | y\n- 1 0\n+ 1 1 E\n+ 1 . .\n";
+        let errors = TransitionTable::parse_all_errors(input).unwrap_err();
+        let positions = errors
+            .iter()
+            .map(|error| (error.span().line, error.span().column, error.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            [
+                (1, 1, "Line 1 has an invalid accepting state".to_string()),
+                (
+                    1,
+                    5,
+                    "Line 1 column 5 has an invalid transition: invalid digit found in string"
+                        .to_string()
+                ),
+                (
+                    1,
+                    11,
+                    "Line 1 has an invalid fallback target: invalid digit found in string"
+                        .to_string()
+                ),
+                (
+                    2,
+                    1,
+                    "Line 2 has 3 columns, but the previous lines have 4".to_string()
+                ),
+                (4, 3, "Line 4 repeats state ID 1 from line 3".to_string()),
+            ]
+        );
+
+        assert!(TransitionTable::parse_all_errors("- 0 0\n").is_ok());
+    }
+}
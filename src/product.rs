@@ -0,0 +1,108 @@
+use crate::{TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::{HashMap, VecDeque};
+
+impl TransitionTable {
+    /// Build a table accepting the inputs both tables accept
+    ///
+    /// Only the pairs of states reachable from the pair of starting states are built, so the
+    /// result is usually much smaller than the full cross product. Tables of different widths are
+    /// combined as if the narrower one had error transitions on the extra columns.
+    pub fn intersect(&self, other: &TransitionTable) -> TransitionTable {
+        self.product(other, |a, b| a && b)
+    }
+
+    /// Build a table accepting the inputs either table accepts, like
+    /// [`TransitionTable::intersect`]
+    pub fn union(&self, other: &TransitionTable) -> TransitionTable {
+        self.product(other, |a, b| a || b)
+    }
+
+    /// Build a table accepting the inputs this table accepts and `other` rejects, like
+    /// [`TransitionTable::intersect`]
+    pub fn difference(&self, other: &TransitionTable) -> TransitionTable {
+        self.product(other, |a, b| a && !b)
+    }
+
+    /// Run two tables side by side, accepting where `accepting` holds for the pair of states
+    ///
+    /// Both tables are completed with their sink, so the product also follows inputs one table
+    /// rejects. Only pairs reachable from the pair of starting states are kept, numbered in
+    /// breadth-first order, and the result is as wide as the wider table.
+    pub(crate) fn product(
+        &self,
+        other: &TransitionTable,
+        accepting: impl Fn(bool, bool) -> bool,
+    ) -> TransitionTable {
+        let width = self.width().max(other.width());
+        let start = |table: &TransitionTable| {
+            table
+                .index_of(STARTING_STATE_ID)
+                .unwrap_or(table.rows.len())
+        };
+        let is_accepting = |table: &TransitionTable, index: usize| {
+            table.rows.get(index).is_some_and(|row| row.accepting)
+        };
+
+        let start = (start(self), start(other));
+        let mut ids = HashMap::from([(start, STARTING_STATE_ID)]);
+        let mut queue = VecDeque::from([start]);
+        let mut rows = Vec::new();
+        while let Some(pair) = queue.pop_front() {
+            let (a, b) = pair;
+            let transitions = (0..width)
+                .map(|symbol| {
+                    let target = (self.delta(a, symbol), other.delta(b, symbol));
+                    let next_id = ids.len();
+                    Some(*ids.entry(target).or_insert_with(|| {
+                        queue.push_back(target);
+                        next_id
+                    }))
+                })
+                .collect();
+
+            rows.push(TransitionTableRow {
+                accepting: accepting(is_accepting(self, a), is_accepting(other, b)),
+                id: ids[&pair],
+                transitions,
+                doc: None,
+                label: None,
+            });
+        }
+
+        // The columns only keep their symbols if both tables name them the same
+        let alphabet = if self.alphabet == other.alphabet {
+            self.alphabet.clone()
+        } else {
+            None
+        };
+        TransitionTable {
+            rows,
+            alphabet,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::TransitionTable;
+
+    #[test]
+    fn set_operations() {
+        // Over a and b: inputs with an a, and inputs of even length
+        let has_a = TransitionTable::parse("- 0 1 0\n+ 1 1 1\n").unwrap();
+        let even = TransitionTable::parse("+ 0 1 1\n- 1 0 0\n").unwrap();
+
+        let inputs = [&[][..], &[0], &[1], &[0, 1], &[1, 1], &[1, 1, 0]];
+        for input in inputs {
+            let (a, b) = (has_a.accepts(input), even.accepts(input));
+            assert_eq!(has_a.intersect(&even).accepts(input), a && b);
+            assert_eq!(has_a.union(&even).accepts(input), a || b);
+            assert_eq!(has_a.difference(&even).accepts(input), a && !b);
+        }
+
+        // Only the four reachable pairs are built, not every pair with the sinks
+        assert_eq!(has_a.intersect(&even).rows.len(), 4);
+    }
+}
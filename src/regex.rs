@@ -0,0 +1,496 @@
+use crate::{Alphabet, CharSet, Nfa, NfaRow, ParseSerializeError, TransitionTable};
+use std::collections::BTreeSet;
+
+/// A regular expression over characters
+///
+/// Regexes are parsed from a small syntax with concatenation, alternation with `|`, grouping with
+/// parentheses, the postfix operators `*`, `+` and `?`, character classes like `[a-z_]` or
+/// `[^0-9]`, and `.` for any character. Outside a class, `\` makes the next character literal and
+/// `\u{...}` is a hexadecimal code point, like inside one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Regex {
+    /// Matches only the empty string
+    Empty,
+
+    /// Matches one character in the set
+    Class(CharSet),
+
+    /// Matches each regex in turn
+    Concat(Vec<Regex>),
+
+    /// Matches any one of the regexes
+    Alternate(Vec<Regex>),
+
+    /// Matches the regex zero or more times
+    Star(Box<Regex>),
+
+    /// Matches the regex one or more times
+    Plus(Box<Regex>),
+
+    /// Matches the regex zero or one times
+    Optional(Box<Regex>),
+}
+
+impl Regex {
+    /// Parse a regex from a pattern
+    pub fn parse(pattern: &str) -> Result<Self, ParseSerializeError> {
+        let mut parser = Parser {
+            pattern,
+            chars: pattern.chars().collect(),
+            position: 0,
+        };
+        let regex = parser.alternation()?;
+        match parser.peek() {
+            Some(_) => Err(parser.error("unmatched closing parenthesis")),
+            None => Ok(regex),
+        }
+    }
+
+    /// Build the coarsest alphabet telling apart the characters the regex treats differently
+    ///
+    /// Every column is a set of characters that are in exactly the same character classes of the
+    /// regex, ordered by their smallest characters. Characters in no class get no column.
+    pub fn alphabet(&self) -> Alphabet {
+        let mut classes = Vec::new();
+        self.collect_classes(&mut classes);
+
+        let mut parts: Vec<CharSet> = Vec::new();
+        for class in classes {
+            let mut rest = class.clone();
+            let mut refined = Vec::new();
+            for part in parts {
+                rest = rest.difference(&part);
+                refined.extend(
+                    [part.intersection(class), part.difference(class)]
+                        .into_iter()
+                        .filter(|part| !part.is_empty()),
+                );
+            }
+            if !rest.is_empty() {
+                refined.push(rest);
+            }
+            parts = refined;
+        }
+
+        parts.sort_by_key(|part| part.first());
+        Alphabet::new(parts)
+    }
+
+    /// Add every character class in the regex to a list
+    fn collect_classes<'a>(&'a self, classes: &mut Vec<&'a CharSet>) {
+        match self {
+            Regex::Empty => {}
+            Regex::Class(class) => classes.push(class),
+            Regex::Concat(regexes) | Regex::Alternate(regexes) => {
+                for regex in regexes {
+                    regex.collect_classes(classes);
+                }
+            }
+            Regex::Star(regex) | Regex::Plus(regex) | Regex::Optional(regex) => {
+                regex.collect_classes(classes)
+            }
+        }
+    }
+
+    /// Build an NFA matching the same strings classified with an alphabet, with the Thompson
+    /// construction
+    ///
+    /// A character class matches every column whose characters are all in the class, and fails
+    /// if it only contains some characters of a column, since the NFA couldn't tell them apart.
+    /// Characters without a column can't be matched. The NFA has an epsilon transition from its
+    /// starting state, and a single accepting state.
+    pub fn to_nfa(&self, alphabet: &Alphabet) -> Result<Nfa, ParseSerializeError> {
+        let mut construction = Thompson {
+            alphabet,
+            rows: Vec::new(),
+        };
+        let start = construction.state();
+        let (first, last) = construction.build(self)?;
+        construction.rows[start].epsilon.insert(first);
+        construction.rows[last].accepting = true;
+
+        Ok(Nfa {
+            rows: construction.rows,
+        })
+    }
+
+    /// Build the minimal table matching the same strings classified with an alphabet
+    ///
+    /// Fails like [`Regex::to_nfa`].
+    pub fn compile(&self, alphabet: &Alphabet) -> Result<TransitionTable, ParseSerializeError> {
+        Ok(self.to_nfa(alphabet)?.determinize().minimize())
+    }
+
+    /// Serialize the regex as a pattern, in the syntax read by [`Regex::parse`]
+    ///
+    /// [`Regex::Empty`] is written as `()`, and a class without characters, which matches
+    /// nothing, as `[]`. Parentheses are only added where precedence needs them.
+    pub fn serialize(&self) -> String {
+        let grouped = |regex: &Regex| match regex {
+            Regex::Concat(_) | Regex::Alternate(_) => format!("({})", regex.serialize()),
+            _ => regex.serialize(),
+        };
+        match self {
+            Regex::Empty => "()".to_string(),
+            Regex::Class(class) => serialize_class(class),
+            Regex::Concat(regexes) => regexes
+                .iter()
+                .map(|regex| match regex {
+                    Regex::Alternate(_) => grouped(regex),
+                    _ => regex.serialize(),
+                })
+                .collect(),
+            Regex::Alternate(regexes) => regexes
+                .iter()
+                .map(Regex::serialize)
+                .collect::<Vec<_>>()
+                .join("|"),
+            Regex::Star(regex) => format!("{}*", grouped(regex)),
+            Regex::Plus(regex) => format!("{}+", grouped(regex)),
+            Regex::Optional(regex) => format!("{}?", grouped(regex)),
+        }
+    }
+}
+
+impl std::str::FromStr for Regex {
+    type Err = ParseSerializeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Regex::parse(s)
+    }
+}
+
+impl std::fmt::Display for Regex {
+    /// Write the regex like [`Regex::serialize`]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.serialize())
+    }
+}
+
+/// Serialize a character class, as a single character if it has one and `.` if it has all of them
+fn serialize_class(class: &CharSet) -> String {
+    match class.ranges() {
+        [(start, end)] if start == end => {
+            let c = *start;
+            if c.is_whitespace() || c.is_control() {
+                format!("\\u{{{:x}}}", c as u32)
+            } else if matches!(
+                c,
+                '\\' | '|' | '*' | '+' | '?' | '(' | ')' | '[' | ']' | '.'
+            ) {
+                format!("\\{}", c)
+            } else {
+                c.to_string()
+            }
+        }
+        _ if *class == CharSet::any() => ".".to_string(),
+        _ => class.serialize(),
+    }
+}
+
+impl TransitionTable {
+    /// Build the minimal table matching a regex, along with the alphabet from [`Regex::alphabet`]
+    /// to classify its input with
+    ///
+    /// The table records its provenance with [`TransitionTable::with_provenance`], so
+    /// [`TransitionTable::was_generated_from`] recognizes the pattern.
+    pub fn from_regex(pattern: &str) -> Result<(TransitionTable, Alphabet), ParseSerializeError> {
+        let regex = Regex::parse(pattern)?;
+        let alphabet = regex.alphabet();
+        let table = regex
+            .compile(&alphabet)?
+            .with_provenance("transition-tables regex", pattern);
+        Ok((table, alphabet))
+    }
+}
+
+/// A recursive descent parser for the regex syntax
+struct Parser<'a> {
+    /// The whole pattern, for error messages
+    pattern: &'a str,
+
+    /// The characters of the pattern
+    chars: Vec<char>,
+
+    /// The index of the next character to read
+    position: usize,
+}
+
+impl Parser<'_> {
+    /// Make an error at the current position
+    fn error(&self, message: &str) -> ParseSerializeError {
+        ParseSerializeError {
+            message: format!(
+                "Invalid regex {} at character {}: {}",
+                self.pattern, self.position, message
+            ),
+        }
+    }
+
+    /// Get the next character without reading it
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    /// Parse concatenations separated by `|`
+    fn alternation(&mut self) -> Result<Regex, ParseSerializeError> {
+        let mut branches = vec![self.concatenation()?];
+        while self.peek() == Some('|') {
+            self.position += 1;
+            branches.push(self.concatenation()?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.remove(0)
+        } else {
+            Regex::Alternate(branches)
+        })
+    }
+
+    /// Parse repeated atoms up to the end of a branch
+    fn concatenation(&mut self) -> Result<Regex, ParseSerializeError> {
+        let mut parts = Vec::new();
+        while let Some(c) = self.peek() {
+            let mut atom = match c {
+                '|' | ')' => break,
+                '*' | '+' | '?' => return Err(self.error("nothing to repeat")),
+                _ => self.atom()?,
+            };
+            while let Some(operator) = self.peek() {
+                atom = match operator {
+                    '*' => Regex::Star(Box::new(atom)),
+                    '+' => Regex::Plus(Box::new(atom)),
+                    '?' => Regex::Optional(Box::new(atom)),
+                    _ => break,
+                };
+                self.position += 1;
+            }
+            parts.push(atom);
+        }
+        Ok(match parts.len() {
+            0 => Regex::Empty,
+            1 => parts.remove(0),
+            _ => Regex::Concat(parts),
+        })
+    }
+
+    /// Parse a character, class, or group
+    fn atom(&mut self) -> Result<Regex, ParseSerializeError> {
+        let start = self.position;
+        self.position += 1;
+        match self.chars[start] {
+            '(' => {
+                let group = self.alternation()?;
+                if self.peek() != Some(')') {
+                    return Err(self.error("unclosed group"));
+                }
+                self.position += 1;
+                Ok(group)
+            }
+            '.' => Ok(Regex::Class(CharSet::any())),
+            '[' => {
+                let negated = self.peek() == Some('^');
+                if negated {
+                    self.position += 1;
+                }
+                let inner = self.position;
+                let mut escaped = false;
+                while let Some(c) = self.peek() {
+                    if !escaped && c == ']' {
+                        break;
+                    }
+                    escaped = !escaped && c == '\\';
+                    self.position += 1;
+                }
+                if self.peek().is_none() {
+                    return Err(self.error("unclosed character class"));
+                }
+                let class: String = self.chars[inner..self.position].iter().collect();
+                self.position += 1;
+
+                let class = CharSet::parse(&format!("[{}]", class))?;
+                Ok(Regex::Class(if negated {
+                    class.complement()
+                } else {
+                    class
+                }))
+            }
+            '\\' => {
+                let escape = match self.peek() {
+                    Some('u') => {
+                        let end = self.chars[self.position..]
+                            .iter()
+                            .position(|c| *c == '}')
+                            .ok_or_else(|| self.error("unterminated code point escape"))?;
+                        self.position += end + 1;
+                        self.chars[start..self.position].iter().collect()
+                    }
+                    Some(c) => {
+                        self.position += 1;
+                        format!("\\{}", c)
+                    }
+                    None => return Err(self.error("trailing escape")),
+                };
+                Ok(Regex::Class(CharSet::parse(&format!("[{}]", escape))?))
+            }
+            c => Ok(Regex::Class(CharSet::single(c))),
+        }
+    }
+}
+
+/// The state of the Thompson construction of an NFA
+struct Thompson<'a> {
+    /// The alphabet classifying the characters
+    alphabet: &'a Alphabet,
+
+    /// The states built so far
+    rows: Vec<NfaRow>,
+}
+
+impl Thompson<'_> {
+    /// Add a rejecting state without transitions, returning its ID
+    fn state(&mut self) -> usize {
+        let id = self.rows.len();
+        self.rows.push(NfaRow {
+            accepting: false,
+            id,
+            transitions: vec![BTreeSet::new(); self.alphabet.len()],
+            epsilon: BTreeSet::new(),
+        });
+        id
+    }
+
+    /// Add an epsilon transition
+    fn epsilon(&mut self, from: usize, to: usize) {
+        self.rows[from].epsilon.insert(to);
+    }
+
+    /// Get the columns matching a character class, failing if the class splits a column
+    fn columns(&self, class: &CharSet) -> Result<Vec<usize>, ParseSerializeError> {
+        let ranges = self.alphabet.ranges();
+        let mut columns: Vec<usize> = ranges
+            .iter()
+            .filter(|(start, end, _)| {
+                !CharSet::new([(*start, *end)])
+                    .intersection(class)
+                    .is_empty()
+            })
+            .map(|(_, _, column)| *column)
+            .collect();
+        columns.sort_unstable();
+        columns.dedup();
+
+        for (start, end, column) in ranges {
+            if columns.binary_search(column).is_ok()
+                && !CharSet::new([(*start, *end)]).difference(class).is_empty()
+            {
+                return Err(ParseSerializeError {
+                    message: format!(
+                        "Character class {} only contains some characters of column {}",
+                        class.serialize(),
+                        column
+                    ),
+                });
+            }
+        }
+        Ok(columns)
+    }
+
+    /// Build the states of a regex, returning the state it starts in and the state it ends in
+    fn build(&mut self, regex: &Regex) -> Result<(usize, usize), ParseSerializeError> {
+        Ok(match regex {
+            Regex::Empty => {
+                let state = self.state();
+                (state, state)
+            }
+            Regex::Class(class) => {
+                let columns = self.columns(class)?;
+                let (first, last) = (self.state(), self.state());
+                for column in columns {
+                    self.rows[first].transitions[column].insert(last);
+                }
+                (first, last)
+            }
+            Regex::Concat(regexes) => {
+                let (first, mut last) = self.build(&Regex::Empty)?;
+                for regex in regexes {
+                    let (next_first, next_last) = self.build(regex)?;
+                    self.epsilon(last, next_first);
+                    last = next_last;
+                }
+                (first, last)
+            }
+            Regex::Alternate(regexes) => {
+                let (first, last) = (self.state(), self.state());
+                for regex in regexes {
+                    let (branch_first, branch_last) = self.build(regex)?;
+                    self.epsilon(first, branch_first);
+                    self.epsilon(branch_last, last);
+                }
+                (first, last)
+            }
+            Regex::Star(inner) | Regex::Plus(inner) | Regex::Optional(inner) => {
+                let (first, last) = (self.state(), self.state());
+                let (inner_first, inner_last) = self.build(inner)?;
+                self.epsilon(first, inner_first);
+                self.epsilon(inner_last, last);
+                // Skipping the regex allows zero matches, and looping back allows more than one
+                if !matches!(regex, Regex::Plus(_)) {
+                    self.epsilon(first, last);
+                }
+                if !matches!(regex, Regex::Optional(_)) {
+                    self.epsilon(inner_last, inner_first);
+                }
+                (first, last)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_regex() -> Result<(), ParseSerializeError> {
+        let (table, alphabet) = TransitionTable::from_regex("[a-z_][a-z0-9_]*|-?[0-9]+")?;
+        assert_eq!(alphabet.len(), 3);
+        for word in ["x", "snake_case2", "_", "42", "-7"] {
+            assert!(table.accepts_str(&alphabet, word), "{}", word);
+        }
+        for word in ["", "2x", "-", "--1", "a-b"] {
+            assert!(!table.accepts_str(&alphabet, word), "{}", word);
+        }
+
+        let (table, alphabet) = TransitionTable::from_regex(r"(ab|\.)?c+|[^abc\u{2e}]")?;
+        for word in ["c", "abccc", ".c", "z", "\u{10FFFF}"] {
+            assert!(table.accepts_str(&alphabet, word), "{}", word);
+        }
+        for word in ["", "ab", "abc.c", "zz", "a"] {
+            assert!(!table.accepts_str(&alphabet, word), "{}", word);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn to_nfa() -> Result<(), ParseSerializeError> {
+        let regex: Regex = "(0|1)*1".parse()?;
+        let alphabet = Alphabet::from_chars("01".chars());
+        let nfa = regex.to_nfa(&alphabet)?;
+        for input in [vec![1], vec![0, 0, 1], vec![1, 0], vec![]] {
+            assert_eq!(nfa.accepts(&input), input.last() == Some(&1));
+        }
+        assert_eq!(regex.compile(&alphabet)?.serialize(), "- 0 0 1\n+ 1 0 1\n");
+
+        // A class must take whole columns of the alphabet
+        let digits = Alphabet::new(vec![CharSet::new([('0', '9')])]);
+        assert!(Regex::parse("[0-9]")?.compile(&digits).is_ok());
+        assert!(Regex::parse("[0-4]")?.compile(&digits).is_err());
+
+        for pattern in ["(a", "a)", "*a", "a|+", "[ab", "a\\"] {
+            assert!(Regex::parse(pattern).is_err(), "{}", pattern);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,206 @@
+use crate::{TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// How to assign new IDs to states when a table is rebuilt
+///
+/// The starting state always becomes state 0. Every strategy but [`Renumbering::Preserve`] numbers
+/// the remaining states from 1 without gaps, and depends only on the table's structure, never on
+/// its old IDs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Renumbering {
+    /// Number states in the order a breadth-first search from the starting state reaches them,
+    /// trying columns in order
+    #[default]
+    BreadthFirst,
+
+    /// Number states in the order a depth-first search from the starting state reaches them,
+    /// trying columns in order
+    DepthFirst,
+
+    /// Number states with the most incoming transitions first, breaking ties breadth-first
+    InDegree,
+
+    /// Keep each state's old ID, giving a new ID past the largest only to a state whose ID is
+    /// taken by the starting state
+    ///
+    /// States merged together keep the smallest of their IDs.
+    Preserve,
+}
+
+impl TransitionTable {
+    /// Drop the states unreachable from the starting state, renumbering the rest
+    ///
+    /// Transitions to states that do not exist become error transitions. Returns an empty table if
+    /// there is no starting state.
+    pub fn canonicalize(&self, renumbering: Renumbering) -> TransitionTable {
+        match self.row(STARTING_STATE_ID) {
+            Some(_) => {
+                self.renumber_reachable_with(STARTING_STATE_ID, renumbering)
+                    .0
+            }
+            None => TransitionTable {
+                rows: Vec::new(),
+                alphabet: self.alphabet.clone(),
+                metadata: self.metadata.clone(),
+                comment: self.comment.clone(),
+            },
+        }
+    }
+
+    /// Copy the states reachable from `start` into a new table, renumbered in breadth-first order
+    ///
+    /// Also returns the original ID of every new state, indexed by new ID. Transitions to states
+    /// that do not exist become error transitions.
+    pub(crate) fn renumber_reachable(&self, start: usize) -> (TransitionTable, Vec<usize>) {
+        self.renumber_reachable_with(start, Renumbering::BreadthFirst)
+    }
+
+    /// Copy the states reachable from `start` into a new table, renumbered with a strategy
+    ///
+    /// Also returns the original ID of every row of the new table.
+    pub(crate) fn renumber_reachable_with(
+        &self,
+        start: usize,
+        renumbering: Renumbering,
+    ) -> (TransitionTable, Vec<usize>) {
+        let targets = |id: usize| {
+            self.row(id)
+                .unwrap()
+                .transitions
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|target| self.row(*target).is_some())
+        };
+
+        // Find the reachable states in the order they are discovered
+        let mut order = Vec::new();
+        if renumbering == Renumbering::DepthFirst {
+            let mut seen = HashSet::new();
+            let mut stack = vec![start];
+            while let Some(id) = stack.pop() {
+                if seen.insert(id) {
+                    order.push(id);
+                    let mut next: Vec<usize> = targets(id).collect();
+                    next.reverse();
+                    stack.extend(next);
+                }
+            }
+        } else {
+            let mut seen = HashSet::from([start]);
+            let mut queue = VecDeque::from([start]);
+            while let Some(id) = queue.pop_front() {
+                order.push(id);
+                for target in targets(id) {
+                    if seen.insert(target) {
+                        queue.push_back(target);
+                    }
+                }
+            }
+        }
+
+        let new_ids: HashMap<usize, usize> = match renumbering {
+            Renumbering::BreadthFirst | Renumbering::DepthFirst => order
+                .iter()
+                .enumerate()
+                .map(|(new_id, id)| (*id, new_id))
+                .collect(),
+            Renumbering::InDegree => {
+                let mut in_degree = HashMap::new();
+                for id in &order {
+                    for target in targets(*id) {
+                        *in_degree.entry(target).or_insert(0) += 1;
+                    }
+                }
+                // The sort is stable, so ties stay in breadth-first order
+                let mut rest = order[1..].to_vec();
+                rest.sort_by_key(|id| std::cmp::Reverse(in_degree.get(id).copied().unwrap_or(0)));
+                std::iter::once(start)
+                    .chain(rest)
+                    .enumerate()
+                    .map(|(new_id, id)| (id, new_id))
+                    .collect()
+            }
+            Renumbering::Preserve => {
+                let unused = order.iter().max().unwrap() + 1;
+                order
+                    .iter()
+                    .map(|id| match *id {
+                        id if id == start => (id, STARTING_STATE_ID),
+                        STARTING_STATE_ID => (STARTING_STATE_ID, unused),
+                        id => (id, id),
+                    })
+                    .collect()
+            }
+        };
+
+        let mut rows: Vec<TransitionTableRow> = order
+            .iter()
+            .map(|id| {
+                let row = self.row(*id).unwrap();
+                TransitionTableRow {
+                    accepting: row.accepting,
+                    id: new_ids[id],
+                    transitions: row
+                        .transitions
+                        .iter()
+                        .map(|target| target.and_then(|target| new_ids.get(&target).copied()))
+                        .collect(),
+                    doc: row.doc.clone(),
+                    label: row.label.clone(),
+                }
+            })
+            .collect();
+        rows.sort_by_key(|row| row.id);
+
+        let mut old_ids: Vec<usize> = order;
+        old_ids.sort_by_key(|id| new_ids[id]);
+
+        (
+            TransitionTable {
+                rows,
+                alphabet: self.alphabet.clone(),
+                metadata: self.metadata.clone(),
+                comment: self.comment.clone(),
+            },
+            old_ids,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn renumbering() -> Result<(), ParseSerializeError> {
+        // State 9 is unreachable, and state 3 is entered the most
+        let table =
+            TransitionTable::parse("- 0 2 5\n- 2 7 3\n+ 3 3 3\n- 5 3 E\n- 7 3 E\n+ 9 0 0\n")?;
+
+        assert_eq!(
+            table.canonicalize(Renumbering::BreadthFirst).serialize(),
+            "- 0 1 2\n- 1 3 4\n- 2 4 E\n- 3 4 E\n+ 4 4 4\n"
+        );
+        assert_eq!(
+            table.canonicalize(Renumbering::DepthFirst).serialize(),
+            "- 0 1 4\n- 1 2 3\n- 2 3 E\n+ 3 3 3\n- 4 3 E\n"
+        );
+        assert_eq!(
+            table.canonicalize(Renumbering::InDegree).serialize(),
+            "- 0 2 3\n+ 1 1 1\n- 2 4 1\n- 3 1 E\n- 4 1 E\n"
+        );
+        assert_eq!(
+            table.canonicalize(Renumbering::Preserve).serialize(),
+            "- 0 2 5\n- 2 7 3\n+ 3 3 3\n- 5 3 E\n- 7 3 E\n"
+        );
+
+        // A starting state other than 0 takes its ID
+        let (sub, old_ids) = table.renumber_reachable_with(2, Renumbering::Preserve);
+        assert_eq!(sub.serialize(), "- 0 7 3\n+ 3 3 3\n- 7 3 E\n");
+        assert_eq!(old_ids, vec![2, 3, 7]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+/// A small deterministic xorshift random number generator
+///
+/// This is not suitable for anything security-related, only for reproducible test data. The same
+/// seed gives the same numbers on every run and machine, so every randomized API in the crate takes
+/// either a seed or a generator, and [`crate::SeedableSession`] threads one seed through several.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    /// The generator state, which is never 0
+    state: u64,
+}
+
+impl Rng {
+    /// Create a generator from a seed
+    pub fn new(seed: u64) -> Self {
+        Rng {
+            state: seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1,
+        }
+    }
+
+    /// Get the next random number
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    /// Split off a generator whose numbers don't overlap with this one's in practice
+    ///
+    /// This takes one number from this generator, so the split is as reproducible as the rest.
+    pub fn split(&mut self) -> Rng {
+        Rng::new(self.next_u64())
+    }
+
+    /// Get a random number below `bound`, which must not be 0
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{Alphabet, Automaton};
+
+/// What to do with a character outside the alphabet
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownSymbol {
+    /// Reject the input, like an error transition
+    #[default]
+    Reject,
+
+    /// Treat the character as the given column
+    Other(usize),
+
+    /// Ignore the character and carry on with the next
+    Skip,
+
+    /// Stop with an [`UnknownSymbolError`] giving the character's position
+    Error,
+}
+
+/// Options for [`Automaton::run_chars_with`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RunConfig {
+    /// What to do with characters the alphabet doesn't classify
+    pub unknown_symbol: UnknownSymbol,
+}
+
+/// A character outside the alphabet, met while running with [`UnknownSymbol::Error`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("character {character:?} at byte {position} is not in the alphabet")]
+pub struct UnknownSymbolError {
+    /// The character
+    pub character: char,
+
+    /// The byte offset of the character in the input
+    pub position: usize,
+}
+
+impl RunConfig {
+    /// Classify a character at a byte offset, returning `None` if it should be skipped
+    ///
+    /// Rejected characters are classified as a column past the automaton's width, which has no
+    /// transitions.
+    fn classify(
+        &self,
+        alphabet: &Alphabet,
+        character: char,
+        position: usize,
+    ) -> Result<Option<usize>, UnknownSymbolError> {
+        if let Some(column) = alphabet.classify(character) {
+            return Ok(Some(column));
+        }
+
+        match self.unknown_symbol {
+            UnknownSymbol::Reject => Ok(Some(usize::MAX)),
+            UnknownSymbol::Other(column) => Ok(Some(column)),
+            UnknownSymbol::Skip => Ok(None),
+            UnknownSymbol::Error => Err(UnknownSymbolError {
+                character,
+                position,
+            }),
+        }
+    }
+}
+
+/// Run a string on an automaton, classifying characters with `alphabet` under `config`
+pub(crate) fn run_chars_with<A: Automaton + ?Sized>(
+    automaton: &A,
+    alphabet: &Alphabet,
+    input: &str,
+    config: &RunConfig,
+) -> Result<Option<A::State>, UnknownSymbolError> {
+    let symbols = input
+        .char_indices()
+        .filter_map(|(position, c)| config.classify(alphabet, c, position).transpose());
+    automaton.try_run(symbols)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharSet, ParseSerializeError, TransitionTable};
+
+    #[test]
+    fn unknown_symbols() -> Result<(), ParseSerializeError> {
+        // Digits, with spaces allowed between them
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n")?;
+        let alphabet = Alphabet::new(vec![CharSet::new([('0', '9')]), CharSet::single(' ')]);
+        let run = |input: &str, unknown_symbol: UnknownSymbol| {
+            table.accepts_chars_with(&alphabet, input, &RunConfig { unknown_symbol })
+        };
+
+        assert_eq!(run("1 2", UnknownSymbol::Reject), Ok(true));
+        assert_eq!(run("1,2", UnknownSymbol::Reject), Ok(false));
+        assert_eq!(run("1,2", UnknownSymbol::Other(1)), Ok(true));
+        assert_eq!(run("1,", UnknownSymbol::Other(1)), Ok(false));
+        assert_eq!(run("1,,2", UnknownSymbol::Skip), Ok(true));
+        assert_eq!(
+            run("1 ½", UnknownSymbol::Error),
+            Err(UnknownSymbolError {
+                character: '½',
+                position: 2
+            })
+        );
+
+        Ok(())
+    }
+}
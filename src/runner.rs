@@ -0,0 +1,100 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+
+/// A step taken by [`DfaRunner::step`] that has no transition
+#[derive(Clone, Copy, Debug, PartialEq, Eq, thiserror::Error)]
+#[error("no transition from state {state} on column {symbol}")]
+pub struct DeadError {
+    /// The state the step was taken from
+    pub state: usize,
+
+    /// The column of the step
+    pub symbol: usize,
+}
+
+/// A cursor driving a table one column at a time
+///
+/// Unlike running a whole input at once, the runner can be inspected between steps, which suits
+/// teaching tools and interactive debugging.
+#[derive(Clone, Copy, Debug)]
+pub struct DfaRunner<'a> {
+    /// The table being run
+    table: &'a TransitionTable,
+
+    /// The current state ID
+    state: usize,
+}
+
+impl<'a> DfaRunner<'a> {
+    /// Create a runner at the starting state of a table
+    pub fn new(table: &'a TransitionTable) -> Self {
+        DfaRunner {
+            table,
+            state: STARTING_STATE_ID,
+        }
+    }
+
+    /// Get the ID of the current state
+    pub fn current_state(&self) -> usize {
+        self.state
+    }
+
+    /// Check whether the current state is accepting
+    pub fn is_accepting(&self) -> bool {
+        self.table.row(self.state).is_some_and(|row| row.accepting)
+    }
+
+    /// Take the transition on a column, returning the ID of the state entered
+    ///
+    /// Fails on an error transition, staying in the current state so another column can be tried.
+    pub fn step(&mut self, symbol: usize) -> Result<usize, DeadError> {
+        self.state = self.table.next_state(self.state, symbol).ok_or(DeadError {
+            state: self.state,
+            symbol,
+        })?;
+        Ok(self.state)
+    }
+
+    /// Go back to the starting state
+    pub fn reset(&mut self) {
+        self.state = STARTING_STATE_ID;
+    }
+}
+
+impl TransitionTable {
+    /// Create a [`DfaRunner`] at the starting state, to drive the table one column at a time
+    pub fn runner(&self) -> DfaRunner<'_> {
+        DfaRunner::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn dfa_runner() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 2\n- 2 E 1\n")?;
+        let mut runner = table.runner();
+
+        assert_eq!(runner.current_state(), 0);
+        assert!(!runner.is_accepting());
+        assert_eq!(runner.step(0), Ok(1));
+        assert!(runner.is_accepting());
+        assert_eq!(runner.step(1), Ok(2));
+        assert_eq!(
+            runner.step(0),
+            Err(DeadError {
+                state: 2,
+                symbol: 0
+            })
+        );
+        assert_eq!(runner.current_state(), 2);
+
+        runner.reset();
+        assert_eq!(runner.current_state(), 0);
+        assert!(runner.step(1).is_err());
+
+        Ok(())
+    }
+}
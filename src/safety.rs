@@ -0,0 +1,104 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+use std::collections::{HashMap, VecDeque};
+
+/// An input whose run breaks a safety property, from [`TransitionTable::check_always_within`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("The input {input:?} reaches state {state}, which breaks the property")]
+pub struct SafetyViolation {
+    /// A shortest input whose run enters a state breaking the property
+    pub input: Vec<usize>,
+
+    /// The ID of the state the run enters
+    pub state: usize,
+}
+
+impl TransitionTable {
+    /// Check that every run of at most `max_len` columns only enters states where `predicate`
+    /// holds
+    ///
+    /// This is bounded model checking with the table as the model: states are explored
+    /// breadth-first from the starting state, each once, up to `max_len` columns deep, so the
+    /// check is linear in the size of the table however large `max_len` is. Runs end at error
+    /// transitions, which enter no state. The starting state is checked too, as the run of the
+    /// empty input. Fails with a shortest input entering a state where `predicate` doesn't hold.
+    pub fn check_always_within(
+        &self,
+        max_len: usize,
+        predicate: impl Fn(usize) -> bool,
+    ) -> Result<(), SafetyViolation> {
+        let Some(start) = self.index_of(STARTING_STATE_ID) else {
+            return Ok(());
+        };
+        let width = self.width();
+        let sink = self.rows.len();
+
+        // The row index and column each row was first reached from, with the input length there
+        let mut parents = HashMap::from([(start, None)]);
+        let mut queue = VecDeque::from([(start, 0)]);
+        while let Some((index, len)) = queue.pop_front() {
+            let state = self.rows[index].id;
+            if !predicate(state) {
+                let mut input = Vec::new();
+                let mut current = index;
+                while let Some((parent, symbol)) = parents[&current] {
+                    input.push(symbol);
+                    current = parent;
+                }
+                input.reverse();
+                return Err(SafetyViolation { input, state });
+            }
+            if len == max_len {
+                continue;
+            }
+
+            for symbol in 0..width {
+                let next = self.delta(index, symbol);
+                if next != sink {
+                    parents.entry(next).or_insert_with(|| {
+                        queue.push_back((next, len + 1));
+                        Some((index, symbol))
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that no run of at most `max_len` columns enters any of `states`, like
+    /// [`TransitionTable::check_always_within`]
+    pub fn check_never_reaches(
+        &self,
+        states: &[usize],
+        max_len: usize,
+    ) -> Result<(), SafetyViolation> {
+        self.check_always_within(max_len, |state| !states.contains(&state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_never_reaches() {
+        // A protocol over open, send, and close, where sending after closing is an error state 3
+        let table = TransitionTable::parse("- 0 1 E E\n+ 1 E 1 2\n+ 2 E 3 E\n- 3 3 3 3\n").unwrap();
+        assert_eq!(table.check_never_reaches(&[3], 2), Ok(()));
+        assert_eq!(
+            table.check_never_reaches(&[3], 5),
+            Err(SafetyViolation {
+                input: vec![0, 2, 1],
+                state: 3
+            })
+        );
+        assert_eq!(
+            table
+                .check_always_within(10, |state| state != 0)
+                .unwrap_err()
+                .input,
+            []
+        );
+        assert!(table.check_always_within(10, |state| state < 4).is_ok());
+    }
+}
@@ -0,0 +1,242 @@
+use crate::rng::Rng;
+use crate::{TransitionTable, STARTING_STATE_ID};
+use std::collections::BTreeMap;
+
+/// Draws random inputs from a table, uniformly among the inputs ending in chosen states
+///
+/// Sampling counts the inputs of every length that lead from each state to the chosen states,
+/// then builds an input column by column with each column weighted by how many inputs continue
+/// through it, so every input of at most the maximum length is equally likely. Targeting one
+/// accepting state gives inputs for one token kind rather than for the whole language. The same
+/// seed gives the same inputs.
+#[derive(Clone, Debug)]
+pub struct Sampler<'a> {
+    /// The table being sampled
+    table: &'a TransitionTable,
+
+    /// The source of randomness
+    rng: Rng,
+}
+
+impl<'a> Sampler<'a> {
+    /// Create a sampler for a table from a seed
+    pub fn new(table: &'a TransitionTable, seed: u64) -> Self {
+        Sampler::with_rng(table, Rng::new(seed))
+    }
+
+    /// Create a sampler for a table drawing from a generator
+    pub fn with_rng(table: &'a TransitionTable, rng: Rng) -> Self {
+        Sampler { table, rng }
+    }
+
+    /// Draw an accepted input of at most `max_len` columns, or `None` if there is none
+    pub fn sample(&mut self, max_len: usize) -> Option<Vec<usize>> {
+        let targets: Vec<bool> = self.table.rows.iter().map(|row| row.accepting).collect();
+        self.sample_into(&targets, max_len)
+    }
+
+    /// Draw an input of at most `max_len` columns ending in a state, or `None` if there is none
+    ///
+    /// The state doesn't have to be accepting.
+    pub fn sample_ending_in(&mut self, state: usize, max_len: usize) -> Option<Vec<usize>> {
+        let index = self.table.index_of(state)?;
+        let mut targets = vec![false; self.table.rows.len()];
+        targets[index] = true;
+        self.sample_into(&targets, max_len)
+    }
+
+    /// Draw `count` inputs of at most `max_len` columns ending in each accepting state, by state ID
+    ///
+    /// Accepting states no input of at most `max_len` columns reaches are left out.
+    pub fn sample_per_state(
+        &mut self,
+        max_len: usize,
+        count: usize,
+    ) -> BTreeMap<usize, Vec<Vec<usize>>> {
+        let accepting: Vec<usize> = self
+            .table
+            .rows
+            .iter()
+            .filter(|row| row.accepting)
+            .map(|row| row.id)
+            .collect();
+
+        accepting
+            .into_iter()
+            .filter_map(|state| {
+                let inputs: Option<Vec<_>> = (0..count)
+                    .map(|_| self.sample_ending_in(state, max_len))
+                    .collect();
+                inputs
+                    .filter(|inputs| !inputs.is_empty())
+                    .map(|inputs| (state, inputs))
+            })
+            .collect()
+    }
+
+    /// Draw up to `k` rejected inputs one edit away from accepted inputs of at most `max_len`
+    /// columns
+    ///
+    /// Each near miss is an accepted input with one column substituted, inserted, or deleted, so it
+    /// can be one column longer than `max_len`. These sit right at the boundary of the language,
+    /// where validators tend to go wrong. Gives up after `100 * k` tries, so languages where few
+    /// edits make a difference can give fewer than `k`, and ones closed under edits give none.
+    /// Near misses may repeat.
+    pub fn sample_near_misses(&mut self, k: usize, max_len: usize) -> Vec<Vec<usize>> {
+        let width = self.table.width();
+        let mut misses = Vec::new();
+        for _ in 0..100 * k {
+            if misses.len() == k {
+                break;
+            }
+            let Some(mut input) = self.sample(max_len) else {
+                break;
+            };
+
+            let position = self.rng.below(input.len() + 1);
+            match self.rng.below(3) {
+                0 if position < input.len() && width > 1 => {
+                    // Substitute a different column
+                    let symbol = self.rng.below(width - 1);
+                    input[position] = symbol + usize::from(symbol >= input[position]);
+                }
+                1 if width > 0 => input.insert(position, self.rng.below(width)),
+                _ if position < input.len() => {
+                    input.remove(position);
+                }
+                _ => continue,
+            }
+            if !self.table.accepts(&input) {
+                misses.push(input);
+            }
+        }
+
+        misses
+    }
+
+    /// Draw an input of at most `max_len` columns ending in a row index marked in `targets`
+    fn sample_into(&mut self, targets: &[bool], max_len: usize) -> Option<Vec<usize>> {
+        let table = self.table;
+        let start = table.index_of(STARTING_STATE_ID)?;
+        let width = table.width();
+        let sink = table.rows.len();
+
+        // `counts[len][index]` is the number of inputs of `len` columns from a row to a target,
+        // as a float so that long inputs over wide tables can't overflow
+        let mut counts = vec![targets
+            .iter()
+            .map(|target| f64::from(u8::from(*target)))
+            .collect::<Vec<_>>()];
+        for len in 1..=max_len {
+            let previous = &counts[len - 1];
+            let next = (0..sink)
+                .map(|index| {
+                    (0..width)
+                        .map(|symbol| table.delta(index, symbol))
+                        .filter(|target| *target != sink)
+                        .map(|target| previous[target])
+                        .sum()
+                })
+                .collect();
+            counts.push(next);
+        }
+
+        // Pick a length, then each column, in proportion to the inputs it allows
+        let len = self.weighted((0..=max_len).map(|len| counts[len][start]))?;
+        let mut input = Vec::with_capacity(len);
+        let mut index = start;
+        for remaining in (0..len).rev() {
+            let symbol = self.weighted((0..width).map(|symbol| {
+                let target = table.delta(index, symbol);
+                if target == sink {
+                    0.0
+                } else {
+                    counts[remaining][target]
+                }
+            }))?;
+            input.push(symbol);
+            index = table.delta(index, symbol);
+        }
+
+        Some(input)
+    }
+
+    /// Pick a position with probability proportional to its weight, or `None` if they are all 0
+    fn weighted(&mut self, weights: impl Iterator<Item = f64> + Clone) -> Option<usize> {
+        let total: f64 = weights.clone().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut point = self.rng.next_u64() as f64 / u64::MAX as f64 * total;
+        let mut last = None;
+        for (position, weight) in weights.enumerate().filter(|(_, weight)| *weight > 0.0) {
+            if point < weight {
+                return Some(position);
+            }
+            point -= weight;
+            last = Some(position);
+        }
+
+        // Rounding can leave a sliver past the last weight
+        last
+    }
+}
+
+impl TransitionTable {
+    /// Create a [`Sampler`] drawing random inputs from the table
+    pub fn sampler(&self, seed: u64) -> Sampler<'_> {
+        Sampler::new(self, seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_per_state() {
+        // Over a and b: state 1 after "a" and state 2 after one or more b
+        let table = TransitionTable::parse("- 0 1 2\n+ 1 E E\n+ 2 E 2\n- 3 3 3\n").unwrap();
+        let mut sampler = table.sampler(1);
+        for _ in 0..20 {
+            let input = sampler.sample(4).unwrap();
+            assert!(table.accepts(&input));
+            assert!(input.len() <= 4);
+        }
+        assert_eq!(sampler.sample_ending_in(1, 4), Some(vec![0]));
+        assert_eq!(sampler.sample_ending_in(0, 4), Some(vec![]));
+        assert_eq!(sampler.sample_ending_in(3, 4), None);
+
+        let samples = sampler.sample_per_state(3, 5);
+        assert_eq!(samples.keys().copied().collect::<Vec<_>>(), [1, 2]);
+        assert!(samples[&1].iter().all(|input| *input == [0]));
+        assert!(samples[&2]
+            .iter()
+            .all(|input| !input.is_empty() && input.iter().all(|symbol| *symbol == 1)));
+
+        // Every one of the three inputs ending in state 2 shows up
+        let mut seen = samples[&2].clone();
+        seen.extend((0..50).filter_map(|_| sampler.sample_ending_in(2, 3)));
+        seen.sort();
+        seen.dedup();
+        assert_eq!(seen, [vec![1], vec![1, 1], vec![1, 1, 1]]);
+    }
+
+    #[test]
+    fn sample_near_misses() {
+        // Inputs over a and b with exactly two a
+        let table = TransitionTable::parse("- 0 1 0\n- 1 2 1\n+ 2 E 2\n").unwrap();
+        let misses = table.sampler(2).sample_near_misses(30, 4);
+        assert_eq!(misses.len(), 30);
+        for miss in misses {
+            assert!(!table.accepts(&miss));
+            let a = miss.iter().filter(|symbol| **symbol == 0).count();
+            assert!(a == 1 || a == 3);
+        }
+
+        // Every edit of an input over one column is still accepted
+        let anything = TransitionTable::parse("+ 0 0\n").unwrap();
+        assert!(anything.sampler(2).sample_near_misses(5, 4).is_empty());
+    }
+}
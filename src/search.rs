@@ -0,0 +1,480 @@
+use crate::{Alphabet, Automaton, TransitionTable, STARTING_STATE_ID};
+use std::borrow::Borrow;
+use std::ops::Range;
+
+/// A table paired with an alphabet, for searching strings like a regular expression
+#[derive(Clone, Copy, Debug)]
+pub struct Pattern<'a> {
+    /// The table to match
+    table: &'a TransitionTable,
+
+    /// The alphabet classifying characters into columns
+    alphabet: &'a Alphabet,
+}
+
+impl<'a> Pattern<'a> {
+    /// Get the table being matched
+    pub fn table(&self) -> &'a TransitionTable {
+        self.table
+    }
+
+    /// Get the alphabet classifying characters
+    pub fn alphabet(&self) -> &'a Alphabet {
+        self.alphabet
+    }
+
+    /// Check whether any substring of the haystack is accepted
+    pub fn is_match(&self, haystack: &str) -> bool {
+        self.find(haystack).is_some()
+    }
+
+    /// Find the byte range of the leftmost-longest accepted substring
+    pub fn find(&self, haystack: &str) -> Option<Range<usize>> {
+        self.table.find_in_str(self.alphabet, haystack)
+    }
+
+    /// Check whether some prefix of the haystack is accepted
+    pub fn starts_with(&self, haystack: &str) -> bool {
+        self.table
+            .longest_match_str(self.alphabet, haystack, 0)
+            .is_some()
+    }
+
+    /// Iterate over the byte ranges of successive non-overlapping leftmost-longest matches
+    ///
+    /// An empty match is never reported right after the end of the previous match.
+    pub fn find_iter<'h>(&self, haystack: &'h str) -> Matches<'a, 'h> {
+        Matches {
+            pattern: *self,
+            haystack,
+            position: Some(0),
+            last_end: None,
+        }
+    }
+
+    /// Iterate over the matched substrings of the haystack
+    pub fn extract<'h>(&self, haystack: &'h str) -> impl Iterator<Item = &'h str> + 'a
+    where
+        'h: 'a,
+    {
+        self.find_iter(haystack).map(move |range| &haystack[range])
+    }
+
+    /// Iterate over the substrings of the haystack between matches
+    ///
+    /// Like [`str::split`], this yields an empty substring for matches at the start or end of the
+    /// haystack and for adjacent matches.
+    pub fn split<'h>(&self, haystack: &'h str) -> Split<'a, 'h> {
+        Split {
+            matches: self.find_iter(haystack),
+            last: Some(0),
+        }
+    }
+
+    /// Lazily keep only the items the table accepts whole, such as the lines of a log
+    ///
+    /// Unlike [`Pattern::is_match`], an item must be accepted from its first character to its
+    /// last, so search for substrings with a table accepting anything around them. Items are
+    /// checked one at a time as the iterator is advanced, without collecting them.
+    pub fn filter_matching<I>(&self, lines: I) -> FilterMatching<'a, I::IntoIter>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        FilterMatching {
+            pattern: *self,
+            lines: lines.into_iter(),
+        }
+    }
+}
+
+/// An iterator over the matches of a [`Pattern`], created by [`Pattern::find_iter`]
+#[derive(Clone, Debug)]
+pub struct Matches<'a, 'h> {
+    /// The pattern being matched
+    pattern: Pattern<'a>,
+
+    /// The string being searched
+    haystack: &'h str,
+
+    /// The byte offset to search from next, or `None` when the search is done
+    position: Option<usize>,
+
+    /// The end of the previous match
+    last_end: Option<usize>,
+}
+
+impl Iterator for Matches<'_, '_> {
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let position = self.position?;
+            let found =
+                self.pattern
+                    .table
+                    .find_in_str_at(self.pattern.alphabet, self.haystack, position);
+            let Some(range) = found else {
+                self.position = None;
+                return None;
+            };
+
+            // Step over empty matches by one character so the search always advances
+            self.position = if range.is_empty() {
+                self.haystack[range.end..]
+                    .chars()
+                    .next()
+                    .map(|c| range.end + c.len_utf8())
+            } else {
+                Some(range.end)
+            };
+
+            if range.is_empty() && self.last_end == Some(range.end) {
+                continue;
+            }
+            self.last_end = Some(range.end);
+            return Some(range);
+        }
+    }
+}
+
+/// An iterator over the substrings between the matches of a [`Pattern`], created by
+/// [`Pattern::split`]
+#[derive(Clone, Debug)]
+pub struct Split<'a, 'h> {
+    /// The matches delimiting the substrings
+    matches: Matches<'a, 'h>,
+
+    /// The start of the next substring, or `None` after the last one
+    last: Option<usize>,
+}
+
+impl<'h> Iterator for Split<'_, 'h> {
+    type Item = &'h str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.last?;
+        let haystack = self.matches.haystack;
+
+        match self.matches.next() {
+            Some(range) => {
+                self.last = Some(range.end);
+                Some(&haystack[start..range.start])
+            }
+            None => {
+                self.last = None;
+                Some(&haystack[start..])
+            }
+        }
+    }
+}
+
+/// An iterator over the items a [`Pattern`] accepts, created by [`Pattern::filter_matching`]
+#[derive(Clone, Debug)]
+pub struct FilterMatching<'a, I> {
+    /// The pattern accepting items
+    pattern: Pattern<'a>,
+
+    /// The items being filtered
+    lines: I,
+}
+
+impl<I> Iterator for FilterMatching<'_, I>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pattern = self.pattern;
+        self.lines
+            .find(|line| pattern.table.accepts_str(pattern.alphabet, line.as_ref()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.lines.size_hint().1)
+    }
+}
+
+impl TransitionTable {
+    /// Pair the table with an alphabet to search strings
+    pub fn pattern<'a>(&'a self, alphabet: &'a Alphabet) -> Pattern<'a> {
+        Pattern {
+            table: self,
+            alphabet,
+        }
+    }
+
+    /// Check whether any substring of a byte string is accepted, using each byte as its column
+    ///
+    /// This pairs with byte-level tables such as those from [`TransitionTable::to_utf8_bytes`].
+    pub fn is_match_bytes(&self, haystack: &[u8]) -> bool {
+        self.find_bytes(haystack).is_some()
+    }
+
+    /// Find the leftmost-longest accepted substring of a byte string, using each byte as its
+    /// column
+    pub fn find_bytes(&self, haystack: &[u8]) -> Option<Range<usize>> {
+        (0..=haystack.len()).find_map(|start| {
+            self.longest_match_bytes(haystack, start)
+                .map(|end| start..end)
+        })
+    }
+
+    /// Check whether some prefix of a byte string is accepted, using each byte as its column
+    pub fn starts_with_bytes(&self, haystack: &[u8]) -> bool {
+        self.longest_match_bytes(haystack, 0).is_some()
+    }
+
+    /// Find the end of the longest accepted substring of a byte string starting at `start`
+    fn longest_match_bytes(&self, haystack: &[u8], start: usize) -> Option<usize> {
+        let symbols = haystack[start..]
+            .iter()
+            .enumerate()
+            .map(|(offset, byte)| (start + offset + 1, Some(*byte as usize)));
+        longest_match(self, start, symbols)
+    }
+
+    /// Check whether the table accepts a sequence of columns
+    ///
+    /// Walks transitions from [`STARTING_STATE_ID`], rejecting as soon as the run takes an error
+    /// transition, without needing the [`Automaton`] trait in scope.
+    pub fn accepts<I>(&self, input: I) -> bool
+    where
+        I: IntoIterator,
+        I::Item: Borrow<usize>,
+    {
+        Automaton::accepts(self, input)
+    }
+
+    /// Check whether the table accepts a string, classifying each character with `alphabet`
+    pub fn accepts_str(&self, alphabet: &Alphabet, input: &str) -> bool {
+        self.run_str(alphabet, input)
+            .is_some_and(|state| self.row(state).is_some_and(|row| row.accepting))
+    }
+
+    /// Check whether the table accepts a string, classifying each character with the table's
+    /// [`TransitionTable::alphabet`]
+    ///
+    /// Rejects every string if the table has no alphabet.
+    pub fn accepts_chars(&self, input: &str) -> bool {
+        self.alphabet
+            .as_ref()
+            .is_some_and(|alphabet| self.accepts_str(alphabet, input))
+    }
+
+    /// Run the table on a string, classifying each character with `alphabet`
+    ///
+    /// Returns the state reached after the whole string, or `None` if the run takes an error
+    /// transition or meets a character with no column.
+    pub fn run_str(&self, alphabet: &Alphabet, input: &str) -> Option<usize> {
+        input.chars().try_fold(STARTING_STATE_ID, |state, c| {
+            self.next_state(state, alphabet.classify(c)?)
+        })
+    }
+
+    /// Find the leftmost-longest match of the table in a string
+    ///
+    /// Returns the byte range of the longest accepted substring starting at the first position
+    /// where any substring is accepted. The match is empty if the starting state is accepting.
+    pub fn find_in_str(&self, alphabet: &Alphabet, haystack: &str) -> Option<Range<usize>> {
+        self.find_in_str_at(alphabet, haystack, 0)
+    }
+
+    /// Like [`TransitionTable::find_in_str`], ignoring matches starting before byte `start`
+    pub(crate) fn find_in_str_at(
+        &self,
+        alphabet: &Alphabet,
+        haystack: &str,
+        start: usize,
+    ) -> Option<Range<usize>> {
+        find_at(self, alphabet, haystack, start)
+    }
+
+    /// Find the end of the longest accepted substring starting at byte `start`
+    pub(crate) fn longest_match_str(
+        &self,
+        alphabet: &Alphabet,
+        haystack: &str,
+        start: usize,
+    ) -> Option<usize> {
+        longest_match_chars(self, alphabet, haystack, start)
+    }
+}
+
+/// Find the leftmost-longest match of an automaton in a string, ignoring matches starting before
+/// byte `start`
+pub(crate) fn find_at<A: Automaton + ?Sized>(
+    automaton: &A,
+    alphabet: &Alphabet,
+    haystack: &str,
+    start: usize,
+) -> Option<Range<usize>> {
+    haystack[start..]
+        .char_indices()
+        .map(|(offset, _)| start + offset)
+        .chain([haystack.len()])
+        .find_map(|start| {
+            longest_match_chars(automaton, alphabet, haystack, start).map(|end| start..end)
+        })
+}
+
+/// Find the end of the longest substring accepted by an automaton starting at byte `start`
+fn longest_match_chars<A: Automaton + ?Sized>(
+    automaton: &A,
+    alphabet: &Alphabet,
+    haystack: &str,
+    start: usize,
+) -> Option<usize> {
+    let symbols = haystack[start..]
+        .char_indices()
+        .map(|(offset, c)| (start + offset + c.len_utf8(), alphabet.classify(c)));
+    longest_match(automaton, start, symbols)
+}
+
+/// Find the end of the longest prefix of a sequence of symbols accepted by an automaton
+///
+/// `symbols` yields the column of each symbol, or `None` if it has no column, paired with the
+/// offset just after it. `start` is the offset before the first symbol.
+pub(crate) fn longest_match<A: Automaton + ?Sized>(
+    automaton: &A,
+    start: usize,
+    symbols: impl Iterator<Item = (usize, Option<usize>)>,
+) -> Option<usize> {
+    let mut state = automaton.start()?;
+    let mut end = automaton.is_accepting(state).then_some(start);
+    for (offset, symbol) in symbols {
+        let Some(next) = symbol.and_then(|symbol| automaton.next_state(state, symbol)) else {
+            break;
+        };
+
+        state = next;
+        if automaton.is_accepting(state) {
+            end = Some(offset);
+        }
+    }
+
+    end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharSet, ParseSerializeError};
+
+    /// Numbers with an optional fractional part, over digits and '.'
+    const NUMBER: &str = "- 0 1 E\n+ 1 1 2\n- 2 3 E\n+ 3 3 E\n";
+
+    fn number_alphabet() -> Alphabet {
+        Alphabet::new(vec![CharSet::new([('0', '9')]), CharSet::single('.')])
+    }
+
+    #[test]
+    fn run_str() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(NUMBER)?;
+        let alphabet = number_alphabet();
+
+        assert_eq!(table.run_str(&alphabet, "3.14"), Some(3));
+        assert_eq!(table.run_str(&alphabet, "3."), Some(2));
+        assert_eq!(table.run_str(&alphabet, ".5"), None);
+        assert_eq!(table.run_str(&alphabet, "3,14"), None);
+        assert_eq!(table.run_str(&alphabet, ""), Some(0));
+
+        assert!(table.accepts_str(&alphabet, "3.14"));
+        assert!(!table.accepts_str(&alphabet, "3."));
+        assert!(table.accepts([0, 1, 0]));
+        assert!(!table.accepts(vec![0, 1]));
+
+        // The table's own alphabet classifies characters without a separate one
+        assert!(!table.accepts_chars("3.14"));
+        let named = TransitionTable {
+            alphabet: Some(number_alphabet()),
+            ..table
+        };
+        assert!(named.accepts_chars("3.14"));
+        assert!(!named.accepts_chars("3,14"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_in_str() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(NUMBER)?;
+        let alphabet = number_alphabet();
+
+        assert_eq!(table.find_in_str(&alphabet, "π ≈ 3.14159!"), Some(7..14));
+        assert_eq!(table.find_in_str(&alphabet, "version 2."), Some(8..9));
+        assert_eq!(table.find_in_str(&alphabet, "no digits"), None);
+        assert_eq!(table.find_in_str_at(&alphabet, "1 and 22", 1), Some(6..8));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(NUMBER)?;
+        let alphabet = number_alphabet();
+        let pattern = table.pattern(&alphabet);
+
+        assert!(pattern.is_match("about 2.5 km"));
+        assert!(!pattern.is_match("a few km"));
+        assert_eq!(pattern.find("about 2.5 km"), Some(6..9));
+        assert!(pattern.starts_with("12 monkeys"));
+        assert!(!pattern.starts_with("monkeys: 12"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_bytes() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(NUMBER)?.to_utf8_bytes(&number_alphabet());
+
+        assert!(table.is_match_bytes(b"\xff\x00 42"));
+        assert_eq!(table.find_bytes(b"\xff\x00 4.2."), Some(3..6));
+        assert!(table.starts_with_bytes(b"9\xff"));
+        assert!(!table.starts_with_bytes(b"\xff9"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn pattern_iterators() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(NUMBER)?;
+        let alphabet = number_alphabet();
+        let pattern = table.pattern(&alphabet);
+
+        assert_eq!(
+            pattern.find_iter("1, 2.5 and 30.").collect::<Vec<_>>(),
+            vec![0..1, 3..6, 11..13]
+        );
+        assert_eq!(
+            pattern.extract("1, 2.5 and 30.").collect::<Vec<_>>(),
+            vec!["1", "2.5", "30"]
+        );
+        assert_eq!(
+            pattern.split("a1b22c").collect::<Vec<_>>(),
+            vec!["a", "b", "c"]
+        );
+        assert_eq!(pattern.split("1a1").collect::<Vec<_>>(), vec!["", "a", ""]);
+        assert_eq!(pattern.split("").collect::<Vec<_>>(), vec![""]);
+        assert_eq!(
+            pattern
+                .filter_matching("3.14\n2.\nx1\n10".lines())
+                .collect::<Vec<_>>(),
+            vec!["3.14", "10"]
+        );
+        let owned = vec!["7".to_string(), "7a".to_string()];
+        assert_eq!(pattern.filter_matching(owned).count(), 1);
+
+        // A table accepting the empty string matches between every character
+        let empty = TransitionTable::parse("+ 0 E E\n")?;
+        let pattern = empty.pattern(&alphabet);
+        assert_eq!(
+            pattern.find_iter("ab").collect::<Vec<_>>(),
+            vec![0..0, 1..1, 2..2]
+        );
+
+        Ok(())
+    }
+}
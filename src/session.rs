@@ -0,0 +1,81 @@
+#[cfg(feature = "bench")]
+use crate::bench::Workload;
+use crate::laws::ArbitraryTables;
+use crate::{Rng, Sampler, TransitionTable};
+
+/// One seed threaded through every randomized step of a pipeline
+///
+/// Each step gets its own generator split off the session's, in the order the steps ask for
+/// them. A pipeline that asks in the same order gets the same tables and inputs from the same seed
+/// on every run and machine, and adding steps at the end doesn't change the earlier ones. Logging
+/// [`SeedableSession::seed`] is enough to reproduce a failing run.
+#[derive(Clone, Debug)]
+pub struct SeedableSession {
+    /// The seed the session was created from
+    seed: u64,
+
+    /// The generator every step's generator is split from
+    rng: Rng,
+}
+
+impl SeedableSession {
+    /// Start a session from a seed
+    pub fn new(seed: u64) -> Self {
+        SeedableSession {
+            seed,
+            rng: Rng::new(seed),
+        }
+    }
+
+    /// Get the seed the session was created from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Split off a generator for the next step
+    pub fn rng(&mut self) -> Rng {
+        self.rng.split()
+    }
+
+    /// Create a [`Sampler`] for a table as the next step
+    pub fn sampler<'a>(&mut self, table: &'a TransitionTable) -> Sampler<'a> {
+        Sampler::with_rng(table, self.rng())
+    }
+
+    /// Create a generator of random tables with up to `max_states` states and `width` columns as
+    /// the next step
+    pub fn arbitrary_tables(&mut self, max_states: usize, width: usize) -> ArbitraryTables {
+        ArbitraryTables::with_rng(self.rng(), max_states, width)
+    }
+
+    /// Create a generator of benchmark tables and inputs as the next step
+    #[cfg(feature = "bench")]
+    pub fn workload(&mut self) -> Workload {
+        Workload::with_rng(self.rng())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seedable_session() {
+        let run = |seed| {
+            let mut session = SeedableSession::new(seed);
+            let table = session
+                .arbitrary_tables(5, 2)
+                .find(|table| table.rows[0].accepting)
+                .unwrap();
+            let inputs: Vec<_> = {
+                let mut sampler = session.sampler(&table);
+                (0..5).filter_map(|_| sampler.sample(6)).collect()
+            };
+            (table, inputs, session.rng().next_u64())
+        };
+
+        assert_eq!(run(7), run(7));
+        assert_ne!(run(7), run(8));
+        assert_eq!(SeedableSession::new(7).seed(), 7);
+    }
+}
@@ -0,0 +1,124 @@
+use crate::{Alphabet, TransitionTable, STARTING_STATE_ID};
+
+/// A Rust type whose values are the states of a transition table
+///
+/// This connects hand-written state machines with the rest of the crate: the table can be
+/// analyzed like any other, while values of the type are stepped through it. With the `derive`
+/// feature, the trait can be derived for an enum of unit variants. The first variant is the
+/// starting state, `#[accepting]` marks accepting states, and each `#[transition(on = 'c', to =
+/// Variant)]` adds a transition, with one column per distinct character. Variant names become
+/// the labels of their states, except for one-character names, which can't be labels, and
+/// variant doc comments become the documentation of their states:
+///
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use transition_tables::StateMachine;
+///
+/// #[derive(StateMachine, Debug, PartialEq)]
+/// enum Door {
+///     /// The door is shut
+///     #[transition(on = 'o', to = Open)]
+///     Closed,
+///     #[accepting]
+///     #[transition(on = 'c', to = Closed)]
+///     Open,
+/// }
+///
+/// assert_eq!(Door::run("oco"), Some(Door::Open));
+/// assert!(!Door::accepts("oc"));
+/// assert_eq!(Door::transition_table().rows.len(), 2);
+/// assert_eq!(Door::transition_table().rows[0].doc.as_deref(), Some("The door is shut"));
+/// assert_eq!(Door::transition_table().rows[1].label.as_deref(), Some("Open"));
+/// # }
+/// ```
+pub trait StateMachine: Sized {
+    /// Get the table of the state machine
+    fn transition_table() -> &'static TransitionTable;
+
+    /// Get the alphabet classifying characters into the table's columns
+    fn alphabet() -> &'static Alphabet;
+
+    /// Get the state ID of a value
+    fn state_id(&self) -> usize;
+
+    /// Get the value with a state ID, if there is one
+    fn from_state_id(id: usize) -> Option<Self>;
+
+    /// Get the starting state
+    ///
+    /// Panics if no value has the starting state ID.
+    fn start() -> Self {
+        Self::from_state_id(STARTING_STATE_ID).expect("the starting state has no value")
+    }
+
+    /// Get the state reached on a character, or `None` for an error transition
+    fn next(&self, c: char) -> Option<Self> {
+        let symbol = Self::alphabet().classify(c)?;
+        Self::from_state_id(Self::transition_table().next_state(self.state_id(), symbol)?)
+    }
+
+    /// Check whether the state is accepting
+    fn is_accepting(&self) -> bool {
+        Self::transition_table()
+            .row(self.state_id())
+            .is_some_and(|row| row.accepting)
+    }
+
+    /// Run a string from the starting state, returning the state reached
+    fn run(input: &str) -> Option<Self> {
+        input
+            .chars()
+            .try_fold(Self::start(), |state, c| state.next(c))
+    }
+
+    /// Check whether a string is accepted
+    fn accepts(input: &str) -> bool {
+        Self::run(input).is_some_and(|state| state.is_accepting())
+    }
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use crate::StateMachine;
+
+    #[derive(StateMachine, Clone, Copy, Debug, PartialEq)]
+    enum Number {
+        #[transition(on = '0', to = Digits)]
+        #[transition(on = '1', to = Digits)]
+        Start,
+        #[accepting]
+        #[transition(on = '0', to = Digits)]
+        #[transition(on = '1', to = Digits)]
+        #[transition(on = '.', to = Point)]
+        Digits,
+        #[transition(on = '0', to = Fraction)]
+        #[transition(on = '1', to = Fraction)]
+        Point,
+        #[accepting]
+        #[transition(on = '0', to = Fraction)]
+        #[transition(on = '1', to = Fraction)]
+        Fraction,
+    }
+
+    #[test]
+    fn derive_state_machine() {
+        assert_eq!(
+            Number::transition_table().serialize(),
+            "- 0 Start 1 1 E\n+ 1 Digits 1 1 2\n- 2 Point 3 3 E\n+ 3 Fraction 3 3 E\n"
+        );
+        assert_eq!(Number::alphabet().classify('.'), Some(2));
+        assert_eq!(Number::start(), Number::Start);
+        assert_eq!(Number::Point.state_id(), 2);
+        assert_eq!(Number::from_state_id(4), None);
+
+        assert_eq!(Number::run("10.1"), Some(Number::Fraction));
+        assert_eq!(Number::Digits.next('2'), None);
+        assert!(Number::accepts("101"));
+        assert!(!Number::accepts("1."));
+        assert!(!Number::accepts(""));
+
+        // The table works with the rest of the crate
+        assert_eq!(Number::transition_table().minimize().rows.len(), 4);
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{Alphabet, ParseSerializeError, TransitionTable, STARTING_STATE_ID};
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// A search for the non-empty leftmost-longest matches of a table in a stream of text chunks
+///
+/// Matches are reported as byte ranges from the start of the stream, as soon as no longer match
+/// is possible. The text of the match being tried is buffered until it is resolved, since a failed
+/// attempt is retried from the next character. Empty matches are never reported.
+#[derive(Clone, Debug)]
+pub struct StreamMatcher<'a> {
+    /// The table to match
+    table: &'a TransitionTable,
+
+    /// The alphabet classifying characters into columns
+    alphabet: &'a Alphabet,
+
+    /// The resumable state of the search
+    checkpoint: StreamCheckpoint,
+}
+
+/// The resumable state of a [`StreamMatcher`]
+///
+/// A checkpoint can be saved with [`StreamCheckpoint::serialize`] and later restored with
+/// [`StreamMatcher::restore`], so a process matching an endless stream can resume after a
+/// restart without reprocessing more than the buffered text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StreamCheckpoint {
+    /// The state reached by the text of the match being tried, or `None` if the table has no
+    /// starting state
+    pub state: Option<usize>,
+
+    /// The byte offset in the stream where the match being tried starts
+    pub start: usize,
+
+    /// The end of the longest match found so far from `start`, if there is one
+    pub end: Option<usize>,
+
+    /// The text read since `start`
+    pub pending: String,
+}
+
+impl StreamCheckpoint {
+    /// Get the byte offset in the stream of the next character to be read
+    pub fn position(&self) -> usize {
+        self.start + self.pending.len()
+    }
+
+    /// Serialize the checkpoint
+    ///
+    /// The state, start, and end are written one per line, followed by a `pending` line and then
+    /// the pending text exactly as it is.
+    pub fn serialize(&self) -> String {
+        let optional =
+            |value: Option<usize>| value.map_or("E".to_string(), |value| value.to_string());
+        format!(
+            "state {}\nstart {}\nend {}\npending\n{}",
+            optional(self.state),
+            self.start,
+            optional(self.end),
+            self.pending
+        )
+    }
+
+    /// Parse a checkpoint written by [`StreamCheckpoint::serialize`]
+    pub fn parse(input: &str) -> Result<Self, ParseSerializeError> {
+        let error = |message: &str| ParseSerializeError {
+            message: format!("Invalid stream checkpoint: {}", message),
+        };
+
+        let mut lines = input.splitn(5, '\n');
+        let mut field = |name: &str| {
+            lines
+                .next()
+                .and_then(|line| line.strip_prefix(name))
+                .and_then(|value| value.strip_prefix(' '))
+                .ok_or_else(|| error(&format!("expected {}", name)))
+        };
+        let optional = |value: &str| match value {
+            "E" => Ok(None),
+            _ => value.parse().map(Some).map_err(|_| error("invalid number")),
+        };
+
+        let state = optional(field("state")?)?;
+        let start = field("start")?
+            .parse()
+            .map_err(|_| error("invalid number"))?;
+        let end = optional(field("end")?)?;
+        if lines.next() != Some("pending") {
+            return Err(error("expected pending"));
+        }
+        let pending = lines.next().unwrap_or("").to_string();
+
+        Ok(StreamCheckpoint {
+            state,
+            start,
+            end,
+            pending,
+        })
+    }
+}
+
+impl<'a> StreamMatcher<'a> {
+    /// Start matching at the beginning of a stream
+    pub fn new(table: &'a TransitionTable, alphabet: &'a Alphabet) -> Self {
+        StreamMatcher::restore(
+            table,
+            alphabet,
+            StreamCheckpoint {
+                state: table.row(STARTING_STATE_ID).map(|row| row.id),
+                start: 0,
+                end: None,
+                pending: String::new(),
+            },
+        )
+    }
+
+    /// Resume matching from a checkpoint taken with the same table and alphabet
+    pub fn restore(
+        table: &'a TransitionTable,
+        alphabet: &'a Alphabet,
+        checkpoint: StreamCheckpoint,
+    ) -> Self {
+        StreamMatcher {
+            table,
+            alphabet,
+            checkpoint,
+        }
+    }
+
+    /// Get the state to save to resume matching later
+    pub fn checkpoint(&self) -> &StreamCheckpoint {
+        &self.checkpoint
+    }
+
+    /// Read the next chunk of the stream, returning the matches it completes
+    pub fn feed(&mut self, chunk: &str) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        let mut input: VecDeque<char> = chunk.chars().collect();
+
+        while let Some(c) = input.pop_front() {
+            let checkpoint = &mut self.checkpoint;
+            checkpoint.pending.push(c);
+
+            let next = checkpoint.state.zip(self.alphabet.classify(c));
+            match next.and_then(|(state, symbol)| self.table.next_state(state, symbol)) {
+                Some(next) => {
+                    checkpoint.state = Some(next);
+                    if self.table.row(next).is_some_and(|row| row.accepting) {
+                        checkpoint.end = Some(checkpoint.position());
+                    }
+                }
+                None => {
+                    let (found, leftover) = self.resolve();
+                    matches.extend(found);
+                    for c in leftover.chars().rev() {
+                        input.push_front(c);
+                    }
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// End the stream, returning the matches in the text still buffered
+    pub fn finish(mut self) -> Vec<Range<usize>> {
+        let mut matches = Vec::new();
+        while !self.checkpoint.pending.is_empty() {
+            let (found, leftover) = self.resolve();
+            matches.extend(found);
+            matches.extend(self.feed(&leftover));
+        }
+        matches
+    }
+
+    /// Give up on extending the match being tried, reporting it if there is one
+    ///
+    /// Restarts from the end of the match, or from the character after the start if there was no
+    /// match, and returns the buffered text after that point so it can be read again.
+    fn resolve(&mut self) -> (Option<Range<usize>>, String) {
+        let checkpoint = &mut self.checkpoint;
+        let (found, restart) = match checkpoint.end {
+            Some(end) => (Some(checkpoint.start..end), end - checkpoint.start),
+            None => (
+                None,
+                checkpoint.pending.chars().next().map_or(0, char::len_utf8),
+            ),
+        };
+
+        let leftover = checkpoint.pending.split_off(restart);
+        checkpoint.pending.clear();
+        checkpoint.start += restart;
+        checkpoint.end = None;
+        checkpoint.state = self.table.row(STARTING_STATE_ID).map(|row| row.id);
+
+        (found, leftover)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CharSet;
+
+    /// Numbers with an optional fractional part, over digits and '.'
+    const NUMBER: &str = "- 0 1 E\n+ 1 1 2\n- 2 3 E\n+ 3 3 E\n";
+
+    fn number_alphabet() -> Alphabet {
+        Alphabet::new(vec![CharSet::new([('0', '9')]), CharSet::single('.')])
+    }
+
+    #[test]
+    fn stream_matcher() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(NUMBER)?;
+        let alphabet = number_alphabet();
+        let haystack = "1, 2.5 and 30. and 4.x7";
+
+        // Feeding the stream in pieces finds the same matches as searching it whole
+        let expected = table
+            .pattern(&alphabet)
+            .find_iter(haystack)
+            .collect::<Vec<_>>();
+        for split in 0..haystack.len() {
+            let mut matcher = StreamMatcher::new(&table, &alphabet);
+            let mut matches = matcher.feed(&haystack[..split]);
+            matches.extend(matcher.feed(&haystack[split..]));
+            matches.extend(matcher.finish());
+            assert_eq!(matches, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn stream_checkpoint() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(NUMBER)?;
+        let alphabet = number_alphabet();
+
+        let mut matcher = StreamMatcher::new(&table, &alphabet);
+        assert_eq!(matcher.feed("12 3."), vec![0..2]);
+        let saved = matcher.checkpoint().serialize();
+        assert_eq!(saved, "state 2\nstart 3\nend 4\npending\n3.");
+        assert_eq!(matcher.checkpoint().position(), 5);
+
+        // Resume in a new matcher as if after a restart
+        let checkpoint = StreamCheckpoint::parse(&saved)?;
+        let mut resumed = StreamMatcher::restore(&table, &alphabet, checkpoint);
+        assert_eq!(resumed.feed("14\n5"), vec![3..7]);
+        assert_eq!(resumed.finish(), vec![8..9]);
+
+        let multiline = StreamCheckpoint {
+            state: None,
+            start: 0,
+            end: None,
+            pending: "a\nb".to_string(),
+        };
+        assert_eq!(StreamCheckpoint::parse(&multiline.serialize())?, multiline);
+        assert!(StreamCheckpoint::parse("state 1\nstart x\nend E\npending\n").is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,354 @@
+use crate::{Layout, TransitionTable, STARTING_STATE_ID};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+/// The radius of a state's circle
+const STATE_RADIUS: f64 = 20.0;
+
+/// The gap between the two circles of an accepting state
+const ACCEPTING_GAP: f64 = 4.0;
+
+/// The space around the drawing
+const MARGIN: f64 = 60.0;
+
+/// The color used for highlighted states and transitions
+const HIGHLIGHT_COLOR: &str = "#d62728";
+
+/// Options controlling how a table is rendered
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RenderOptions {
+    /// Labels for the columns, used on edges instead of the column indices
+    pub symbol_labels: Option<Vec<String>>,
+
+    /// States to draw in the highlight color
+    pub highlighted_states: Vec<usize>,
+
+    /// Transitions to draw in the highlight color, as (state ID, column) pairs
+    pub highlighted_transitions: Vec<(usize, usize)>,
+
+    /// Error transitions to mark with a highlighted stub ending in a cross, as (state ID, column)
+    /// pairs
+    pub failed_transitions: Vec<(usize, usize)>,
+}
+
+impl RenderOptions {
+    /// Get the label for a column
+    fn symbol_label(&self, symbol: usize) -> String {
+        self.symbol_labels
+            .as_ref()
+            .and_then(|labels| labels.get(symbol).cloned())
+            .unwrap_or_else(|| symbol.to_string())
+    }
+}
+
+impl TransitionTable {
+    /// Render the table as a self-contained SVG image
+    ///
+    /// States are placed by [`TransitionTable::layout`]. Transitions between the same pair of
+    /// states are drawn as one edge labeled with all of their symbols.
+    pub fn to_svg(&self, options: &RenderOptions) -> String {
+        let layout = self.layout();
+        let mut output = String::new();
+
+        let width = layout.width + 2.0 * MARGIN;
+        let height = layout.height + 2.0 * MARGIN;
+        writeln!(
+            output,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="14">"#
+        )
+        .unwrap();
+
+        // Arrowheads for the plain and highlighted edges
+        output.push_str("<defs>\n");
+        for (name, color) in [("arrow", "black"), ("arrow-highlight", HIGHLIGHT_COLOR)] {
+            writeln!(
+                output,
+                r#"<marker id="{name}" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="{color}"/></marker>"#
+            )
+            .unwrap();
+        }
+        output.push_str("</defs>\n");
+
+        // Group the transitions into edges between pairs of states
+        let mut edges: BTreeMap<(usize, usize), (Vec<String>, bool)> = BTreeMap::new();
+        for row in &self.rows {
+            for (symbol, transition) in row.transitions.iter().enumerate() {
+                let Some(target) = transition.filter(|target| self.row(*target).is_some()) else {
+                    continue;
+                };
+
+                let edge = edges.entry((row.id, target)).or_default();
+                edge.0.push(options.symbol_label(symbol));
+                edge.1 |= options.highlighted_transitions.contains(&(row.id, symbol));
+            }
+        }
+
+        for ((from, to), (labels, highlighted)) in &edges {
+            let reverse = from != to && edges.contains_key(&(*to, *from));
+            render_edge(
+                &mut output,
+                &layout,
+                (*from, *to),
+                &labels.join(","),
+                *highlighted,
+                reverse,
+            );
+        }
+
+        // Stubs for the failed error transitions, one per state
+        let mut failed: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for (id, symbol) in &options.failed_transitions {
+            if layout.position(*id).is_some() {
+                failed
+                    .entry(*id)
+                    .or_default()
+                    .push(options.symbol_label(*symbol));
+            }
+        }
+        for (id, labels) in &failed {
+            let position = layout.position(*id).unwrap();
+            let (x, y) = (position.x + MARGIN, position.y + MARGIN);
+            let (x1, y1) = (x + STATE_RADIUS * 0.7, y + STATE_RADIUS * 0.7);
+            let (x2, y2) = (x + STATE_RADIUS + 20.0, y + STATE_RADIUS + 20.0);
+            writeln!(
+                output,
+                r#"<line x1="{x1}" y1="{y1}" x2="{x2}" y2="{y2}" stroke="{HIGHLIGHT_COLOR}" stroke-width="2"/>"#
+            )
+            .unwrap();
+            writeln!(
+                output,
+                r#"<path d="M {} {} L {} {} M {} {} L {} {}" stroke="{HIGHLIGHT_COLOR}" stroke-width="2"/>"#,
+                x2 - 5.0,
+                y2 - 5.0,
+                x2 + 5.0,
+                y2 + 5.0,
+                x2 - 5.0,
+                y2 + 5.0,
+                x2 + 5.0,
+                y2 - 5.0
+            )
+            .unwrap();
+            writeln!(
+                output,
+                r#"<text x="{}" y="{}" fill="{HIGHLIGHT_COLOR}">{}</text>"#,
+                x2 + 8.0,
+                y2 + 14.0,
+                escape(&labels.join(","))
+            )
+            .unwrap();
+        }
+
+        // Arrow into the starting state
+        if let Some(start) = layout.position(STARTING_STATE_ID) {
+            let (x, y) = (start.x + MARGIN, start.y + MARGIN);
+            writeln!(
+                output,
+                r#"<line x1="{}" y1="{y}" x2="{}" y2="{y}" stroke="black" marker-end="url(#arrow)"/>"#,
+                x - STATE_RADIUS - 30.0,
+                x - STATE_RADIUS
+            )
+            .unwrap();
+        }
+
+        for row in &self.rows {
+            let position = layout.position(row.id).unwrap();
+            let (x, y) = (position.x + MARGIN, position.y + MARGIN);
+            let color = if options.highlighted_states.contains(&row.id) {
+                HIGHLIGHT_COLOR
+            } else {
+                "black"
+            };
+
+            writeln!(
+                output,
+                r#"<circle cx="{x}" cy="{y}" r="{STATE_RADIUS}" fill="white" stroke="{color}" stroke-width="2"/>"#
+            )
+            .unwrap();
+            if row.accepting {
+                writeln!(
+                    output,
+                    r#"<circle cx="{x}" cy="{y}" r="{}" fill="none" stroke="{color}" stroke-width="2"/>"#,
+                    STATE_RADIUS - ACCEPTING_GAP
+                )
+                .unwrap();
+            }
+            writeln!(
+                output,
+                r#"<text x="{x}" y="{y}" text-anchor="middle" dominant-baseline="central" fill="{color}">{}</text>"#,
+                row.id
+            )
+            .unwrap();
+        }
+
+        output.push_str("</svg>\n");
+        output
+    }
+
+    /// Render one SVG frame per step of running the table on `input`
+    ///
+    /// The first frame highlights the starting state, and every following frame highlights the
+    /// transition just taken and the state it entered, on top of the highlights in `options`. The
+    /// frames stop after the first error transition, which has no edge, so its frame highlights
+    /// the state it failed in and marks the transition as one of
+    /// [`RenderOptions::failed_transitions`].
+    pub fn animate_run(&self, input: &[usize], options: &RenderOptions) -> Vec<String> {
+        let frame = |state: usize, taken: Option<(usize, usize)>, failed: bool| {
+            let mut options = options.clone();
+            options.highlighted_states.push(state);
+            if failed {
+                options.failed_transitions.extend(taken);
+            } else {
+                options.highlighted_transitions.extend(taken);
+            }
+            self.to_svg(&options)
+        };
+
+        let mut frames = vec![frame(STARTING_STATE_ID, None, false)];
+        for step in self.trace(input).steps {
+            let taken = Some((step.from, step.symbol));
+            frames.push(frame(
+                step.to.unwrap_or(step.from),
+                taken,
+                step.to.is_none(),
+            ));
+        }
+
+        frames
+    }
+}
+
+/// Render one edge, curving it when there is an edge in the opposite direction
+fn render_edge(
+    output: &mut String,
+    layout: &Layout,
+    (from, to): (usize, usize),
+    label: &str,
+    highlighted: bool,
+    reverse: bool,
+) {
+    let (color, marker) = if highlighted {
+        (HIGHLIGHT_COLOR, "arrow-highlight")
+    } else {
+        ("black", "arrow")
+    };
+    let start = layout.position(from).unwrap();
+    let end = layout.position(to).unwrap();
+    let (x1, y1) = (start.x + MARGIN, start.y + MARGIN);
+    let (x2, y2) = (end.x + MARGIN, end.y + MARGIN);
+
+    let (path, label_x, label_y) = if from == to {
+        // Self-loops are drawn as a loop above the state
+        let path = format!(
+            "M {} {} C {} {} {} {} {} {}",
+            x1 - 8.0,
+            y1 - STATE_RADIUS + 2.0,
+            x1 - 25.0,
+            y1 - STATE_RADIUS - 40.0,
+            x1 + 25.0,
+            y1 - STATE_RADIUS - 40.0,
+            x1 + 8.0,
+            y1 - STATE_RADIUS + 2.0
+        );
+        (path, x1, y1 - STATE_RADIUS - 34.0)
+    } else {
+        let (dx, dy) = (x2 - x1, y2 - y1);
+        let length = (dx * dx + dy * dy).sqrt();
+        let (ux, uy) = (dx / length, dy / length);
+
+        // Bend edges with an opposite edge to the side so the two don't overlap
+        let bend = if reverse { 30.0 } else { 0.0 };
+        let (cx, cy) = ((x1 + x2) / 2.0 + uy * bend, (y1 + y2) / 2.0 - ux * bend);
+
+        let path = format!(
+            "M {} {} Q {cx} {cy} {} {}",
+            x1 + ux * STATE_RADIUS,
+            y1 + uy * STATE_RADIUS,
+            x2 - ux * STATE_RADIUS,
+            y2 - uy * STATE_RADIUS
+        );
+        (
+            path,
+            (x1 + x2 + 2.0 * cx) / 4.0,
+            (y1 + y2 + 2.0 * cy) / 4.0 - 6.0,
+        )
+    };
+
+    writeln!(
+        output,
+        r#"<path d="{path}" fill="none" stroke="{color}" marker-end="url(#{marker})"/>"#
+    )
+    .unwrap();
+    writeln!(
+        output,
+        r#"<text x="{label_x}" y="{label_y}" text-anchor="middle" fill="{color}">{}</text>"#,
+        escape(label)
+    )
+    .unwrap();
+}
+
+/// Escape text for use in SVG content
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn to_svg() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 0 1\n")?;
+
+        let svg = table.to_svg(&RenderOptions {
+            symbol_labels: Some(vec!["a".to_string(), "<b>".to_string()]),
+            highlighted_states: vec![1],
+            highlighted_transitions: vec![(0, 0)],
+            ..RenderOptions::default()
+        });
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<circle").count(), 3);
+        assert_eq!(svg.matches(r#"<path d="M 0 0"#).count(), 2);
+        assert_eq!(svg.matches("<path d=").count(), 5);
+        assert!(svg.contains(">&lt;b&gt;</text>"));
+        assert_eq!(
+            svg.matches(r#"marker-end="url(#arrow-highlight)""#).count(),
+            1
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn animate_run() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 0 1\n")?;
+        let options = RenderOptions::default();
+
+        let frames = table.animate_run(&[0, 1, 0, 1, 0], &options);
+        assert_eq!(frames.len(), 5);
+        assert_eq!(
+            frames[1],
+            table.to_svg(&RenderOptions {
+                highlighted_states: vec![1],
+                highlighted_transitions: vec![(0, 0)],
+                ..RenderOptions::default()
+            })
+        );
+        // The error transition is marked from the state it failed in
+        assert_eq!(
+            frames[4],
+            table.to_svg(&RenderOptions {
+                highlighted_states: vec![0],
+                failed_transitions: vec![(0, 1)],
+                ..RenderOptions::default()
+            })
+        );
+        assert!(frames[4].contains(r##"fill="#d62728">1</text>"##));
+
+        Ok(())
+    }
+}
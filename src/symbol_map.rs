@@ -0,0 +1,249 @@
+use crate::{ParseSerializeError, TransitionTable};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::hash::Hash;
+use std::str::FromStr;
+
+/// The line starting the symbol section of a table
+const SYMBOLS_SECTION: &str = "%symbols";
+
+/// A two-way mapping between external symbols and columns
+///
+/// Symbols can be anything hashable, such as characters, bytes, strings, or an enum of events.
+/// Columns are numbered from 0 in the order symbols are inserted. In text, the mapping is a
+/// section after a table's rows, starting with a `%symbols` line and followed by one
+/// `column symbol` line per column.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolMap<S: Eq + Hash> {
+    /// The symbol of each column
+    symbols: Vec<S>,
+
+    /// The column of each symbol
+    columns: HashMap<S, usize>,
+}
+
+impl<S: Eq + Hash> Default for SymbolMap<S> {
+    fn default() -> Self {
+        SymbolMap {
+            symbols: Vec::new(),
+            columns: HashMap::new(),
+        }
+    }
+}
+
+impl<S: Eq + Hash + Clone> FromIterator<S> for SymbolMap<S> {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let mut map = SymbolMap::new();
+        for symbol in iter {
+            map.insert(symbol);
+        }
+        map
+    }
+}
+
+impl<S: Eq + Hash> SymbolMap<S> {
+    /// Create an empty mapping
+    pub fn new() -> Self {
+        SymbolMap::default()
+    }
+
+    /// Get the column of a symbol
+    pub fn column(&self, symbol: &S) -> Option<usize> {
+        self.columns.get(symbol).copied()
+    }
+
+    /// Get the symbol of a column
+    pub fn symbol(&self, column: usize) -> Option<&S> {
+        self.symbols.get(column)
+    }
+
+    /// Get the number of mapped symbols
+    pub fn len(&self) -> usize {
+        self.symbols.len()
+    }
+
+    /// Check whether no symbols are mapped
+    pub fn is_empty(&self) -> bool {
+        self.symbols.is_empty()
+    }
+
+    /// Iterate over the symbols in order of column
+    pub fn iter(&self) -> impl Iterator<Item = &S> {
+        self.symbols.iter()
+    }
+}
+
+impl<S: Eq + Hash + Clone> SymbolMap<S> {
+    /// Map a symbol to the next column, returning its column
+    ///
+    /// A symbol that is already mapped keeps its column.
+    pub fn insert(&mut self, symbol: S) -> usize {
+        if let Some(column) = self.column(&symbol) {
+            return column;
+        }
+        self.columns.insert(symbol.clone(), self.symbols.len());
+        self.symbols.push(symbol);
+        self.symbols.len() - 1
+    }
+}
+
+impl<S: Eq + Hash + Display> SymbolMap<S> {
+    /// Get the label of every column, for [`crate::RenderOptions::symbol_labels`]
+    pub fn labels(&self) -> Vec<String> {
+        self.symbols
+            .iter()
+            .map(|symbol| symbol.to_string())
+            .collect()
+    }
+
+    /// Serialize the mapping as a `%symbols` section
+    pub fn serialize(&self) -> String {
+        let mut output = format!("{}\n", SYMBOLS_SECTION);
+        for (column, symbol) in self.symbols.iter().enumerate() {
+            output.push_str(&format!("{} {}\n", column, symbol));
+        }
+        output
+    }
+}
+
+impl<S> SymbolMap<S>
+where
+    S: Eq + Hash + Clone + FromStr,
+    S::Err: Display,
+{
+    /// Parse the lines of a `%symbols` section, without the `%symbols` line
+    ///
+    /// Columns must be listed in order from 0, each with a different symbol.
+    pub fn parse(section: &str) -> Result<Self, ParseSerializeError> {
+        let mut map = SymbolMap::new();
+        for (line_index, line) in section.lines().enumerate() {
+            let error = |message: String| ParseSerializeError {
+                message: format!("Symbol line {} {}", line_index + 1, message),
+            };
+
+            let (column, symbol) = line
+                .trim()
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| error("needs a column and a symbol".to_string()))?;
+            let column: usize = column
+                .parse()
+                .map_err(|e| error(format!("has an invalid column: {}", e)))?;
+            let symbol: S = symbol
+                .trim()
+                .parse()
+                .map_err(|e| error(format!("has an invalid symbol: {}", e)))?;
+
+            if column != map.len() {
+                return Err(error(format!(
+                    "maps column {}, but column {} is next",
+                    column,
+                    map.len()
+                )));
+            }
+            if map.column(&symbol).is_some() {
+                return Err(error("repeats a symbol".to_string()));
+            }
+            map.insert(symbol);
+        }
+
+        Ok(map)
+    }
+}
+
+impl TransitionTable {
+    /// Parse a table followed by an optional `%symbols` section naming its columns
+    ///
+    /// Fails if the section names more columns than the table has.
+    pub fn parse_with_symbols<S>(input: &str) -> Result<(Self, SymbolMap<S>), ParseSerializeError>
+    where
+        S: Eq + Hash + Clone + FromStr,
+        S::Err: Display,
+    {
+        let (table, section) = match input.split_once(&format!("{}\n", SYMBOLS_SECTION)) {
+            Some((table, section)) => (table, section),
+            None => (input.strip_suffix(SYMBOLS_SECTION).unwrap_or(input), ""),
+        };
+        let table = TransitionTable::parse(table)?;
+        let symbols = SymbolMap::parse(section)?;
+
+        if symbols.len() > table.width() {
+            return Err(ParseSerializeError {
+                message: format!(
+                    "Symbol section names {} columns, but the table has {}",
+                    symbols.len(),
+                    table.width()
+                ),
+            });
+        }
+
+        Ok((table, symbols))
+    }
+
+    /// Serialize the table followed by a `%symbols` section
+    pub fn serialize_with_symbols<S: Eq + Hash + Display>(&self, symbols: &SymbolMap<S>) -> String {
+        self.serialize() + &symbols.serialize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Automaton;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    enum Event {
+        Coin,
+        Push,
+    }
+
+    impl FromStr for Event {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "coin" => Ok(Event::Coin),
+                "push" => Ok(Event::Push),
+                _ => Err(format!("unknown event {}", s)),
+            }
+        }
+    }
+
+    impl Display for Event {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Event::Coin => write!(f, "coin"),
+                Event::Push => write!(f, "push"),
+            }
+        }
+    }
+
+    /// A turnstile that unlocks on a coin and locks again when pushed
+    const TURNSTILE: &str = "+ 0 1 0\n- 1 1 0\n%symbols\n0 coin\n1 push\n";
+
+    #[test]
+    fn symbol_map() -> Result<(), ParseSerializeError> {
+        let (table, symbols) = TransitionTable::parse_with_symbols::<Event>(TURNSTILE)?;
+        assert_eq!(symbols.column(&Event::Push), Some(1));
+        assert_eq!(symbols.symbol(0), Some(&Event::Coin));
+        assert_eq!(table.serialize_with_symbols(&symbols), TURNSTILE);
+
+        assert_eq!(
+            table.run_symbols(&symbols, &[Event::Coin, Event::Coin]),
+            Some(1)
+        );
+        assert!(table.accepts_symbols(&symbols, &[Event::Coin, Event::Push]));
+
+        let mut chars: SymbolMap<char> = "abca".chars().collect();
+        assert_eq!(chars.len(), 3);
+        assert_eq!(chars.insert('d'), 3);
+        assert_eq!(chars.insert('b'), 1);
+
+        assert!(TransitionTable::parse_with_symbols::<Event>("+ 0 0\n%symbols\n1 coin\n").is_err());
+        assert!(
+            TransitionTable::parse_with_symbols::<Event>("+ 0 0\n%symbols\n0 coin\n1 push\n")
+                .is_err()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,574 @@
+use crate::{
+    Alphabet, CharSet, ParseSerializeError, TransitionTable, TransitionTableRow, STARTING_STATE_ID,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+/// The condition a character must meet to take a symbolic transition
+#[derive(Clone)]
+pub enum Guard {
+    /// The character must be in the set
+    Set(CharSet),
+
+    /// The predicate must return true for the character
+    ///
+    /// Predicate guards can't be serialized.
+    Predicate(Arc<dyn Fn(char) -> bool + Send + Sync>),
+}
+
+impl Guard {
+    /// Create a predicate guard from a closure
+    pub fn predicate(predicate: impl Fn(char) -> bool + Send + Sync + 'static) -> Self {
+        Guard::Predicate(Arc::new(predicate))
+    }
+
+    /// Check whether a character satisfies the guard
+    pub fn matches(&self, c: char) -> bool {
+        match self {
+            Guard::Set(set) => set.contains(c),
+            Guard::Predicate(predicate) => predicate(c),
+        }
+    }
+
+    /// Get the guard satisfied by characters satisfying both guards
+    pub fn intersection(&self, other: &Guard) -> Guard {
+        match (self, other) {
+            (Guard::Set(a), Guard::Set(b)) => Guard::Set(a.intersection(b)),
+            _ => {
+                let (a, b) = (self.clone(), other.clone());
+                Guard::predicate(move |c| a.matches(c) && b.matches(c))
+            }
+        }
+    }
+
+    /// Get the guard satisfied by characters satisfying either guard
+    pub fn union(&self, other: &Guard) -> Guard {
+        match (self, other) {
+            (Guard::Set(a), Guard::Set(b)) => Guard::Set(a.union(b)),
+            _ => {
+                let (a, b) = (self.clone(), other.clone());
+                Guard::predicate(move |c| a.matches(c) || b.matches(c))
+            }
+        }
+    }
+
+    /// Get the guard satisfied by characters not satisfying this guard
+    pub fn complement(&self) -> Guard {
+        match self {
+            Guard::Set(set) => Guard::Set(set.complement()),
+            Guard::Predicate(_) => {
+                let guard = self.clone();
+                Guard::predicate(move |c| !guard.matches(c))
+            }
+        }
+    }
+
+    /// Check whether any character satisfies the guard
+    ///
+    /// Returns `None` for predicate guards, which can't be decided.
+    pub fn is_satisfiable(&self) -> Option<bool> {
+        match self {
+            Guard::Set(set) => Some(set.is_satisfiable()),
+            Guard::Predicate(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Debug for Guard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Guard::Set(set) => f.debug_tuple("Set").field(set).finish(),
+            Guard::Predicate(_) => f.write_str("Predicate(..)"),
+        }
+    }
+}
+
+/// A transition of a symbolic automaton
+#[derive(Clone, Debug)]
+pub struct SymbolicTransition {
+    /// The condition for taking the transition
+    pub guard: Guard,
+
+    /// The state the transition enters
+    pub target: usize,
+}
+
+/// A state (row) in a symbolic automaton
+#[derive(Clone, Debug)]
+pub struct SymbolicRow {
+    /// Whether the row is for an accepting state
+    pub accepting: bool,
+
+    /// The row's state ID (0 means the starting state)
+    pub id: usize,
+
+    /// The row's transitions, tried in order
+    ///
+    /// Characters matched by no guard take an error transition.
+    pub transitions: Vec<SymbolicTransition>,
+}
+
+/// A DFA whose transitions are guarded by conditions on characters instead of table columns
+///
+/// This keeps automata over huge alphabets, such as all of Unicode, small. The text format is like
+/// the transition table format, but each transition is a character set and target state joined by a
+/// colon, such as `- 0 [a-z_]:1 [0-9]:2`.
+#[derive(Clone, Debug)]
+pub struct SymbolicAutomaton {
+    /// The rows in the automaton, sorted by state ID
+    pub rows: Vec<SymbolicRow>,
+}
+
+impl SymbolicAutomaton {
+    /// Get the row with the given state ID, if it exists
+    pub fn row(&self, id: usize) -> Option<&SymbolicRow> {
+        self.rows
+            .binary_search_by_key(&id, |row| row.id)
+            .ok()
+            .map(|index| &self.rows[index])
+    }
+
+    /// Get the state reached from `state` on a character, using the first matching guard
+    pub fn next_state(&self, state: usize, c: char) -> Option<usize> {
+        let transition = self
+            .row(state)?
+            .transitions
+            .iter()
+            .find(|transition| transition.guard.matches(c))?;
+        self.row(transition.target).map(|row| row.id)
+    }
+
+    /// Check whether the automaton accepts a string
+    pub fn accepts(&self, input: &str) -> bool {
+        let mut state = STARTING_STATE_ID;
+
+        for c in input.chars() {
+            match self.next_state(state, c) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+
+        self.row(state).is_some_and(|row| row.accepting)
+    }
+
+    /// Get the transitions of a state with disjoint guards, plus the guard for the error transition
+    ///
+    /// Each guard has the characters matched by earlier guards removed, so exactly one of the
+    /// guards is satisfied by every character. Unsatisfiable guards are dropped.
+    fn disjoint_transitions(&self, state: usize) -> (Vec<(Guard, usize)>, Guard) {
+        let mut transitions = Vec::new();
+        let mut covered = Guard::Set(CharSet::default());
+
+        for transition in self.row(state).map_or(&[][..], |row| &row.transitions) {
+            let guard = transition.guard.intersection(&covered.complement());
+            if guard.is_satisfiable() != Some(false) && self.row(transition.target).is_some() {
+                transitions.push((guard, transition.target));
+            }
+            covered = covered.union(&transition.guard);
+        }
+
+        (transitions, covered.complement())
+    }
+
+    /// Build the product of two automata over pairs of states
+    ///
+    /// A missing side means that automaton has taken an error transition. `union` keeps pairs
+    /// where one side is missing, and `accepting` decides which pairs accept.
+    fn product(
+        &self,
+        other: &SymbolicAutomaton,
+        union: bool,
+        accepting: impl Fn(bool, bool) -> bool,
+    ) -> SymbolicAutomaton {
+        let start = (
+            self.row(STARTING_STATE_ID).map(|row| row.id),
+            other.row(STARTING_STATE_ID).map(|row| row.id),
+        );
+        let mut ids = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        let mut rows = Vec::new();
+
+        // Each side has a transition for every character, with `None` for error transitions
+        let side = |automaton: &SymbolicAutomaton, state: Option<usize>| match state {
+            Some(state) => {
+                let (mut transitions, rest) = automaton.disjoint_transitions(state);
+                let mut transitions = transitions
+                    .drain(..)
+                    .map(|(guard, target)| (guard, Some(target)))
+                    .collect::<Vec<_>>();
+                transitions.push((rest, None));
+                transitions
+            }
+            None => vec![(Guard::Set(CharSet::any()), None)],
+        };
+
+        while let Some((a, b)) = queue.pop_front() {
+            let mut transitions = Vec::new();
+
+            for (a_guard, a_target) in side(self, a) {
+                for (b_guard, b_target) in side(other, b) {
+                    let keep = match (a_target, b_target) {
+                        (Some(_), Some(_)) => true,
+                        (None, None) => false,
+                        _ => union,
+                    };
+                    let guard = a_guard.intersection(&b_guard);
+                    if !keep || guard.is_satisfiable() == Some(false) {
+                        continue;
+                    }
+
+                    let target = (a_target, b_target);
+                    let id = *ids.entry(target).or_insert_with(|| {
+                        queue.push_back(target);
+                        rows.len() + queue.len()
+                    });
+                    transitions.push(SymbolicTransition { guard, target: id });
+                }
+            }
+
+            let is_accepting = |automaton: &SymbolicAutomaton, state: Option<usize>| {
+                state
+                    .and_then(|state| automaton.row(state))
+                    .is_some_and(|row| row.accepting)
+            };
+            rows.push(SymbolicRow {
+                accepting: accepting(is_accepting(self, a), is_accepting(other, b)),
+                id: ids[&(a, b)],
+                transitions,
+            });
+        }
+
+        SymbolicAutomaton { rows }
+    }
+
+    /// Build the automaton accepting strings accepted by both automata
+    pub fn intersect(&self, other: &SymbolicAutomaton) -> SymbolicAutomaton {
+        self.product(other, false, |a, b| a && b)
+    }
+
+    /// Build the automaton accepting strings accepted by either automaton
+    pub fn union(&self, other: &SymbolicAutomaton) -> SymbolicAutomaton {
+        self.product(other, true, |a, b| a || b)
+    }
+
+    /// Split the characters used by the guards into classes that every guard treats the same way
+    ///
+    /// Characters matched by no guard are left out. Returns `None` if any guard is a predicate.
+    pub fn minterms(&self) -> Option<Alphabet> {
+        let mut sets = Vec::new();
+        for transition in self.rows.iter().flat_map(|row| &row.transitions) {
+            match &transition.guard {
+                Guard::Set(set) => sets.push(set),
+                Guard::Predicate(_) => return None,
+            }
+        }
+
+        // Every range boundary starts a new elementary interval
+        let mut boundaries = sets
+            .iter()
+            .flat_map(|set| set.ranges())
+            .flat_map(|(start, end)| [Some(*start), crate::char_set::next_char(*end)])
+            .flatten()
+            .collect::<Vec<_>>();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        // Group the intervals by which sets contain them
+        let mut classes: HashMap<Vec<bool>, Vec<(char, char)>> = HashMap::new();
+        let mut order = Vec::new();
+        for (index, start) in boundaries.iter().enumerate() {
+            let signature = sets
+                .iter()
+                .map(|set| set.contains(*start))
+                .collect::<Vec<_>>();
+            if !signature.contains(&true) {
+                continue;
+            }
+
+            let end = boundaries.get(index + 1).map_or(char::MAX, |next| {
+                crate::char_set::previous_char(*next).unwrap()
+            });
+            classes
+                .entry(signature.clone())
+                .or_insert_with(|| {
+                    order.push(signature);
+                    Vec::new()
+                })
+                .push((*start, end));
+        }
+
+        Some(Alphabet::new(
+            order
+                .into_iter()
+                .map(|signature| CharSet::new(classes.remove(&signature).unwrap()))
+                .collect(),
+        ))
+    }
+
+    /// Convert the automaton to a transition table with one column per minterm
+    ///
+    /// Returns `None` if any guard is a predicate.
+    pub fn to_table(&self) -> Option<(TransitionTable, Alphabet)> {
+        let alphabet = self.minterms()?;
+
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| TransitionTableRow {
+                accepting: row.accepting,
+                id: row.id,
+                transitions: alphabet
+                    .classes()
+                    .iter()
+                    .map(|class| self.next_state(row.id, class.first().unwrap()))
+                    .collect(),
+                doc: None,
+                label: None,
+            })
+            .collect();
+
+        Some((
+            TransitionTable {
+                rows,
+                alphabet: None,
+                metadata: Default::default(),
+                comment: None,
+            },
+            alphabet,
+        ))
+    }
+
+    /// Convert a transition table to a symbolic automaton, guarding each column by its class
+    ///
+    /// Columns entering the same state are merged into one transition.
+    pub fn from_table(table: &TransitionTable, alphabet: &Alphabet) -> SymbolicAutomaton {
+        let rows = table
+            .rows
+            .iter()
+            .map(|row| {
+                let mut targets: Vec<(usize, CharSet)> = Vec::new();
+                for (transition, class) in row.transitions.iter().zip(alphabet.classes()) {
+                    let Some(target) = transition else {
+                        continue;
+                    };
+                    match targets.iter_mut().find(|(existing, _)| existing == target) {
+                        Some((_, set)) => *set = set.union(class),
+                        None => targets.push((*target, class.clone())),
+                    }
+                }
+                targets.sort_by_key(|(target, _)| *target);
+
+                SymbolicRow {
+                    accepting: row.accepting,
+                    id: row.id,
+                    transitions: targets
+                        .into_iter()
+                        .map(|(target, set)| SymbolicTransition {
+                            guard: Guard::Set(set),
+                            target,
+                        })
+                        .collect(),
+                }
+            })
+            .collect();
+
+        SymbolicAutomaton { rows }
+    }
+
+    /// Compute the minimal automaton accepting the same language
+    ///
+    /// Returns `None` if any guard is a predicate.
+    pub fn minimize(&self) -> Option<SymbolicAutomaton> {
+        let (table, alphabet) = self.to_table()?;
+        Some(SymbolicAutomaton::from_table(&table.minimize(), &alphabet))
+    }
+
+    /// Parse a symbolic automaton from a string
+    pub fn parse(input: &str) -> Result<Self, ParseSerializeError> {
+        let mut rows = Vec::new();
+
+        for (line_index, line) in input.lines().enumerate() {
+            let columns = line.split_whitespace().collect::<Vec<_>>();
+
+            // Check that there are at least the accepting state and ID columns
+            if columns.len() < 2 {
+                return Err(ParseSerializeError {
+                    message: format!("Line {} has too few columns", line_index + 1),
+                });
+            }
+
+            // Parse accepting state column
+            let accepting = match columns[0] {
+                "+" => true,
+                "-" => false,
+                _ => {
+                    return Err(ParseSerializeError {
+                        message: format!("Line {} has an invalid accepting state", line_index + 1),
+                    });
+                }
+            };
+
+            // Parse the ID column
+            let id = columns[1].parse().map_err(|e| ParseSerializeError {
+                message: format!("Line {} has an invalid state ID: {}", line_index + 1, e),
+            })?;
+
+            // Parse the transitions
+            let mut transitions = Vec::new();
+            for (column_index, column) in columns.iter().skip(2).enumerate() {
+                let error = |message: String| ParseSerializeError {
+                    message: format!(
+                        "Line {} column {} has an invalid transition: {}",
+                        line_index + 1,
+                        column_index + 3,
+                        message
+                    ),
+                };
+
+                let (set, target) = column
+                    .rsplit_once(':')
+                    .ok_or_else(|| error("expected a set and target".to_string()))?;
+                transitions.push(SymbolicTransition {
+                    guard: Guard::Set(CharSet::parse(set).map_err(|e| error(e.message))?),
+                    target: target.parse().map_err(|e| error(format!("{}", e)))?,
+                });
+            }
+
+            rows.push(SymbolicRow {
+                accepting,
+                id,
+                transitions,
+            });
+        }
+
+        // Sort the rows by state ID
+        rows.sort_by_key(|row| row.id);
+
+        Ok(SymbolicAutomaton { rows })
+    }
+
+    /// Serialize the automaton to a string
+    ///
+    /// Fails if any transition has a predicate guard.
+    pub fn serialize(&self) -> Result<String, ParseSerializeError> {
+        let mut output = String::new();
+
+        for row in &self.rows {
+            output.push(if row.accepting { '+' } else { '-' });
+            output.push(' ');
+            output.push_str(&row.id.to_string());
+
+            for transition in &row.transitions {
+                let Guard::Set(set) = &transition.guard else {
+                    return Err(ParseSerializeError {
+                        message: format!(
+                            "State {} has a predicate guard, which can't be serialized",
+                            row.id
+                        ),
+                    });
+                };
+
+                output.push(' ');
+                output.push_str(&set.serialize());
+                output.push(':');
+                output.push_str(&transition.target.to_string());
+            }
+
+            output.push('\n');
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Identifiers: a letter or underscore, then letters, digits, or underscores
+    const IDENTIFIER: &str = "- 0 [A-Z_a-z]:1\n+ 1 [0-9A-Z_a-z]:1\n";
+
+    #[test]
+    fn symbolic_automaton_parse_serialize() -> Result<(), ParseSerializeError> {
+        let automaton = SymbolicAutomaton::parse(IDENTIFIER)?;
+
+        assert!(automaton.accepts("snake_case9"));
+        assert!(!automaton.accepts("9lives"));
+        assert!(!automaton.accepts(""));
+        assert_eq!(automaton.serialize()?, IDENTIFIER);
+
+        assert!(SymbolicAutomaton::parse("- 0 [a]\n").is_err());
+        assert!(SymbolicAutomaton::parse("- 0 [a]:x\n").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_automaton_predicate() {
+        let automaton = SymbolicAutomaton {
+            rows: vec![SymbolicRow {
+                accepting: true,
+                id: 0,
+                transitions: vec![SymbolicTransition {
+                    guard: Guard::predicate(char::is_alphabetic),
+                    target: 0,
+                }],
+            }],
+        };
+
+        assert!(automaton.accepts("日本語"));
+        assert!(!automaton.accepts("a1"));
+        assert!(automaton.serialize().is_err());
+    }
+
+    #[test]
+    fn symbolic_automaton_products() -> Result<(), ParseSerializeError> {
+        let identifier = SymbolicAutomaton::parse(IDENTIFIER)?;
+        let hex = SymbolicAutomaton::parse("- 0 [0-9a-f]:1\n+ 1 [0-9a-f]:1\n")?;
+
+        let both = identifier.intersect(&hex);
+        assert!(both.accepts("cafe"));
+        assert!(!both.accepts("c0ffee_"));
+        assert!(!both.accepts("0ff"));
+
+        let either = identifier.union(&hex);
+        assert!(either.accepts("0ff"));
+        assert!(either.accepts("c0ffee_"));
+        assert!(!either.accepts("_-"));
+
+        let either_predicate = identifier.union(&SymbolicAutomaton {
+            rows: vec![SymbolicRow {
+                accepting: true,
+                id: 0,
+                transitions: vec![SymbolicTransition {
+                    guard: Guard::predicate(|c| c == '-'),
+                    target: 0,
+                }],
+            }],
+        });
+        assert!(either_predicate.accepts("--"));
+        assert!(either_predicate.accepts("a1"));
+        assert!(!either_predicate.accepts("a-"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_automaton_minimize() -> Result<(), ParseSerializeError> {
+        // Two copies of the identifier loop, and a redundant overlapping guard
+        let automaton = SymbolicAutomaton::parse(
+            "- 0 [a-m]:1 [n-z]:2\n+ 1 [a-z]:2 [0-9]:1 [5]:0\n+ 2 [a-z]:1 [0-9]:2\n",
+        )?;
+
+        let (table, alphabet) = automaton.to_table().unwrap();
+        assert_eq!(alphabet.len(), 4);
+        assert_eq!(table.width(), 4);
+
+        let minimal = automaton.minimize().unwrap();
+        assert_eq!(minimal.rows.len(), 2);
+        assert_eq!(minimal.serialize()?, "- 0 [a-z]:1\n+ 1 [0-9a-z]:1\n");
+
+        Ok(())
+    }
+}
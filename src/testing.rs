@@ -0,0 +1,326 @@
+//! Assertions for unit tests of tables
+//!
+//! The macros panic with a readable explanation when an assertion fails, such as the state a
+//! rejected input ended in or a shortest input two tables disagree on. A failing input is also
+//! shrunk with [`shrink`], so the report shows the smallest related input that fails the same way:
+//!
+//! ```
+//! use transition_tables::testing::{
+//!     assert_accepts, assert_equivalent_to_regex, assert_language, assert_rejects,
+//! };
+//! use transition_tables::{Alphabet, TransitionTable};
+//!
+//! let even_ones = TransitionTable::parse("+ 0 0 1\n- 1 1 0\n").unwrap();
+//! assert_accepts!(even_ones, [1, 0, 1]);
+//! assert_rejects!(even_ones, [1]);
+//!
+//! let alphabet = Alphabet::from_chars("01".chars());
+//! assert_accepts!(even_ones, alphabet, "0110");
+//!
+//! assert_language!(even_ones, even_ones.minimize());
+//! assert_equivalent_to_regex!(even_ones, alphabet, "(0|10*1)*");
+//! ```
+
+use crate::{Alphabet, Regex, TransitionTable};
+
+pub use crate::{assert_accepts, assert_equivalent_to_regex, assert_language, assert_rejects};
+
+/// Assert that a table accepts a sequence of columns, or a string classified with an alphabet
+#[macro_export]
+macro_rules! assert_accepts {
+    ($table:expr, $alphabet:expr, $input:expr $(,)?) => {
+        $crate::testing::check_str(&$table, &$alphabet, $input, true)
+    };
+    ($table:expr, $input:expr $(,)?) => {
+        $crate::testing::check_columns(&$table, &$input, true)
+    };
+}
+
+/// Assert that a table rejects a sequence of columns, or a string classified with an alphabet
+#[macro_export]
+macro_rules! assert_rejects {
+    ($table:expr, $alphabet:expr, $input:expr $(,)?) => {
+        $crate::testing::check_str(&$table, &$alphabet, $input, false)
+    };
+    ($table:expr, $input:expr $(,)?) => {
+        $crate::testing::check_columns(&$table, &$input, false)
+    };
+}
+
+/// Assert that two tables accept the same language
+#[macro_export]
+macro_rules! assert_language {
+    ($table:expr, $expected:expr $(,)?) => {
+        $crate::testing::check_language(&$table, &$expected)
+    };
+}
+
+/// Assert that a table accepts the same strings as a regex, classifying characters with an
+/// alphabet
+#[macro_export]
+macro_rules! assert_equivalent_to_regex {
+    ($table:expr, $alphabet:expr, $pattern:expr $(,)?) => {
+        $crate::testing::check_regex(&$table, &$alphabet, $pattern)
+    };
+}
+
+/// Describe where a run ended, for a failure message
+fn describe_run(table: &TransitionTable, state: Option<usize>) -> String {
+    match state {
+        Some(state) if table.row(state).is_some_and(|row| row.accepting) => {
+            format!("in accepting state {}", table.state_name(state))
+        }
+        Some(state) => format!("in rejecting state {}", table.state_name(state)),
+        None => "on an error transition".to_string(),
+    }
+}
+
+/// Shrink an input that fails a check to a locally minimal one that still fails
+///
+/// Repeatedly deletes runs of columns, longest first, then lowers single columns, keeping every
+/// change that still fails. The result is a subsequence of the input no single deletion or lower
+/// column of which fails, and is usually much shorter.
+pub fn shrink(input: &[usize], fails: impl Fn(&[usize]) -> bool) -> Vec<usize> {
+    let mut input = input.to_vec();
+    loop {
+        let len = input.len();
+        let mut run = len.div_ceil(2);
+        while run > 0 {
+            let mut start = 0;
+            while start + run <= input.len() {
+                let mut candidate = input.clone();
+                candidate.drain(start..start + run);
+                if fails(&candidate) {
+                    input = candidate;
+                } else {
+                    start += 1;
+                }
+            }
+            run /= 2;
+        }
+
+        let mut lowered = false;
+        for position in 0..input.len() {
+            for symbol in 0..input[position] {
+                let mut candidate = input.clone();
+                candidate[position] = symbol;
+                if fails(&candidate) {
+                    input = candidate;
+                    lowered = true;
+                    break;
+                }
+            }
+        }
+
+        if !lowered && input.len() == len {
+            return input;
+        }
+    }
+}
+
+/// Panic unless a table gives the expected answer for a sequence of columns
+#[doc(hidden)]
+#[track_caller]
+pub fn check_columns(table: &TransitionTable, input: &[usize], accepted: bool) {
+    if table.accepts(input) != accepted {
+        let shrunk = shrink(input, |input| table.accepts(input) != accepted);
+        let shrunk = if shrunk == input {
+            String::new()
+        } else {
+            format!(" ({:?} fails too)", shrunk)
+        };
+        panic!(
+            "expected the table to {} {:?}, but the run ended {}{}",
+            if accepted { "accept" } else { "reject" },
+            input,
+            describe_run(table, table.run_prefix(input)),
+            shrunk
+        );
+    }
+}
+
+/// Get a character of each column of an alphabet, then one past the last column for characters
+/// in no column, preferring the characters of an input
+fn representatives(alphabet: &Alphabet, input: &str) -> Vec<Option<char>> {
+    let mut representatives = vec![None; alphabet.len() + 1];
+    for c in input.chars() {
+        let column = alphabet.classify(c).unwrap_or(alphabet.len());
+        representatives[column].get_or_insert(c);
+    }
+    for (start, _, column) in alphabet.ranges() {
+        representatives[*column].get_or_insert(*start);
+    }
+    representatives
+}
+
+/// Write columns as a string with a character of each, or `None` if a column has no characters
+fn render(representatives: &[Option<char>], columns: &[usize]) -> Option<String> {
+    columns
+        .iter()
+        .map(|column| representatives[*column])
+        .collect()
+}
+
+/// Panic unless a table gives the expected answer for a string
+///
+/// The string is shrunk over the columns of its characters, rendering each column as one of the
+/// string's own characters in it where possible.
+#[doc(hidden)]
+#[track_caller]
+pub fn check_str(table: &TransitionTable, alphabet: &Alphabet, input: &str, accepted: bool) {
+    if table.accepts_str(alphabet, input) != accepted {
+        let representatives = representatives(alphabet, input);
+        let columns: Vec<usize> = input
+            .chars()
+            .map(|c| alphabet.classify(c).unwrap_or(alphabet.len()))
+            .collect();
+        let shrunk = shrink(&columns, |columns| {
+            render(&representatives, columns)
+                .is_some_and(|input| table.accepts_str(alphabet, &input) != accepted)
+        });
+        let shrunk = match render(&representatives, &shrunk) {
+            Some(shrunk) if shrunk != input => format!(" ({:?} fails too)", shrunk),
+            _ => String::new(),
+        };
+        panic!(
+            "expected the table to {} {:?}, but the run ended {}{}",
+            if accepted { "accept" } else { "reject" },
+            input,
+            describe_run(table, table.run_str(alphabet, input)),
+            shrunk
+        );
+    }
+}
+
+/// Panic unless two tables accept the same language
+///
+/// The reported input is the shortest, and lexicographically least among the shortest, that the
+/// tables disagree on.
+#[doc(hidden)]
+#[track_caller]
+pub fn check_language(table: &TransitionTable, expected: &TransitionTable) {
+    if let Some(input) = TransitionTable::shortest_divergence(table, expected) {
+        let accepted = table.accepts(&input);
+        panic!(
+            "expected the tables to accept the same language, but {:?} is {} by the table and {} \
+             by the expected table",
+            input,
+            if accepted { "accepted" } else { "rejected" },
+            if accepted { "rejected" } else { "accepted" },
+        );
+    }
+}
+
+/// Panic unless a table accepts the same strings as a regex compiled with an alphabet
+///
+/// The reported string is a shortest one the table and the regex disagree on.
+#[doc(hidden)]
+#[track_caller]
+pub fn check_regex(table: &TransitionTable, alphabet: &Alphabet, pattern: &str) {
+    let compiled = match Regex::parse(pattern).and_then(|regex| regex.compile(alphabet)) {
+        Ok(compiled) => compiled,
+        Err(error) => panic!("the regex {:?} can't be compiled: {}", pattern, error),
+    };
+    if let Some(input) = TransitionTable::shortest_divergence(table, &compiled) {
+        let accepted = table.accepts(&input);
+        let representatives = representatives(alphabet, "");
+        let input = match render(&representatives, &input) {
+            Some(input) => format!("{:?}", input),
+            None => format!("{:?}", input),
+        };
+        panic!(
+            "expected the table to match the regex {:?}, but {} is {} by the table and {} by the \
+             regex",
+            pattern,
+            input,
+            if accepted { "accepted" } else { "rejected" },
+            if accepted { "rejected" } else { "accepted" },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrink() {
+        // Inputs with at least two 3s fail
+        let fails = |input: &[usize]| input.iter().filter(|symbol| **symbol == 3).count() >= 2;
+        assert_eq!(super::shrink(&[2, 3, 1, 0, 3, 3, 2], fails), vec![3, 3]);
+
+        // Inputs summing to at least 5 fail
+        let fails = |input: &[usize]| input.iter().sum::<usize>() >= 5;
+        assert_eq!(super::shrink(&[4, 4, 4], fails), vec![1, 4]);
+    }
+
+    #[test]
+    fn assertions() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 2\n- 2 E 1\n").unwrap();
+        assert_accepts!(table, [0, 1, 1]);
+        assert_rejects!(table, [0, 1]);
+        assert_language!(table, table.minimize());
+
+        let message = |check: fn(&TransitionTable)| {
+            let panic = std::panic::catch_unwind(|| check(&table)).unwrap_err();
+            panic.downcast_ref::<String>().unwrap().clone()
+        };
+        assert_eq!(
+            message(|table| assert_accepts!(table, [0, 1])),
+            "expected the table to accept [0, 1], but the run ended in rejecting state 2 ([] fails \
+             too)"
+        );
+        assert_eq!(
+            message(|table| assert_rejects!(table, [0])),
+            "expected the table to reject [0], but the run ended in accepting state 1"
+        );
+        assert_eq!(
+            message(|table| assert_rejects!(table, [0, 1, 1, 0, 0])),
+            "expected the table to reject [0, 1, 1, 0, 0], but the run ended in accepting state 1 \
+             ([0] fails too)"
+        );
+        assert_eq!(
+            message(|table| assert_accepts!(table, [1])),
+            "expected the table to accept [1], but the run ended on an error transition ([] fails too)"
+        );
+        assert_eq!(
+            message(|table| {
+                let other = TransitionTable::parse("- 0 1 E\n+ 1 1 1\n").unwrap();
+                assert_language!(table, other)
+            }),
+            "expected the tables to accept the same language, but [0, 1] is rejected by the table \
+             and accepted by the expected table"
+        );
+
+        // Strings are shrunk like columns, keeping their own characters
+        let alphabet = Alphabet::from_chars("ab".chars());
+        assert_accepts!(table, alphabet, "abba");
+        assert_eq!(
+            message(|table| assert_accepts!(table, Alphabet::from_chars("ab".chars()), "abbab")),
+            "expected the table to accept \"abbab\", but the run ended in rejecting state 2 \
+             (\"\" fails too)"
+        );
+        assert_eq!(
+            message(|table| assert_rejects!(table, Alphabet::from_chars("ab".chars()), "abbaa")),
+            "expected the table to reject \"abbaa\", but the run ended in accepting state 1 \
+             (\"a\" fails too)"
+        );
+    }
+
+    #[test]
+    fn assert_equivalent_to_regex() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 2\n- 2 E 1\n").unwrap();
+        let alphabet = Alphabet::from_chars("ab".chars());
+        assert_equivalent_to_regex!(table, alphabet, "a(a|bb)*");
+
+        let panic = std::panic::catch_unwind(|| {
+            assert_equivalent_to_regex!(table, alphabet, "a(a|b)*");
+        })
+        .unwrap_err();
+        assert_eq!(
+            panic.downcast_ref::<String>().unwrap(),
+            "expected the table to match the regex \"a(a|b)*\", but \"ab\" is rejected by the \
+             table and accepted by the regex"
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{Alphabet, TransitionTable, STARTING_STATE_ID};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+impl TransitionTable {
+    /// Export the table as a TikZ picture, labeling edges with column indices
+    pub fn to_tikz(&self) -> String {
+        let labels: Vec<String> = (0..self.width()).map(|column| column.to_string()).collect();
+        self.to_tikz_labeled(&labels, &[])
+    }
+
+    /// Export the table as a TikZ picture, labeling edges with the columns' character sets
+    ///
+    /// If any column has a description, a legend box below the picture lists the described
+    /// columns.
+    pub fn to_tikz_with_alphabet(&self, alphabet: &Alphabet) -> String {
+        let labels: Vec<String> = (0..self.width())
+            .map(|column| {
+                if column < alphabet.len() {
+                    alphabet.label(column)
+                } else {
+                    column.to_string()
+                }
+            })
+            .collect();
+        self.to_tikz_labeled(&labels, &alphabet.legend())
+    }
+
+    /// Export the table as a TikZ picture with a label for every column, followed by a legend of
+    /// column labels and descriptions if there are any
+    ///
+    /// The picture uses the `automata` TikZ library, with the states in a row in ID order.
+    /// Transitions between the same pair of states are one edge labeled with all of their symbols,
+    /// and edges going both ways between two states are bent apart.
+    fn to_tikz_labeled(&self, labels: &[String], legend: &[(String, &str)]) -> String {
+        let mut output = String::from("\\begin{tikzpicture}[->, node distance=2.5cm, auto]\n");
+        let mut previous = None;
+        for row in &self.rows {
+            let mut options = vec!["state"];
+            if row.id == STARTING_STATE_ID {
+                options.push("initial");
+            }
+            if row.accepting {
+                options.push("accepting");
+            }
+            let placement = previous
+                .map(|previous| format!(", right of=s{}", previous))
+                .unwrap_or_default();
+            writeln!(
+                output,
+                "  \\node[{}{}] (s{}) {{${}$}};",
+                options.join(", "),
+                placement,
+                row.id,
+                row.id
+            )
+            .unwrap();
+            previous = Some(row.id);
+        }
+
+        let mut edges: BTreeMap<(usize, usize), Vec<String>> = BTreeMap::new();
+        for row in &self.rows {
+            for (symbol, target) in row.transitions.iter().enumerate() {
+                if let Some(target) = target {
+                    edges
+                        .entry((row.id, *target))
+                        .or_default()
+                        .push(format!("\\texttt{{{}}}", escape(&labels[symbol])));
+                }
+            }
+        }
+        if !edges.is_empty() {
+            output.push_str("  \\path");
+            for ((source, target), symbols) in &edges {
+                let bend = if source == target {
+                    " [loop above]"
+                } else if edges.contains_key(&(*target, *source)) {
+                    " [bend left]"
+                } else {
+                    ""
+                };
+                write!(
+                    output,
+                    "\n    (s{}) edge{} node {{{}}} (s{})",
+                    source,
+                    bend,
+                    symbols.join(", "),
+                    target
+                )
+                .unwrap();
+            }
+            output.push_str(";\n");
+        }
+
+        if !legend.is_empty() {
+            let lines: Vec<String> = legend
+                .iter()
+                .map(|(label, description)| {
+                    format!("\\texttt{{{}}}: {}", escape(label), escape(description))
+                })
+                .collect();
+            writeln!(
+                output,
+                "  \\node[draw, align=left, anchor=north west] at ([yshift=-0.5cm]current bounding \
+                 box.south west) {{{}}};",
+                lines.join(" \\\\ ")
+            )
+            .unwrap();
+        }
+
+        output.push_str("\\end{tikzpicture}\n");
+        output
+    }
+}
+
+/// Escape the characters LaTeX treats specially
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '{' | '}' | '$' | '&' | '#' | '_' | '%' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alphabet, TransitionTable};
+
+    #[test]
+    fn to_tikz() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n").unwrap();
+        assert_eq!(
+            table.to_tikz(),
+            "\\begin{tikzpicture}[->, node distance=2.5cm, auto]\n\
+             \x20 \\node[state, initial] (s0) {$0$};\n\
+             \x20 \\node[state, accepting, right of=s0] (s1) {$1$};\n\
+             \x20 \\path\n\
+             \x20   (s0) edge [bend left] node {\\texttt{0}} (s1)\n\
+             \x20   (s1) edge [bend left] node {\\texttt{1}} (s0)\n\
+             \x20   (s1) edge [loop above] node {\\texttt{0}} (s1);\n\
+             \\end{tikzpicture}\n"
+        );
+
+        let alphabet = Alphabet::parse(r#"[0-9]="digit" [_]="under_score""#).unwrap();
+        let tikz = table.to_tikz_with_alphabet(&alphabet);
+        assert!(tikz.contains("node {\\texttt{[\\_]}} (s0)"));
+        assert!(tikz.contains("{\\texttt{[0-9]}: digit \\\\ \\texttt{[\\_]}: under\\_score};"));
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{ParseSerializeError, ScanError, Scanner, TransitionTable};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::Range;
+use std::str::FromStr;
+
+/// The line starting the token kind section of a table
+const KINDS_SECTION: &str = "%kinds";
+
+/// A mapping from accepting state IDs to token kinds
+///
+/// In text, the mapping is a section after a table's rows, starting with a `%kinds` line and
+/// followed by one `state kind` line per mapped state.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenKinds<K> {
+    /// The kind of each mapped state
+    kinds: BTreeMap<usize, K>,
+}
+
+impl<K> Default for TokenKinds<K> {
+    fn default() -> Self {
+        TokenKinds {
+            kinds: BTreeMap::new(),
+        }
+    }
+}
+
+impl<K> FromIterator<(usize, K)> for TokenKinds<K> {
+    fn from_iter<I: IntoIterator<Item = (usize, K)>>(iter: I) -> Self {
+        TokenKinds {
+            kinds: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<K> TokenKinds<K> {
+    /// Create an empty mapping
+    pub fn new() -> Self {
+        TokenKinds::default()
+    }
+
+    /// Map a state to a kind, returning the kind it had before
+    pub fn insert(&mut self, state: usize, kind: K) -> Option<K> {
+        self.kinds.insert(state, kind)
+    }
+
+    /// Get the kind of a state
+    pub fn get(&self, state: usize) -> Option<&K> {
+        self.kinds.get(&state)
+    }
+
+    /// Iterate over the mapped states and their kinds, in order of state ID
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &K)> {
+        self.kinds.iter().map(|(state, kind)| (*state, kind))
+    }
+}
+
+impl<K: Display> TokenKinds<K> {
+    /// Serialize the mapping as a `%kinds` section
+    pub fn serialize(&self) -> String {
+        let mut output = format!("{}\n", KINDS_SECTION);
+        for (state, kind) in &self.kinds {
+            output.push_str(&format!("{} {}\n", state, kind));
+        }
+        output
+    }
+}
+
+/// A token found by [`Scanner::tokens`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token<K> {
+    /// The kind of the accepting state the token ended in
+    pub kind: K,
+
+    /// The byte range of the token in the input
+    pub span: Range<usize>,
+}
+
+impl TransitionTable {
+    /// Parse a table followed by an optional `%kinds` section mapping accepting states to kinds
+    ///
+    /// Fails if a mapped state does not exist or is not accepting.
+    pub fn parse_with_kinds<K>(input: &str) -> Result<(Self, TokenKinds<K>), ParseSerializeError>
+    where
+        K: FromStr,
+        K::Err: Display,
+    {
+        let (table, section) = match input.split_once(&format!("{}\n", KINDS_SECTION)) {
+            Some((table, section)) => (table, section),
+            None => match input.strip_suffix(KINDS_SECTION) {
+                Some(table) => (table, ""),
+                None => (input, ""),
+            },
+        };
+        let table = TransitionTable::parse(table)?;
+
+        let mut kinds = TokenKinds::new();
+        for (line_index, line) in section.lines().enumerate() {
+            let error = |message: String| ParseSerializeError {
+                message: format!("Token kind line {} {}", line_index + 1, message),
+            };
+
+            let (state, kind) = line
+                .trim()
+                .split_once(char::is_whitespace)
+                .ok_or_else(|| error("needs a state and a kind".to_string()))?;
+            let state: usize = state
+                .parse()
+                .map_err(|e| error(format!("has an invalid state ID: {}", e)))?;
+            let kind = kind
+                .trim()
+                .parse()
+                .map_err(|e| error(format!("has an invalid kind: {}", e)))?;
+
+            if !table.row(state).is_some_and(|row| row.accepting) {
+                return Err(error(format!(
+                    "maps state {}, which is not accepting",
+                    state
+                )));
+            }
+            kinds.insert(state, kind);
+        }
+
+        Ok((table, kinds))
+    }
+
+    /// Serialize the table followed by a `%kinds` section
+    pub fn serialize_with_kinds<K: Display>(&self, kinds: &TokenKinds<K>) -> String {
+        self.serialize() + &kinds.serialize()
+    }
+}
+
+impl<'a> Scanner<'a> {
+    /// Turn the lexemes into tokens, using the kinds of each mode's states by mode index
+    ///
+    /// Lexemes ending in a state without a kind, such as whitespace, are skipped.
+    pub fn tokens<K: Clone>(
+        self,
+        kinds: &'a [TokenKinds<K>],
+    ) -> impl Iterator<Item = Result<Token<K>, ScanError>> + 'a {
+        self.filter_map(move |lexeme| match lexeme {
+            Ok(lexeme) => {
+                let kind = kinds.get(lexeme.mode)?.get(lexeme.state)?;
+                Some(Ok(Token {
+                    kind: kind.clone(),
+                    span: lexeme.span,
+                }))
+            }
+            Err(error) => Some(Err(error)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alphabet, CharSet, ModedTable};
+
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    enum Kind {
+        Word,
+        Number,
+    }
+
+    impl FromStr for Kind {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "word" => Ok(Kind::Word),
+                "number" => Ok(Kind::Number),
+                _ => Err(format!("unknown kind {}", s)),
+            }
+        }
+    }
+
+    impl Display for Kind {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                Kind::Word => write!(f, "word"),
+                Kind::Number => write!(f, "number"),
+            }
+        }
+    }
+
+    /// Words, numbers, and spaces
+    const TABLE: &str = "- 0 1 2 3\n+ 1 1 E E\n+ 2 E 2 E\n+ 3 E E 3\n%kinds\n1 word\n2 number\n";
+
+    #[test]
+    fn token_kinds() -> Result<(), ParseSerializeError> {
+        let (table, kinds) = TransitionTable::parse_with_kinds::<Kind>(TABLE)?;
+        assert_eq!(kinds.get(2), Some(&Kind::Number));
+        assert_eq!(kinds.get(3), None);
+        assert_eq!(table.serialize_with_kinds(&kinds), TABLE);
+
+        assert!(TransitionTable::parse_with_kinds::<Kind>("+ 0 E\n%kinds\n0 verb\n").is_err());
+        assert!(TransitionTable::parse_with_kinds::<Kind>("- 0 E\n%kinds\n0 word\n").is_err());
+        assert!(TransitionTable::parse_with_kinds::<Kind>("+ 0 E\n")?
+            .1
+            .iter()
+            .next()
+            .is_none());
+
+        let alphabet = Alphabet::new(vec![
+            CharSet::new([('a', 'z')]),
+            CharSet::new([('0', '9')]),
+            CharSet::single(' '),
+        ]);
+        let mut lexer = ModedTable::new();
+        lexer.add_mode(table, alphabet);
+
+        let tokens = lexer
+            .scan("route 66  east")
+            .tokens(std::slice::from_ref(&kinds))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    kind: Kind::Word,
+                    span: 0..5
+                },
+                Token {
+                    kind: Kind::Number,
+                    span: 6..8
+                },
+                Token {
+                    kind: Kind::Word,
+                    span: 10..14
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
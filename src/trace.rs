@@ -0,0 +1,101 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+
+/// One transition taken while running a table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TraceStep {
+    /// The state the transition leaves
+    pub from: usize,
+
+    /// The column consumed
+    pub symbol: usize,
+
+    /// The state the transition enters, or `None` for an error transition
+    pub to: Option<usize>,
+}
+
+/// A record of every transition taken while running a table on an input
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    /// The steps in order, ending at the first error transition if there is one
+    pub steps: Vec<TraceStep>,
+
+    /// Whether the whole input was consumed and the run ended in an accepting state
+    pub accepted: bool,
+}
+
+impl Trace {
+    /// Get the state after the first `position` steps, or `None` if the run failed before then
+    pub fn state_at(&self, position: usize) -> Option<usize> {
+        match position {
+            0 => Some(STARTING_STATE_ID),
+            _ => self.steps.get(position - 1)?.to,
+        }
+    }
+}
+
+impl TransitionTable {
+    /// Run the table on a sequence of columns, recording every transition taken
+    pub fn trace(&self, input: &[usize]) -> Trace {
+        let mut steps = Vec::new();
+        let mut state = STARTING_STATE_ID;
+
+        for symbol in input {
+            let to = self.next_state(state, *symbol);
+            steps.push(TraceStep {
+                from: state,
+                symbol: *symbol,
+                to,
+            });
+
+            match to {
+                Some(to) => state = to,
+                None => break,
+            }
+        }
+
+        let accepted = steps.len() == input.len()
+            && steps.last().is_none_or(|step| step.to.is_some())
+            && self.row(state).is_some_and(|row| row.accepting);
+
+        Trace { steps, accepted }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseSerializeError;
+
+    #[test]
+    fn trace() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 0 1\n")?;
+
+        let trace = table.trace(&[0, 1]);
+        assert!(trace.accepted);
+        assert_eq!(
+            trace.steps,
+            vec![
+                TraceStep {
+                    from: 0,
+                    symbol: 0,
+                    to: Some(1)
+                },
+                TraceStep {
+                    from: 1,
+                    symbol: 1,
+                    to: Some(1)
+                },
+            ]
+        );
+        assert_eq!(trace.state_at(2), Some(1));
+
+        let trace = table.trace(&[1, 0]);
+        assert!(!trace.accepted);
+        assert_eq!(trace.steps.len(), 1);
+        assert_eq!(trace.state_at(1), None);
+
+        assert!(!table.trace(&[]).accepted);
+
+        Ok(())
+    }
+}
@@ -1,3 +1,6 @@
+use crate::{Alphabet, ParseError, Span};
+use std::collections::BTreeMap;
+
 /// The starting state ID
 pub const STARTING_STATE_ID: usize = 0;
 
@@ -12,6 +15,15 @@ pub struct TransitionTableRow {
 
     /// The row's state transitions
     pub transitions: Vec<Option<usize>>,
+
+    /// The row's documentation, written as `#` comment lines right before the row
+    pub doc: Option<String>,
+
+    /// The row's name, written after its ID
+    ///
+    /// Labels start with a letter or `_`, are at least two characters long so they can't be
+    /// mistaken for `E`, `_`, or a mistyped transition, and can't contain whitespace.
+    pub label: Option<String>,
 }
 
 /// A DFA transition table
@@ -19,9 +31,25 @@ pub struct TransitionTableRow {
 pub struct TransitionTable {
     /// The rows in the table, sorted by state ID
     pub rows: Vec<TransitionTableRow>,
+
+    /// The characters of each column, written as an `alphabet:` header line before the rows in
+    /// the format of [`Alphabet::parse`]
+    pub alphabet: Option<Alphabet>,
+
+    /// Facts about where the table came from, such as its generator, each written as a
+    /// `%meta key value` line before the rows
+    pub metadata: BTreeMap<String, String>,
+
+    /// The comment describing the whole table, written as `#` comment lines followed by a blank
+    /// line at the start
+    pub comment: Option<String>,
 }
 
 /// Errors that can occur when parsing or serializing a transition table
+///
+/// The table and NFA text formats report a [`ParseError`] with a span instead, which converts
+/// into this error. The other formats, such as regular expressions, character sets, alphabet
+/// headers, edge lists, DOT, YAML, JSON, and symbolic automata, only report a message.
 #[derive(Debug, thiserror::Error)]
 pub struct ParseSerializeError {
     /// The error message
@@ -35,130 +63,791 @@ impl std::fmt::Display for ParseSerializeError {
 }
 
 /// The symbol for an error transition
-const ERROR_SYMBOL: &str = "E";
+pub(crate) const ERROR_SYMBOL: &str = "E";
+
+/// The symbol for a transition to the row's fallback target
+const FALLBACK_SYMBOL: &str = "_";
+
+/// The symbol for a transition from a state to itself
+const SELF_LOOP_SYMBOL: &str = ".";
+
+/// The separator between a row's transitions and its fallback target
+const FALLBACK_SEPARATOR: char = '|';
+
+/// The prefix of a line documenting the row after it
+const DOC_PREFIX: char = '#';
+
+/// The prefix of the header line giving the characters of each column
+const ALPHABET_PREFIX: &str = "alphabet:";
+
+/// The directive starting a metadata line
+const META_DIRECTIVE: &str = "%meta";
+
+/// Options for [`TransitionTable::serialize_with`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SerializeOptions {
+    /// Give each row with a repeated target a fallback, written as `| target` after the
+    /// transitions, and write transitions to it as `_`
+    pub fallbacks: bool,
+
+    /// Write transitions from a state to itself as `.`
+    pub self_loops: bool,
+}
+
+/// Options for [`TransitionTable::parse_with`], for files following other conventions
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// The symbol for an error transition, `E` by default
+    ///
+    /// The symbol and the markers can't be `_`, `.`, state IDs, or contain `|`, start with `#`,
+    /// or contain whitespace, since those already mean something in the format.
+    pub error_symbol: String,
+
+    /// The first columns marking a row as accepting, `+` by default
+    pub accepting_markers: Vec<String>,
+
+    /// The first columns marking a row as not accepting, `-` by default
+    pub rejecting_markers: Vec<String>,
+
+    /// Allow rows to have fewer transitions than others, filling in error transitions up to the
+    /// widest row
+    pub ragged: bool,
+
+    /// Require the state IDs to count up from 0 without gaps
+    pub contiguous: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions {
+            error_symbol: ERROR_SYMBOL.to_string(),
+            accepting_markers: vec!["+".to_string()],
+            rejecting_markers: vec!["-".to_string()],
+            ragged: false,
+            contiguous: false,
+        }
+    }
+}
+
+impl ParseOptions {
+    /// Get the first symbol or marker that collides with the table format, if there is one
+    fn reserved_symbol(&self) -> Option<&str> {
+        std::iter::once(&self.error_symbol)
+            .chain(&self.accepting_markers)
+            .chain(&self.rejecting_markers)
+            .map(String::as_str)
+            .find(|symbol| {
+                symbol.is_empty()
+                    || *symbol == FALLBACK_SYMBOL
+                    || *symbol == SELF_LOOP_SYMBOL
+                    || symbol.parse::<usize>().is_ok()
+                    || symbol.contains(FALLBACK_SEPARATOR)
+                    || symbol.starts_with(DOC_PREFIX)
+                    || symbol.contains(char::is_whitespace)
+            })
+    }
+}
+
+impl TransitionTableRow {
+    /// Get the row's ID followed by its label, like `4 (accept_ident)`, for messages
+    pub fn name(&self) -> String {
+        match &self.label {
+            Some(label) => format!("{} ({})", self.id, label),
+            None => self.id.to_string(),
+        }
+    }
+}
 
 impl TransitionTable {
     /// Parse a transition table from a string
-    pub fn parse(input: &str) -> Result<Self, ParseSerializeError> {
-        let mut table = TransitionTable { rows: Vec::new() };
+    pub fn parse(input: &str) -> Result<Self, ParseError> {
+        TransitionTable::parse_with(input, &ParseOptions::default())
+    }
+
+    /// Parse a transition table from a string, with the conventions set in `options`
+    pub fn parse_with(input: &str, options: &ParseOptions) -> Result<Self, ParseError> {
+        TransitionTable::parse_all_errors_with(input, options)
+            .map_err(|mut errors| errors.swap_remove(0))
+    }
+
+    /// Parse a transition table from a string, reporting every problem instead of only the first
+    ///
+    /// Checking continues past problems, which suits editors that show every diagnostic at once.
+    /// The errors are sorted by position. A line with the wrong number of columns is reported
+    /// once, without checking its cells.
+    ///
+    /// Lines starting with `#` document the row right after them, and become its
+    /// [`TransitionTableRow::doc`], one line per line with the `#` and a space after it removed.
+    /// Blank lines are skipped, and comment lines followed by a blank line are plain comments
+    /// instead, of which the first before every row becomes [`TransitionTable::comment`].
+    /// An optional header line before the rows, such as `alphabet: [a-z] [0-9]="digit" *`, gives
+    /// the characters of every column in the format of [`Alphabet::parse`] and becomes
+    /// [`TransitionTable::alphabet`]. Lines like `%meta generator lexgen`
+    /// anywhere in the input add to [`TransitionTable::metadata`], with later lines replacing the
+    /// values of earlier ones. A word after a row's ID, as in `+ 4 accept_ident E E`, becomes its
+    /// [`TransitionTableRow::label`] and isn't counted as a column.
+    pub fn parse_all_errors(input: &str) -> Result<Self, Vec<ParseError>> {
+        TransitionTable::parse_all_errors_with(input, &ParseOptions::default())
+    }
+
+    /// Parse a transition table from a string like [`TransitionTable::parse_all_errors`], with
+    /// the conventions set in `options`
+    pub fn parse_all_errors_with(
+        input: &str,
+        options: &ParseOptions,
+    ) -> Result<Self, Vec<ParseError>> {
+        if let Some(symbol) = options.reserved_symbol() {
+            return Err(vec![ParseError::ReservedSymbol {
+                span: Span {
+                    line: 1,
+                    column: 1,
+                    length: 0,
+                },
+                symbol: symbol.to_string(),
+            }]);
+        }
+
+        let mut rows = Vec::new();
+        let mut errors = Vec::new();
         let mut expected_columns: Option<usize> = None;
+        let mut doc = Vec::new();
+        let mut alphabet: Option<(Alphabet, usize)> = None;
+        let mut metadata = BTreeMap::new();
+        let mut comment = None;
 
         // Split the input into lines
         for (line_index, line) in input.lines().enumerate() {
-            let mut row = TransitionTableRow {
-                accepting: false,
-                id: 0,
-                transitions: Vec::new(),
-            };
-
-            // Split the line into columns
-            let columns = line.split_whitespace().collect::<Vec<_>>();
-
-            // Check that there are at least two columns
-            if columns.len() < 2 {
-                return Err(ParseSerializeError {
-                    message: format!("Line {} has too few columns", line_index + 1),
-                });
+            if let Some(text) = doc_line(line) {
+                doc.push(text);
+                continue;
             }
 
-            // Check that the number of columns is consistent
-            match expected_columns {
-                Some(expected) => {
-                    if expected != columns.len() {
-                        return Err(ParseSerializeError {
-                            message: format!(
-                                "Line {} has a different number of columns than the previous lines",
-                                line_index + 1
-                            ),
-                        });
-                    }
-                }
-                None => {
-                    expected_columns = Some(columns.len());
+            // A blank line detaches the comment lines before it from the next row, making the
+            // first such comment before any row the table's comment
+            if line.trim().is_empty() {
+                if !doc.is_empty() && expected_columns.is_none() && comment.is_none() {
+                    comment = Some(doc.join("\n"));
                 }
+                doc.clear();
+                continue;
             }
-
-            // Parse accepting state column
-            match columns[0].chars().next().unwrap() {
-                '+' => {
-                    row.accepting = true;
-                }
-                '-' => {
-                    row.accepting = false;
+            if let Some(entry) = meta_line(line) {
+                match entry {
+                    Some((key, value)) => {
+                        metadata.insert(key.to_string(), value.to_string());
+                    }
+                    None => errors.push(ParseError::InvalidMeta {
+                        span: line_span(line, line_index),
+                    }),
                 }
-                _ => {
-                    return Err(ParseSerializeError {
-                        message: format!("Line {} has an invalid accepting state", line_index + 1),
+                continue;
+            }
+            if let Some(header) = alphabet_line(line) {
+                if alphabet.is_some() || expected_columns.is_some() {
+                    errors.push(ParseError::MisplacedAlphabet {
+                        span: line_span(line, line_index),
                     });
+                } else {
+                    match Alphabet::parse(header) {
+                        Ok(parsed) => alphabet = Some((parsed, line_index)),
+                        Err(error) => errors.push(ParseError::InvalidAlphabet {
+                            span: line_span(line, line_index),
+                            message: error.message,
+                        }),
+                    }
                 }
+                continue;
             }
+            let row = parse_row_collecting(
+                line,
+                line_index,
+                options,
+                &mut expected_columns,
+                &mut errors,
+            );
 
-            // Parse the ID column
-            row.id = columns[1].parse().map_err(|e| ParseSerializeError {
-                message: format!("Line {} has an invalid state ID: {}", line_index + 1, e),
-            })?;
+            // Add the row to the table with the lines before it, remembering its line
+            let row_doc = (!doc.is_empty()).then(|| doc.join("\n"));
+            doc.clear();
+            if let Some((row, padding)) = row {
+                rows.push((
+                    TransitionTableRow {
+                        doc: row_doc,
+                        ..row
+                    },
+                    line_index,
+                    padding,
+                ));
+            }
+        }
 
-            // Parse the transitions
-            for (column_index, column) in columns.iter().skip(2).enumerate() {
-                // Parse the transition
-                if *column == ERROR_SYMBOL {
-                    row.transitions.push(None);
-                } else {
-                    row.transitions
-                        .push(Some(column.parse().map_err(|e| ParseSerializeError {
-                            message: format!(
-                                "Line {} column {} has an invalid transition: {}",
-                                line_index + 1,
-                                column_index + 3,
-                                e
-                            ),
-                        })?))
+        // Check that the header names every column
+        if let (Some((header, line_index)), Some(columns)) = (&alphabet, expected_columns) {
+            if header.len() != columns - 2 {
+                let line = input.lines().nth(*line_index).unwrap();
+                errors.push(ParseError::AlphabetLength {
+                    span: line_span(line, *line_index),
+                    expected: columns - 2,
+                    found: header.len(),
+                });
+            }
+        }
+
+        // Fill in the transitions missing from ragged rows
+        let columns = expected_columns.map_or(0, |columns| columns - 2);
+        let mut rows: Vec<_> = rows
+            .into_iter()
+            .map(|(mut row, line_index, padding)| {
+                row.transitions.resize(columns, padding);
+                (row, line_index)
+            })
+            .collect();
+
+        // Sort the rows by state ID, which keeps repeated IDs in line order
+        rows.sort_by_key(|(row, _)| row.id);
+        let id_span = |line_index: usize| {
+            let line = input.lines().nth(line_index).unwrap();
+            let (column, id) = cells(line, 1)[1];
+            Span {
+                line: line_index + 1,
+                column,
+                length: id.chars().count(),
+            }
+        };
+        for pair in rows.windows(2).filter(|pair| pair[0].0.id == pair[1].0.id) {
+            let (row, line_index) = &pair[1];
+
+            // Point every repeat at the first row with the ID
+            let first = rows.iter().find(|(first, _)| first.id == row.id).unwrap();
+            errors.push(ParseError::DuplicateStateId {
+                span: id_span(*line_index),
+                id: row.id,
+                first_line: first.1 + 1,
+            });
+        }
+
+        // Check that no ID is skipped, leaving repeats to the check above
+        if options.contiguous {
+            let mut next = STARTING_STATE_ID;
+            for (row, line_index) in &rows {
+                if row.id > next {
+                    errors.push(ParseError::NonContiguousId {
+                        span: id_span(*line_index),
+                        id: row.id,
+                        expected: next,
+                    });
+                    break;
                 }
+                next = row.id + 1;
             }
+        }
 
-            // Add the row to the table
-            table.rows.push(row);
+        if !errors.is_empty() {
+            errors.sort_by_key(|error| {
+                let span = error.span();
+                (span.line, span.column)
+            });
+            return Err(errors);
         }
 
-        // Sort the rows by state ID
-        table.rows.sort_by_key(|row| row.id);
+        Ok(TransitionTable {
+            rows: rows.into_iter().map(|(row, _)| row).collect(),
+            alphabet: alphabet.map(|(header, _)| header),
+            metadata,
+            comment,
+        })
+    }
+
+    /// Get the row with the given state ID, if it exists
+    pub fn row(&self, id: usize) -> Option<&TransitionTableRow> {
+        self.index_of(id).map(|index| &self.rows[index])
+    }
+
+    /// Get the index in `rows` of the row with the given state ID, if it exists
+    pub(crate) fn index_of(&self, id: usize) -> Option<usize> {
+        self.rows.binary_search_by_key(&id, |row| row.id).ok()
+    }
+
+    /// Get the [`TransitionTableRow::name`] of a state, or just its ID if it doesn't exist
+    pub(crate) fn state_name(&self, id: usize) -> String {
+        self.row(id)
+            .map_or_else(|| id.to_string(), TransitionTableRow::name)
+    }
 
-        Ok(table)
+    /// Get the state reached from `state` on the given column
+    ///
+    /// Returns `None` for error transitions, and when the state, column, or target state does not
+    /// exist.
+    pub fn next_state(&self, state: usize, symbol: usize) -> Option<usize> {
+        let target = (*self.row(state)?.transitions.get(symbol)?)?;
+        self.row(target).map(|row| row.id)
     }
 
     /// Serialize the transition table to a string
-    pub fn serialize(&self) -> Result<String, ParseSerializeError> {
+    pub fn serialize(&self) -> String {
+        self.serialize_with(&SerializeOptions::default())
+    }
+
+    /// Serialize the transition table to a string, using the shorthands enabled in `options`
+    pub fn serialize_with(&self, options: &SerializeOptions) -> String {
         let mut output = String::new();
+        serialize_header(self, &mut output);
 
         for row in &self.rows {
-            // Write the accepting state
-            output.push(if row.accepting { '+' } else { '-' });
-            output.push(' ');
+            serialize_row(row, options, &mut output);
+        }
 
-            // Write the state ID
-            output.push_str(&row.id.to_string());
+        output
+    }
 
-            // Write the transitions
-            for transition in &row.transitions {
-                match transition {
-                    Some(state) => {
-                        output.push(' ');
-                        output.push_str(&state.to_string());
-                    }
-                    None => {
-                        output.push(' ');
-                        output.push_str(ERROR_SYMBOL);
-                    }
+    /// Serialize only the states with the given IDs
+    ///
+    /// If `include_targets` is set, every state reachable from the selected states is included as
+    /// well so the output is a self-contained table. Otherwise, a selected state that transitions
+    /// to a state outside the selection is an error.
+    pub fn serialize_states(
+        &self,
+        ids: &[usize],
+        include_targets: bool,
+    ) -> Result<String, ParseSerializeError> {
+        let mut selected = std::collections::BTreeSet::new();
+        let mut pending = ids.to_vec();
+
+        while let Some(id) = pending.pop() {
+            if !selected.insert(id) {
+                continue;
+            }
+
+            // Check that the state exists
+            let row = self.row(id).ok_or_else(|| ParseSerializeError {
+                message: format!("State {} does not exist", id),
+            })?;
+
+            // Check or follow the state's transitions
+            for target in row.transitions.iter().flatten() {
+                if include_targets {
+                    pending.push(*target);
+                } else if !ids.contains(target) {
+                    return Err(ParseSerializeError {
+                        message: format!(
+                            "State {} transitions to state {}, which is not selected",
+                            row.name(),
+                            self.state_name(*target)
+                        ),
+                    });
                 }
             }
+        }
+
+        let mut output = String::new();
+        serialize_header(self, &mut output);
 
-            output.push('\n');
+        // The set is ordered, so the rows are written sorted by state ID
+        for id in selected {
+            serialize_row(
+                self.row(id).unwrap(),
+                &SerializeOptions::default(),
+                &mut output,
+            );
         }
 
         Ok(output)
     }
 }
 
+impl std::str::FromStr for TransitionTable {
+    type Err = ParseError;
+
+    /// Parse a transition table like [`TransitionTable::parse`]
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        TransitionTable::parse(input)
+    }
+}
+
+impl std::fmt::Display for TransitionTable {
+    /// Write the table like [`TransitionTable::serialize`]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.serialize())
+    }
+}
+
+/// Parse a single row, checking that it has `expected_columns` columns if that is set
+///
+/// Sets `expected_columns` from the row if it isn't set yet. The columns don't include the
+/// row's fallback target, which may follow a `|`.
+pub(crate) fn parse_row(
+    line: &str,
+    line_index: usize,
+    expected_columns: &mut Option<usize>,
+) -> Result<TransitionTableRow, ParseError> {
+    let mut errors = Vec::new();
+    parse_row_collecting(
+        line,
+        line_index,
+        &ParseOptions::default(),
+        expected_columns,
+        &mut errors,
+    )
+    .map(|(row, _)| row)
+    .ok_or_else(|| errors.swap_remove(0))
+}
+
+/// Parse a single row like [`parse_row`], adding every problem with it to `errors`
+///
+/// Returns the row with the transition its missing columns take if it is ragged, which is its
+/// fallback target or else an error transition, or `None` if there were any problems. Problems
+/// with individual cells don't stop the rest of the row being checked, but a row with the wrong
+/// number of columns isn't checked further. Ragged rows instead widen `expected_columns` to the
+/// widest row so far.
+fn parse_row_collecting(
+    line: &str,
+    line_index: usize,
+    options: &ParseOptions,
+    expected_columns: &mut Option<usize>,
+    errors: &mut Vec<ParseError>,
+) -> Option<(TransitionTableRow, Option<usize>)> {
+    let errors_before = errors.len();
+    let mut row = TransitionTableRow {
+        accepting: false,
+        id: 0,
+        transitions: Vec::new(),
+        doc: None,
+        label: None,
+    };
+    let span = |column: usize, cell: &str| Span {
+        line: line_index + 1,
+        column,
+        length: cell.chars().count(),
+    };
+    let line_span = span(1, line);
+
+    // Split off the fallback target, then split the rest into columns
+    let (columns_part, fallback) = match line.split_once(FALLBACK_SEPARATOR) {
+        Some((columns_part, fallback)) => (columns_part, Some(fallback)),
+        None => (line, None),
+    };
+    let mut columns = cells(columns_part, 1);
+
+    // Take out the label after the ID, so it isn't counted as a column
+    if let Some((_, cell)) = columns
+        .get(2)
+        .filter(|(_, cell)| is_label(cell) && *cell != options.error_symbol)
+    {
+        row.label = Some(cell.to_string());
+        columns.remove(2);
+    }
+
+    // Check that there are at least two columns
+    if columns.len() < 2 {
+        errors.push(ParseError::TooFewColumns { span: line_span });
+        return None;
+    }
+
+    // Check that the number of columns is consistent
+    match expected_columns {
+        Some(expected) if options.ragged => {
+            *expected = (*expected).max(columns.len());
+        }
+        Some(expected) => {
+            if *expected != columns.len() {
+                errors.push(ParseError::InconsistentColumnCount {
+                    span: line_span,
+                    expected: *expected,
+                    found: columns.len(),
+                });
+                return None;
+            }
+        }
+        None => {
+            *expected_columns = Some(columns.len());
+        }
+    }
+
+    // Parse accepting state column
+    let (column, marker) = columns[0];
+    if options
+        .accepting_markers
+        .iter()
+        .any(|accepting| accepting == marker)
+    {
+        row.accepting = true;
+    } else if !options
+        .rejecting_markers
+        .iter()
+        .any(|rejecting| rejecting == marker)
+    {
+        errors.push(ParseError::InvalidAcceptingMarker {
+            span: span(column, marker),
+        });
+    }
+
+    // Parse the ID column
+    let (column, id) = columns[1];
+    match id.parse() {
+        Ok(id) => row.id = id,
+        Err(source) => errors.push(ParseError::InvalidStateId {
+            span: span(column, id),
+            source,
+        }),
+    }
+
+    // Parse the fallback target, which starts after the separator, reporting it last since it is
+    // the rightmost cell
+    let mut extra_cells = None;
+    let fallback = fallback.map(|fallback| {
+        let offset = columns_part.chars().count() + 2;
+        let fallback_cells = cells(fallback, offset);
+        if let (Some((first, _)), Some((last, cell))) =
+            (fallback_cells.get(1), fallback_cells.last())
+        {
+            extra_cells = Some(Span {
+                line: line_index + 1,
+                column: *first,
+                length: last + cell.chars().count() - first,
+            });
+        }
+        let (column, cell) = fallback_cells.first().copied().unwrap_or((offset, ""));
+        parse_transition(cell, row.id, &options.error_symbol).map_err(|source| {
+            ParseError::InvalidFallback {
+                span: span(column, cell),
+                source,
+            }
+        })
+    });
+
+    // Parse the transitions
+    for (column, cell) in columns.iter().skip(2).copied() {
+        // Parse the transition
+        let transition = if cell == FALLBACK_SYMBOL {
+            match &fallback {
+                Some(Ok(fallback)) => Ok(*fallback),
+                // The invalid fallback is reported below
+                Some(Err(_)) => continue,
+                None => Err(ParseError::MissingFallback {
+                    span: span(column, cell),
+                }),
+            }
+        } else {
+            parse_transition(cell, row.id, &options.error_symbol).map_err(|source| {
+                ParseError::InvalidTransition {
+                    span: span(column, cell),
+                    source,
+                }
+            })
+        };
+
+        match transition {
+            Ok(transition) => row.transitions.push(transition),
+            Err(error) => errors.push(error),
+        }
+    }
+    let padding = match fallback {
+        Some(Ok(fallback)) => fallback,
+        Some(Err(error)) => {
+            errors.push(error);
+            None
+        }
+        None => None,
+    };
+    if let Some(span) = extra_cells {
+        errors.push(ParseError::ExtraFallbackCells { span });
+    }
+
+    (errors.len() == errors_before).then_some((row, padding))
+}
+
+/// Check whether a cell after a row's ID is a label rather than a transition
+pub(crate) fn is_label(cell: &str) -> bool {
+    cell.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && cell.chars().nth(1).is_some()
+        && !cell.contains(char::is_whitespace)
+}
+
+/// Get the cells of an `alphabet:` header line
+pub(crate) fn alphabet_line(line: &str) -> Option<&str> {
+    line.trim_start().strip_prefix(ALPHABET_PREFIX)
+}
+
+/// Get the key and value of a `%meta` line, or `None` inside if it has no key
+pub(crate) fn meta_line(line: &str) -> Option<Option<(&str, &str)>> {
+    let rest = line.trim_start().strip_prefix(META_DIRECTIVE)?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let rest = rest.trim();
+    Some(match rest.split_once(char::is_whitespace) {
+        Some((key, value)) => Some((key, value.trim_start())),
+        None => (!rest.is_empty()).then_some((rest, "")),
+    })
+}
+
+/// Get the span of a whole line
+pub(crate) fn line_span(line: &str, line_index: usize) -> Span {
+    Span {
+        line: line_index + 1,
+        column: 1,
+        length: line.chars().count(),
+    }
+}
+
+/// Get the text of a line documenting the next row, without its `#` and the space after it
+pub(crate) fn doc_line(line: &str) -> Option<&str> {
+    let text = line.trim_start().strip_prefix(DOC_PREFIX)?;
+    Some(text.strip_prefix(' ').unwrap_or(text))
+}
+
+/// Split a line into its whitespace-separated cells, with the column each starts at when the
+/// line starts at column `first_column`
+pub(crate) fn cells(line: &str, first_column: usize) -> Vec<(usize, &str)> {
+    let mut cells = Vec::new();
+    let mut start = None;
+    for (index, (offset, c)) in line.char_indices().enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((first_column + index, offset)),
+            (Some((column, begin)), true) => {
+                cells.push((column, &line[begin..offset]));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some((column, begin)) = start {
+        cells.push((column, &line[begin..]));
+    }
+    cells
+}
+
+/// Parse a transition from the state `id`, which is a state ID, an error transition written as
+/// `error_symbol`, or a self-loop
+pub(crate) fn parse_transition(
+    cell: &str,
+    id: usize,
+    error_symbol: &str,
+) -> Result<Option<usize>, std::num::ParseIntError> {
+    if cell == error_symbol {
+        return Ok(None);
+    }
+    match cell {
+        SELF_LOOP_SYMBOL => Ok(Some(id)),
+        _ => cell.parse().map(Some),
+    }
+}
+
+/// Choose the fallback target for a row, which is its most common target if it is repeated
+///
+/// Ties go to the smaller state ID. Self-loops are left out if they are written as `.` anyway.
+fn choose_fallback(row: &TransitionTableRow, options: &SerializeOptions) -> Option<usize> {
+    let mut counts = std::collections::BTreeMap::new();
+    for target in row.transitions.iter().flatten() {
+        if options.self_loops && *target == row.id {
+            continue;
+        }
+        *counts.entry(*target).or_insert(0) += 1;
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .max_by_key(|(target, count)| (*count, std::cmp::Reverse(*target)))
+        .map(|(target, _)| target)
+}
+
+/// Serialize the comment, the `%meta` lines, and the `alphabet:` header line, if the table has
+/// them, including their trailing newlines
+fn serialize_header(table: &TransitionTable, output: &mut String) {
+    if let Some(comment) = &table.comment {
+        serialize_comment(comment, output);
+        output.push('\n');
+    }
+    for (key, value) in &table.metadata {
+        output.push_str(META_DIRECTIVE);
+        output.push(' ');
+        output.push_str(key);
+        if !value.is_empty() {
+            output.push(' ');
+            output.push_str(value);
+        }
+        output.push('\n');
+    }
+    if let Some(alphabet) = &table.alphabet {
+        output.push_str(ALPHABET_PREFIX);
+        output.push(' ');
+        output.push_str(&alphabet.serialize());
+        output.push('\n');
+    }
+}
+
+/// Serialize text as `#` comment lines
+fn serialize_comment(text: &str, output: &mut String) {
+    for line in text.lines() {
+        output.push(DOC_PREFIX);
+        if !line.is_empty() {
+            output.push(' ');
+            output.push_str(line);
+        }
+        output.push('\n');
+    }
+}
+
+/// Serialize a single row, including the trailing newline
+fn serialize_row(row: &TransitionTableRow, options: &SerializeOptions, output: &mut String) {
+    let fallback = if options.fallbacks {
+        choose_fallback(row, options)
+    } else {
+        None
+    };
+
+    // Write the documentation
+    if let Some(doc) = &row.doc {
+        serialize_comment(doc, output);
+    }
+
+    // Write the accepting state
+    output.push(if row.accepting { '+' } else { '-' });
+    output.push(' ');
+
+    // Write the state ID
+    output.push_str(&row.id.to_string());
+
+    // Write the label
+    if let Some(label) = &row.label {
+        output.push(' ');
+        output.push_str(label);
+    }
+
+    // Write the transitions
+    for transition in &row.transitions {
+        match transition {
+            Some(state) if options.self_loops && *state == row.id => {
+                output.push(' ');
+                output.push_str(SELF_LOOP_SYMBOL);
+            }
+            Some(state) if fallback == Some(*state) => {
+                output.push(' ');
+                output.push_str(FALLBACK_SYMBOL);
+            }
+            Some(state) => {
+                output.push(' ');
+                output.push_str(&state.to_string());
+            }
+            None => {
+                output.push(' ');
+                output.push_str(ERROR_SYMBOL);
+            }
+        }
+    }
+
+    // Write the fallback target
+    if let Some(fallback) = fallback {
+        output.push(' ');
+        output.push(FALLBACK_SEPARATOR);
+        output.push(' ');
+        output.push_str(&fallback.to_string());
+    }
+
+    output.push('\n');
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -177,7 +866,7 @@ mod tests {
 
         assert_eq!(table.rows.len(), 5);
 
-        assert_eq!(table.rows[0].accepting, false);
+        assert!(!table.rows[0].accepting);
         assert_eq!(table.rows[0].id, 0);
         assert_eq!(table.rows[0].transitions.len(), 5);
         assert_eq!(table.rows[0].transitions[0], Some(1));
@@ -186,7 +875,7 @@ mod tests {
         assert_eq!(table.rows[0].transitions[3], None);
         assert_eq!(table.rows[0].transitions[4], None);
 
-        assert_eq!(table.rows[1].accepting, false);
+        assert!(!table.rows[1].accepting);
         assert_eq!(table.rows[1].id, 1);
         assert_eq!(table.rows[1].transitions.len(), 5);
         assert_eq!(table.rows[1].transitions[0], None);
@@ -195,7 +884,7 @@ mod tests {
         assert_eq!(table.rows[1].transitions[3], None);
         assert_eq!(table.rows[1].transitions[4], None);
 
-        assert_eq!(table.rows[2].accepting, false);
+        assert!(!table.rows[2].accepting);
         assert_eq!(table.rows[2].id, 2);
         assert_eq!(table.rows[2].transitions.len(), 5);
         assert_eq!(table.rows[2].transitions[0], Some(2));
@@ -204,7 +893,7 @@ mod tests {
         assert_eq!(table.rows[2].transitions[3], Some(2));
         assert_eq!(table.rows[2].transitions[4], Some(2));
 
-        assert_eq!(table.rows[3].accepting, false);
+        assert!(!table.rows[3].accepting);
         assert_eq!(table.rows[3].id, 3);
         assert_eq!(table.rows[3].transitions.len(), 5);
         assert_eq!(table.rows[3].transitions[0], Some(4));
@@ -213,7 +902,7 @@ mod tests {
         assert_eq!(table.rows[3].transitions[3], Some(2));
         assert_eq!(table.rows[3].transitions[4], Some(2));
 
-        assert_eq!(table.rows[4].accepting, true);
+        assert!(table.rows[4].accepting);
         assert_eq!(table.rows[4].id, 4);
         assert_eq!(table.rows[4].transitions.len(), 5);
         assert_eq!(table.rows[4].transitions[0], None);
@@ -233,34 +922,328 @@ mod tests {
                     accepting: false,
                     id: 0,
                     transitions: vec![Some(1), None, None, None, None],
+                    doc: None,
+                    label: None,
                 },
                 TransitionTableRow {
                     accepting: false,
                     id: 1,
                     transitions: vec![None, Some(2), None, None, None],
+                    doc: None,
+                    label: None,
                 },
                 TransitionTableRow {
                     accepting: false,
                     id: 2,
                     transitions: vec![Some(2), Some(3), Some(2), Some(2), Some(2)],
+                    doc: None,
+                    label: None,
                 },
                 TransitionTableRow {
                     accepting: false,
                     id: 3,
                     transitions: vec![Some(4), Some(3), Some(2), Some(2), Some(2)],
+                    doc: None,
+                    label: None,
                 },
                 TransitionTableRow {
                     accepting: true,
                     id: 4,
                     transitions: vec![None, None, None, None, None],
+                    doc: None,
+                    label: None,
                 },
             ],
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
         };
 
-        let output = input.serialize()?;
+        let output = input.serialize();
 
         assert_eq!(output, PROVIDED_TRANSITION_TABLE);
 
         Ok(())
     }
+
+    #[test]
+    fn transition_table_from_str_and_display() -> Result<(), ParseError> {
+        let table: TransitionTable = PROVIDED_TRANSITION_TABLE.parse()?;
+        assert_eq!(table, TransitionTable::parse(PROVIDED_TRANSITION_TABLE)?);
+        assert_eq!(format!("{}", table), PROVIDED_TRANSITION_TABLE);
+        assert!("- 0 x\n".parse::<TransitionTable>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_next_state() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 E 2\n+ 1 1 0 E\n")?;
+
+        assert_eq!(table.next_state(0, 0), Some(1));
+        assert_eq!(table.next_state(0, 1), None);
+        assert_eq!(table.next_state(0, 2), None);
+        assert_eq!(table.next_state(0, 3), None);
+        assert_eq!(table.next_state(1, 1), Some(0));
+        assert_eq!(table.next_state(2, 0), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_serialize_states() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse(PROVIDED_TRANSITION_TABLE)?;
+
+        assert_eq!(table.serialize_states(&[4], false)?, "+ 4 E E E E E\n");
+        assert!(table.serialize_states(&[3], false).is_err());
+        assert!(table.serialize_states(&[7], true).is_err());
+        assert_eq!(
+            table.serialize_states(&[3], true)?,
+            "- 2 2 3 2 2 2\n- 3 4 3 2 2 2\n+ 4 E E E E E\n"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_fallbacks() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 1 _ _ | E\n- 1 E 2 _ |2\n")?;
+        assert_eq!(table, TransitionTable::parse("- 0 1 E E\n- 1 E 2 2\n")?);
+        assert!(TransitionTable::parse("- 0 1 _\n").is_err());
+        assert!(TransitionTable::parse("- 0 1 _ | x\n").is_err());
+
+        let table = TransitionTable::parse(PROVIDED_TRANSITION_TABLE)?;
+        let options = SerializeOptions {
+            fallbacks: true,
+            ..Default::default()
+        };
+        let serialized = table.serialize_with(&options);
+        assert_eq!(
+            serialized,
+            "- 0 1 E E E E\n- 1 E 2 E E E\n- 2 _ 3 _ _ _ | 2\n- 3 4 3 _ _ _ | 2\n+ 4 E E E E E\n"
+        );
+        assert_eq!(TransitionTable::parse(&serialized)?, table);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_self_loops() -> Result<(), ParseSerializeError> {
+        let table = TransitionTable::parse("- 0 . 1 | .\n+ 1 0 . | E\n")?;
+        assert_eq!(table, TransitionTable::parse("- 0 0 1\n+ 1 0 1\n")?);
+
+        let table = TransitionTable::parse(PROVIDED_TRANSITION_TABLE)?;
+        let options = SerializeOptions {
+            self_loops: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            table.serialize_with(&options),
+            "- 0 1 E E E E\n- 1 E 2 E E E\n- 2 . 3 . . .\n- 3 4 . 2 2 2\n+ 4 E E E E E\n"
+        );
+
+        let options = SerializeOptions {
+            fallbacks: true,
+            self_loops: true,
+        };
+        let serialized = table.serialize_with(&options);
+        assert_eq!(
+            serialized,
+            "- 0 1 E E E E\n- 1 E 2 E E E\n- 2 . 3 . . .\n- 3 4 . _ _ _ | 2\n+ 4 E E E E E\n"
+        );
+        assert_eq!(TransitionTable::parse(&serialized)?, table);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_docs() -> Result<(), ParseSerializeError> {
+        let input = "# The start\n- 0 1 2\n  #Reached on 0\n#\n# and accepting\n+ 1 E E\n- 2 E E\n";
+        let table = TransitionTable::parse(input)?;
+        assert_eq!(table.rows[0].doc.as_deref(), Some("The start"));
+        assert_eq!(
+            table.rows[1].doc.as_deref(),
+            Some("Reached on 0\n\nand accepting")
+        );
+        assert_eq!(table.rows[2].doc, None);
+        assert_eq!(
+            table.serialize(),
+            "# The start\n- 0 1 2\n# Reached on 0\n#\n# and accepting\n+ 1 E E\n- 2 E E\n"
+        );
+
+        // Documentation follows its state through trimming and renumbering
+        let trimmed = table.trim_with(crate::Renumbering::DepthFirst);
+        assert_eq!(
+            trimmed.serialize(),
+            "# The start\n- 0 1 E\n# Reached on 0\n#\n# and accepting\n+ 1 E E\n"
+        );
+        let (sub, _) = table.renumber_reachable(1);
+        assert_eq!(sub.rows[0].doc, table.rows[1].doc);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_alphabet() -> Result<(), ParseSerializeError> {
+        let input = "alphabet: [a] [b] *\n- 0 1 E 0\n+ 1 1 1 E\n";
+        let table = TransitionTable::parse(input)?;
+        let alphabet = table.alphabet.as_ref().unwrap();
+        assert_eq!(alphabet.classify('b'), Some(1));
+        assert_eq!(alphabet.default_column(), Some(2));
+        assert_eq!(table.serialize(), input);
+        assert!(table.to_dot().contains("0 -> 0 [label=\"*\"];"));
+        assert!(table.accepts_chars("zzab"));
+        assert!(!table.accepts_chars("b"));
+
+        // The header is in the alphabet format, so it can describe columns
+        let input = "alphabet: [0-9]=\"digit\" [.]\n- 0 1 E\n+ 1 1 E\n";
+        let table = TransitionTable::parse(input)?;
+        assert_eq!(table.serialize(), input);
+        assert!(table
+            .to_dot()
+            .contains("legend [shape=note, label=\"[0-9]: digit\\l\"];"));
+        assert!(table.accepts_chars("42"));
+
+        // The header keeps its symbols through transformations that keep the columns
+        assert_eq!(table.minimize().alphabet, table.alphabet);
+
+        assert!(matches!(
+            TransitionTable::parse("alphabet: [a]\n- 0 E E\n"),
+            Err(ParseError::AlphabetLength {
+                expected: 2,
+                found: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            TransitionTable::parse("- 0 E\nalphabet: [a]\n"),
+            Err(ParseError::MisplacedAlphabet { .. })
+        ));
+        assert!(matches!(
+            TransitionTable::parse("alphabet: ab\n- 0 E\n"),
+            Err(ParseError::InvalidAlphabet { .. })
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_labels() -> Result<(), ParseSerializeError> {
+        let input = "- 0 start 1 E
++ 1 accept_ident 1 _ | 1
+- 2 . 0
+";
+        let table = TransitionTable::parse(input)?;
+        assert_eq!(table.rows[0].label.as_deref(), Some("start"));
+        assert_eq!(table.rows[1].label.as_deref(), Some("accept_ident"));
+        assert_eq!(table.rows[2].label, None);
+        assert_eq!(table.width(), 2);
+        assert_eq!(
+            table.serialize(),
+            "- 0 start 1 E\n+ 1 accept_ident 1 1\n- 2 2 0\n"
+        );
+        assert_eq!(table.rows[1].name(), "1 (accept_ident)");
+
+        // Labels show in DOT and survive importing it
+        let dot = table.to_dot();
+        assert!(dot.contains("1 [shape=doublecircle, label=\"accept_ident\"];"));
+        assert_eq!(TransitionTable::parse_dot(&dot)?.table, table);
+
+        // Labels name states in messages
+        let issues = table.validate();
+        assert_eq!(issues[0].message, "State 2 can't be reached");
+        let error = table.serialize_states(&[1, 2], false).unwrap_err();
+        assert_eq!(
+            error.message,
+            "State 2 transitions to state 0 (start), which is not selected"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_comments() -> Result<(), ParseSerializeError> {
+        let input = "# Identifiers\n# by hand\n\n%meta owner me\n\n# Not about state 0\n\n\
+                     - 0 1 E\n  \n# Accepts\n+ 1 1 1\n\n# Trailing\n";
+        let table = TransitionTable::parse(input)?;
+        assert_eq!(table.comment.as_deref(), Some("Identifiers\nby hand"));
+        assert_eq!(table.rows[0].doc, None);
+        assert_eq!(table.rows[1].doc.as_deref(), Some("Accepts"));
+        assert_eq!(
+            table.serialize(),
+            "# Identifiers\n# by hand\n\n%meta owner me\n- 0 1 E\n# Accepts\n+ 1 1 1\n"
+        );
+        assert_eq!(TransitionTable::parse(&table.serialize())?, table);
+
+        // Only a comment before every row describes the table
+        let table = TransitionTable::parse("- 0 E\n# Stray\n\n")?;
+        assert_eq!(table.comment, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn transition_table_parse_options() -> Result<(), ParseSerializeError> {
+        let options = ParseOptions {
+            error_symbol: "X".to_string(),
+            accepting_markers: vec!["A".to_string(), "*".to_string()],
+            rejecting_markers: vec!["N".to_string()],
+            ragged: true,
+            contiguous: true,
+        };
+        let table = TransitionTable::parse_with("N 0 1 X 0\nA 1 1\n* 2 start 2 2\n", &options)?;
+        assert_eq!(table.serialize(), "- 0 1 E 0\n+ 1 1 E E\n+ 2 start 2 2 E\n");
+
+        assert!(matches!(
+            TransitionTable::parse_with("N 0 E\n", &options),
+            Err(ParseError::InvalidTransition { .. })
+        ));
+        assert!(matches!(
+            TransitionTable::parse_with("+ 0 X\n", &options),
+            Err(ParseError::InvalidAcceptingMarker { .. })
+        ));
+        let error = TransitionTable::parse_with("N 0 X\nN 2 X\nN 3 X\n", &options).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 2 has state ID 2, but the next state ID is 1"
+        );
+
+        // Ragged rows take their fallback target in the missing columns
+        let ragged = ParseOptions {
+            ragged: true,
+            ..ParseOptions::default()
+        };
+        let table = TransitionTable::parse_with("- 0 1 1 1\n+ 1 0 | 0\n", &ragged)?;
+        assert_eq!(table.rows[1].transitions, [Some(0), Some(0), Some(0)]);
+
+        // Symbols that already mean something in the format are rejected
+        for symbol in ["_", ".", "|", "#", "7", ""] {
+            let options = ParseOptions {
+                error_symbol: symbol.to_string(),
+                ..ParseOptions::default()
+            };
+            assert!(matches!(
+                TransitionTable::parse_with("- 0 1 _\n", &options),
+                Err(ParseError::ReservedSymbol { .. })
+            ));
+        }
+        let options = ParseOptions {
+            accepting_markers: vec!["#".to_string()],
+            ..ParseOptions::default()
+        };
+        assert!(matches!(
+            TransitionTable::parse_with("- 0 E\n", &options),
+            Err(ParseError::ReservedSymbol { .. })
+        ));
+
+        // The defaults are the usual conventions
+        let input = "- 0 1 E\n+ 1 E E\n";
+        assert_eq!(
+            TransitionTable::parse_with(input, &ParseOptions::default())?,
+            TransitionTable::parse(input)?
+        );
+        assert!(TransitionTable::parse("- 0 1\n+ 1 E E\n").is_err());
+
+        Ok(())
+    }
 }
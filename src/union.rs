@@ -0,0 +1,188 @@
+use crate::{TransitionTable, TransitionTableRow, STARTING_STATE_ID};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// IDs for named patterns that stay the same when a [`PatternUnion`] is rebuilt
+///
+/// A name keeps its ID for as long as the same `PatternIds` is used, even while the pattern is
+/// left out of a rebuild, and IDs are never reused for other names. Keep one `PatternIds` next to
+/// the patterns and pass it to every [`TransitionTable::union_many`] call so that systems keyed on
+/// pattern IDs survive edits to other patterns.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PatternIds {
+    /// The ID of every name seen so far
+    ids: BTreeMap<String, usize>,
+
+    /// The ID the next new name gets
+    next: usize,
+}
+
+impl PatternIds {
+    /// Create an empty set of IDs
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the ID of a name, if it has one
+    pub fn get(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    /// Get the ID of a name, giving it the next unused ID if it has none
+    pub fn assign(&mut self, name: &str) -> usize {
+        if let Some(id) = self.get(name) {
+            return id;
+        }
+        let id = self.next;
+        self.ids.insert(name.to_string(), id);
+        self.next += 1;
+        id
+    }
+
+    /// Forget a name, returning its ID, which won't be given to any other name
+    pub fn retire(&mut self, name: &str) -> Option<usize> {
+        self.ids.remove(name)
+    }
+
+    /// Iterate over the names and their IDs, sorted by name
+    pub fn iter(&self) -> impl Iterator<Item = (&str, usize)> {
+        self.ids.iter().map(|(name, id)| (name.as_str(), *id))
+    }
+}
+
+/// A table accepting the strings any of several patterns accept, made by
+/// [`TransitionTable::union_many`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternUnion {
+    /// The combined table
+    pub table: TransitionTable,
+
+    /// The sorted IDs of the patterns each accepting state matches, by state ID
+    pub matches: BTreeMap<usize, Vec<usize>>,
+}
+
+impl PatternUnion {
+    /// Get the IDs of the patterns matched in a state, which are empty if it isn't accepting
+    pub fn matches(&self, state: usize) -> &[usize] {
+        self.matches.get(&state).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl TransitionTable {
+    /// Combine named patterns into one table that runs them all at once
+    ///
+    /// Each pattern gets its ID from `ids`, so unchanged patterns keep their IDs when the union is
+    /// rebuilt after others change. Patterns with the same name share an ID. The tables are run
+    /// side by side, keeping only the reachable combinations of states numbered in breadth-first
+    /// order, and the result is as wide as the widest pattern.
+    pub fn union_many<'a>(
+        patterns: impl IntoIterator<Item = (&'a str, &'a TransitionTable)>,
+        ids: &mut PatternIds,
+    ) -> PatternUnion {
+        let (pattern_ids, tables): (Vec<usize>, Vec<&TransitionTable>) = patterns
+            .into_iter()
+            .map(|(name, table)| (ids.assign(name), table))
+            .unzip();
+        let width = tables.iter().map(|table| table.width()).max().unwrap_or(0);
+
+        let start: Vec<usize> = tables
+            .iter()
+            .map(|table| {
+                table
+                    .index_of(STARTING_STATE_ID)
+                    .unwrap_or(table.rows.len())
+            })
+            .collect();
+        let mut states = HashMap::from([(start.clone(), STARTING_STATE_ID)]);
+        let mut queue = VecDeque::from([start]);
+        let mut rows = Vec::new();
+        let mut matches = BTreeMap::new();
+
+        while let Some(indices) = queue.pop_front() {
+            let id = states[&indices];
+            let transitions = (0..width)
+                .map(|symbol| {
+                    let target: Vec<usize> = tables
+                        .iter()
+                        .zip(&indices)
+                        .map(|(table, index)| table.delta(*index, symbol))
+                        .collect();
+                    let next_id = states.len();
+                    Some(*states.entry(target.clone()).or_insert_with(|| {
+                        queue.push_back(target);
+                        next_id
+                    }))
+                })
+                .collect();
+
+            let mut matched: Vec<usize> = tables
+                .iter()
+                .zip(&indices)
+                .zip(&pattern_ids)
+                .filter(|((table, index), _)| table.rows.get(**index).is_some_and(|r| r.accepting))
+                .map(|(_, pattern)| *pattern)
+                .collect();
+            matched.sort_unstable();
+            matched.dedup();
+
+            rows.push(TransitionTableRow {
+                accepting: !matched.is_empty(),
+                id,
+                transitions,
+                doc: None,
+                label: None,
+            });
+            if !matched.is_empty() {
+                matches.insert(id, matched);
+            }
+        }
+
+        PatternUnion {
+            table: TransitionTable {
+                rows,
+                alphabet: None,
+                metadata: Default::default(),
+                comment: None,
+            },
+            matches,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Automaton;
+
+    #[test]
+    fn union_many() -> Result<(), crate::ParseError> {
+        // Over the columns a and b: "a", "ab", and "b"
+        let a = TransitionTable::parse("- 0 1 E\n+ 1 E E\n")?;
+        let ab = TransitionTable::parse("- 0 1 E\n- 1 E 2\n+ 2 E E\n")?;
+        let b = TransitionTable::parse("- 0 E 1\n+ 1 E E\n")?;
+
+        let mut ids = PatternIds::new();
+        let union = TransitionTable::union_many([("a", &a), ("ab", &ab), ("b", &b)], &mut ids);
+        let end = |input: &[usize]| union.table.run(input.iter()).unwrap();
+        assert_eq!(union.matches(end(&[0])), [0]);
+        assert_eq!(union.matches(end(&[0, 1])), [1]);
+        assert_eq!(union.matches(end(&[1])), [2]);
+        assert!(!union.table.accepts([1, 1]));
+
+        // Changing "a" to "a" or "aa", dropping "ab", and adding "bb" keeps the other IDs
+        let a_or_aa = TransitionTable::parse("- 0 1 E\n+ 1 2 E\n+ 2 E E\n")?;
+        let bb = TransitionTable::parse("- 0 E 1\n- 1 E 2\n+ 2 E E\n")?;
+        let union =
+            TransitionTable::union_many([("bb", &bb), ("b", &b), ("a", &a_or_aa)], &mut ids);
+        let end = |input: &[usize]| union.table.run(input.iter()).unwrap();
+        assert_eq!(union.matches(end(&[0, 0])), [0]);
+        assert_eq!(union.matches(end(&[1])), [2]);
+        assert_eq!(union.matches(end(&[1, 1])), [3]);
+        assert_eq!(ids.get("ab"), Some(1));
+
+        // Retired IDs aren't reused
+        assert_eq!(ids.retire("ab"), Some(1));
+        assert_eq!(ids.assign("ba"), 4);
+
+        Ok(())
+    }
+}
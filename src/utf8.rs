@@ -0,0 +1,261 @@
+use crate::{Alphabet, TransitionTable, TransitionTableRow};
+use std::collections::HashMap;
+
+/// The number of columns in a byte-level table, one per byte value
+pub const BYTE_WIDTH: usize = 256;
+
+/// The largest code point encoded with each UTF-8 sequence length
+const MAX_CODE_POINTS: [u32; 4] = [0x7F, 0x7FF, 0xFFFF, 0x10FFFF];
+
+/// The sequence of byte ranges remaining in a UTF-8 encoded character range, with the target
+/// state entered after the last byte
+type PendingSequence = (Vec<(u8, u8)>, usize);
+
+impl TransitionTable {
+    /// Compile a table over characters into a table over the bytes of their UTF-8 encoding
+    ///
+    /// `alphabet` gives the characters of each column. The result has one column per byte value.
+    /// The original states keep their IDs, and new states for partially read characters are
+    /// numbered after them. Byte sequences that aren't valid UTF-8, including overlong encodings
+    /// and surrogates, take error transitions.
+    pub fn to_utf8_bytes(&self, alphabet: &Alphabet) -> TransitionTable {
+        let mut next_id = self.rows.last().map_or(0, |row| row.id + 1);
+        let mut intermediate_ids: HashMap<Vec<PendingSequence>, usize> = HashMap::new();
+        let mut pending = Vec::new();
+        let mut rows = Vec::new();
+
+        // Start each original state with the encodings of every character it has a transition for
+        for row in &self.rows {
+            let mut sequences = Vec::new();
+            for (start, end, column) in alphabet.ranges() {
+                let Some(target) = row.transitions.get(*column).copied().flatten() else {
+                    continue;
+                };
+                for sequence in utf8_sequences(*start as u32, *end as u32) {
+                    sequences.push((sequence, target));
+                }
+            }
+
+            rows.push(TransitionTableRow {
+                accepting: row.accepting,
+                id: row.id,
+                transitions: byte_transitions(
+                    &sequences,
+                    &mut intermediate_ids,
+                    &mut pending,
+                    &mut next_id,
+                ),
+                doc: None,
+                label: None,
+            });
+        }
+
+        // Add the states for partially read characters until no new ones are found
+        while let Some((sequences, id)) = pending.pop() {
+            rows.push(TransitionTableRow {
+                accepting: false,
+                id,
+                transitions: byte_transitions(
+                    &sequences,
+                    &mut intermediate_ids,
+                    &mut pending,
+                    &mut next_id,
+                ),
+                doc: None,
+                label: None,
+            });
+        }
+
+        rows.sort_by_key(|row| row.id);
+        TransitionTable {
+            rows,
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        }
+    }
+}
+
+/// Compute the transition on every byte from a state with the given pending sequences
+///
+/// A byte completing a sequence enters its target. Otherwise, the sequences still matching become
+/// an intermediate state, which is added to `pending` the first time it is seen.
+fn byte_transitions(
+    sequences: &[PendingSequence],
+    intermediate_ids: &mut HashMap<Vec<PendingSequence>, usize>,
+    pending: &mut Vec<(Vec<PendingSequence>, usize)>,
+    next_id: &mut usize,
+) -> Vec<Option<usize>> {
+    (0..=u8::MAX)
+        .map(|byte| {
+            let mut remaining = Vec::new();
+            for (ranges, target) in sequences {
+                let (start, end) = ranges[0];
+                if byte < start || byte > end {
+                    continue;
+                }
+
+                // UTF-8 is prefix-free, so a completed sequence can't also be partially matched
+                if ranges.len() == 1 {
+                    return Some(*target);
+                }
+                remaining.push((ranges[1..].to_vec(), *target));
+            }
+
+            if remaining.is_empty() {
+                return None;
+            }
+            remaining.sort();
+            remaining.dedup();
+
+            Some(
+                *intermediate_ids.entry(remaining).or_insert_with_key(|key| {
+                    let id = *next_id;
+                    *next_id += 1;
+                    pending.push((key.clone(), id));
+                    id
+                }),
+            )
+        })
+        .collect()
+}
+
+/// Split a range of code points into sequences of byte ranges matching exactly their UTF-8
+/// encodings
+fn utf8_sequences(start: u32, end: u32) -> Vec<Vec<(u8, u8)>> {
+    let mut sequences = Vec::new();
+    let mut ranges = vec![(start, end)];
+
+    while let Some((start, end)) = ranges.pop() {
+        // Surrogates have no encoding
+        if start <= 0xDFFF && end >= 0xD800 {
+            if start < 0xD800 {
+                ranges.push((start, 0xD7FF));
+            }
+            if end > 0xDFFF {
+                ranges.push((0xE000, end));
+            }
+            continue;
+        }
+
+        // Split ranges covering several encoding lengths
+        if let Some(max) = MAX_CODE_POINTS
+            .iter()
+            .find(|max| start <= **max && end > **max)
+        {
+            ranges.push((start, *max));
+            ranges.push((max + 1, end));
+            continue;
+        }
+
+        // Split ranges until every continuation byte ranges over all of its values
+        let length = MAX_CODE_POINTS.iter().position(|max| end <= *max).unwrap() + 1;
+        let split = (1..length).find_map(|continuation_bytes| {
+            let mask = (1 << (6 * continuation_bytes)) - 1;
+            if start & !mask == end & !mask {
+                None
+            } else if start & mask != 0 {
+                Some(start | mask)
+            } else if end & mask != mask {
+                Some((end & !mask) - 1)
+            } else {
+                None
+            }
+        });
+        if let Some(split) = split {
+            ranges.push((start, split));
+            ranges.push((split + 1, end));
+            continue;
+        }
+
+        let encode = |code_point: u32| {
+            let mut buffer = [0; 4];
+            char::from_u32(code_point).unwrap().encode_utf8(&mut buffer);
+            buffer
+        };
+        let (start_bytes, end_bytes) = (encode(start), encode(end));
+        sequences.push(
+            (0..length)
+                .map(|index| (start_bytes[index], end_bytes[index]))
+                .collect(),
+        );
+    }
+
+    sequences
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CharSet, ParseSerializeError, STARTING_STATE_ID};
+
+    /// Run a byte-level table on a byte string
+    fn accepts(table: &TransitionTable, input: &[u8]) -> bool {
+        let mut state = STARTING_STATE_ID;
+        for byte in input {
+            match table.next_state(state, *byte as usize) {
+                Some(next) => state = next,
+                None => return false,
+            }
+        }
+        table.row(state).is_some_and(|row| row.accepting)
+    }
+
+    #[test]
+    fn utf8_sequences_cover_exactly() {
+        for (start, end) in [
+            (0, 0x10FFFF),
+            (0x41, 0x2603),
+            (0x7FF, 0x800),
+            (0xD000, 0xE100),
+            (0x1F600, 0x1F64F),
+        ] {
+            let sequences = utf8_sequences(start, end);
+            for c in [
+                '\0',
+                'A',
+                '\u{7FF}',
+                '\u{800}',
+                '☃',
+                '\u{E000}',
+                '😀',
+                '\u{10FFFF}',
+            ] {
+                let mut buffer = [0; 4];
+                let bytes = c.encode_utf8(&mut buffer).as_bytes();
+                let matched = sequences.iter().any(|sequence| {
+                    sequence.len() == bytes.len()
+                        && sequence
+                            .iter()
+                            .zip(bytes)
+                            .all(|((low, high), byte)| low <= byte && byte <= high)
+                });
+                assert_eq!(matched, (start..=end).contains(&(c as u32)), "{:?}", c);
+            }
+        }
+    }
+
+    #[test]
+    fn to_utf8_bytes() -> Result<(), ParseSerializeError> {
+        // Any number of Greek letters, then one snowman or ASCII digit
+        let table = TransitionTable::parse("- 0 0 1 1\n+ 1 E E E\n")?;
+        let alphabet = Alphabet::new(vec![
+            CharSet::new([('α', 'ω')]),
+            CharSet::single('☃'),
+            CharSet::new([('0', '9')]),
+        ]);
+
+        let bytes = table.to_utf8_bytes(&alphabet);
+        assert_eq!(bytes.width(), BYTE_WIDTH);
+        assert!(accepts(&bytes, "αβγ☃".as_bytes()));
+        assert!(accepts(&bytes, "ω7".as_bytes()));
+        assert!(!accepts(&bytes, "αβγ".as_bytes()));
+        assert!(!accepts(&bytes, "a7".as_bytes()));
+        assert!(!accepts(&bytes, &"☃".as_bytes()[..2]));
+
+        // An overlong encoding of '7'
+        assert!(!accepts(&bytes, &[0xC0, 0xB7]));
+
+        Ok(())
+    }
+}
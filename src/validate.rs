@@ -0,0 +1,266 @@
+use crate::{TransitionTable, STARTING_STATE_ID};
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// The kind of a [`ValidationIssue`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum IssueKind {
+    /// The rows aren't sorted by state ID, which lookups by ID rely on
+    UnsortedRows,
+
+    /// A row has the same state ID as the row before it
+    DuplicateId,
+
+    /// There is no state 0
+    MissingStart,
+
+    /// Some state IDs between 0 and the largest ID are unused
+    NumberingGap,
+
+    /// A row has a different number of transitions than the first row
+    InconsistentWidth,
+
+    /// A transition goes to a state ID with no row
+    MissingTarget,
+
+    /// No state is accepting, so the table accepts nothing
+    NoAcceptingState,
+
+    /// A state can't be reached from the starting state
+    Unreachable,
+}
+
+/// A problem with a table's structure found by [`TransitionTable::validate`]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ValidationIssue {
+    /// What the problem is
+    pub kind: IssueKind,
+
+    /// The index in `rows` of the row with the problem, if it is about one row
+    pub row: Option<usize>,
+
+    /// The column of the transition with the problem, if it is about one transition
+    pub column: Option<usize>,
+
+    /// A description of the problem
+    pub message: String,
+}
+
+impl Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl TransitionTable {
+    /// Check the invariants of the table that parsing alone doesn't guarantee
+    ///
+    /// Tables built or edited in code can break these, and tables that parse can still have
+    /// missing targets, gaps, and states nothing reaches. Returns every issue found, ordered by
+    /// kind and then by row, which is empty for a well-formed table. Unreachable states are only
+    /// reported when there is a starting state.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        let mut issue = |kind, row: Option<usize>, column: Option<usize>, message: String| {
+            issues.push(ValidationIssue {
+                kind,
+                row,
+                column,
+                message,
+            })
+        };
+
+        for (index, pair) in self.rows.windows(2).enumerate() {
+            if pair[0].id > pair[1].id {
+                issue(
+                    IssueKind::UnsortedRows,
+                    Some(index + 1),
+                    None,
+                    format!(
+                        "State {} comes after state {}",
+                        pair[1].name(),
+                        pair[0].name()
+                    ),
+                );
+            }
+        }
+        for (index, pair) in self.rows.windows(2).enumerate() {
+            if pair[0].id == pair[1].id {
+                issue(
+                    IssueKind::DuplicateId,
+                    Some(index + 1),
+                    None,
+                    format!("State {} has more than one row", pair[1].name()),
+                );
+            }
+        }
+
+        let mut ids: Vec<usize> = self.rows.iter().map(|row| row.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        let start = self.rows.iter().position(|row| row.id == STARTING_STATE_ID);
+        if start.is_none() {
+            issue(
+                IssueKind::MissingStart,
+                None,
+                None,
+                "There is no starting state".to_string(),
+            );
+        }
+        let mut previous = STARTING_STATE_ID;
+        for id in ids.iter().copied().filter(|id| *id != STARTING_STATE_ID) {
+            if id > previous + 1 {
+                let row = self.rows.iter().position(|row| row.id == id);
+                let missing = if id == previous + 2 {
+                    format!("State {} is missing", previous + 1)
+                } else {
+                    format!("States {} to {} are missing", previous + 1, id - 1)
+                };
+                issue(IssueKind::NumberingGap, row, None, missing);
+            }
+            previous = id;
+        }
+
+        let width = self.rows.first().map_or(0, |row| row.transitions.len());
+        for (index, row) in self.rows.iter().enumerate() {
+            if row.transitions.len() != width {
+                issue(
+                    IssueKind::InconsistentWidth,
+                    Some(index),
+                    None,
+                    format!(
+                        "State {} has {} transitions, but the first state has {}",
+                        row.name(),
+                        row.transitions.len(),
+                        width
+                    ),
+                );
+            }
+        }
+
+        for (index, row) in self.rows.iter().enumerate() {
+            for (column, target) in row.transitions.iter().enumerate() {
+                if let Some(target) = target.filter(|target| ids.binary_search(target).is_err()) {
+                    issue(
+                        IssueKind::MissingTarget,
+                        Some(index),
+                        Some(column),
+                        format!(
+                            "State {} goes to state {} on column {}, which doesn't exist",
+                            row.name(),
+                            target,
+                            column
+                        ),
+                    );
+                }
+            }
+        }
+
+        if !self.rows.iter().any(|row| row.accepting) {
+            issue(
+                IssueKind::NoAcceptingState,
+                None,
+                None,
+                "No state is accepting".to_string(),
+            );
+        }
+
+        if let Some(start) = start {
+            // Rows by ID, with every row of a repeated ID
+            let mut rows_of: HashMap<usize, Vec<usize>> = HashMap::new();
+            for (index, row) in self.rows.iter().enumerate() {
+                rows_of.entry(row.id).or_default().push(index);
+            }
+
+            let mut reached = vec![false; self.rows.len()];
+            reached[start] = true;
+            let mut pending = vec![start];
+            while let Some(index) = pending.pop() {
+                for target in self.rows[index].transitions.iter().flatten() {
+                    for next in rows_of.get(target).into_iter().flatten() {
+                        if !reached[*next] {
+                            reached[*next] = true;
+                            pending.push(*next);
+                        }
+                    }
+                }
+            }
+
+            for (index, row) in self.rows.iter().enumerate() {
+                if !reached[index] {
+                    issue(
+                        IssueKind::Unreachable,
+                        Some(index),
+                        None,
+                        format!("State {} can't be reached", row.name()),
+                    );
+                }
+            }
+        }
+
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::TransitionTableRow;
+
+    #[test]
+    fn validate() {
+        let table = TransitionTable::parse("- 0 1 E\n+ 1 1 0\n").unwrap();
+        assert!(table.validate().is_empty());
+
+        let row = |id, accepting, transitions: Vec<Option<usize>>| TransitionTableRow {
+            accepting,
+            id,
+            transitions,
+            doc: None,
+            label: None,
+        };
+        let table = TransitionTable {
+            rows: vec![
+                row(1, false, vec![Some(9), None]),
+                row(4, false, vec![Some(1)]),
+                row(4, false, vec![None, None]),
+                row(2, false, vec![None, Some(1)]),
+            ],
+            alphabet: None,
+            metadata: Default::default(),
+            comment: None,
+        };
+        let issues = table
+            .validate()
+            .into_iter()
+            .map(|issue| (issue.kind, issue.row, issue.column))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            issues,
+            [
+                (IssueKind::UnsortedRows, Some(3), None),
+                (IssueKind::DuplicateId, Some(2), None),
+                (IssueKind::MissingStart, None, None),
+                (IssueKind::NumberingGap, Some(1), None),
+                (IssueKind::InconsistentWidth, Some(1), None),
+                (IssueKind::MissingTarget, Some(0), Some(0)),
+                (IssueKind::NoAcceptingState, None, None),
+            ]
+        );
+        assert_eq!(table.validate()[3].to_string(), "State 3 is missing");
+
+        let table = TransitionTable::parse("- 0 0\n+ 1 0\n+ 2 1\n").unwrap();
+        let unreachable = table
+            .validate()
+            .into_iter()
+            .map(|issue| (issue.kind, issue.row))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unreachable,
+            [
+                (IssueKind::Unreachable, Some(1)),
+                (IssueKind::Unreachable, Some(2))
+            ]
+        );
+    }
+}
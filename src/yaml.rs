@@ -0,0 +1,452 @@
+use crate::{ImportedTable, ParseSerializeError, SymbolMap, TransitionTable, TransitionTableRow};
+
+/// A value of the YAML subset read by [`TransitionTable::parse_yaml`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum YamlValue {
+    /// A plain or quoted scalar
+    Scalar(String),
+
+    /// A block or flow sequence
+    List(Vec<YamlValue>),
+
+    /// A block or flow mapping, in order
+    Map(Vec<(String, YamlValue)>),
+}
+
+impl YamlValue {
+    /// Describe the kind of value, for error messages
+    fn kind(&self) -> &'static str {
+        match self {
+            YamlValue::Scalar(_) => "a scalar",
+            YamlValue::List(_) => "a list",
+            YamlValue::Map(_) => "a mapping",
+        }
+    }
+}
+
+/// A line of YAML without its comment, with its indentation and 1-based line number
+struct YamlLine<'a> {
+    number: usize,
+    indent: usize,
+    text: &'a str,
+}
+
+/// Remove a comment from a line, ignoring `#` inside quotes
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut previous = ' ';
+    for (offset, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, '#') if previous.is_whitespace() => return &line[..offset],
+            _ => {}
+        }
+        previous = c;
+    }
+    line
+}
+
+/// Split the items of a flow collection at top-level commas
+fn split_flow(inner: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut depth, mut quote, mut start) = (0, None, 0);
+    for (offset, c) in inner.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, '[' | '{') => depth += 1,
+            (None, ']' | '}') => depth -= 1,
+            (None, ',') if depth == 0 => {
+                items.push(inner[start..offset].trim());
+                start = offset + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(inner[start..].trim());
+    items.retain(|item| !item.is_empty());
+    items
+}
+
+/// Split `key: value` at the first colon outside quotes that is followed by a space or the end
+fn split_key(text: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    let bytes = text.as_bytes();
+    for (offset, c) in text.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), c) if c == open => quote = None,
+            (None, ':') if bytes.get(offset + 1).is_none_or(|next| *next == b' ') => {
+                return Some((text[..offset].trim(), text[offset + 1..].trim()));
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parse a scalar or flow collection written on one line
+fn parse_inline(text: &str, number: usize) -> Result<YamlValue, ParseSerializeError> {
+    let error = |message: &str| ParseSerializeError {
+        message: format!("YAML line {} {}", number, message),
+    };
+
+    if let Some(inner) = text.strip_prefix('[') {
+        let inner = inner
+            .strip_suffix(']')
+            .ok_or_else(|| error("has an unclosed ["))?;
+        return split_flow(inner)
+            .into_iter()
+            .map(|item| parse_inline(item, number))
+            .collect::<Result<_, _>>()
+            .map(YamlValue::List);
+    }
+    if let Some(inner) = text.strip_prefix('{') {
+        let inner = inner
+            .strip_suffix('}')
+            .ok_or_else(|| error("has an unclosed {"))?;
+        return split_flow(inner)
+            .into_iter()
+            .map(|item| {
+                let (key, value) =
+                    split_key(item).ok_or_else(|| error("has an entry without a key"))?;
+                Ok((unquote(key), parse_inline(value, number)?))
+            })
+            .collect::<Result<_, _>>()
+            .map(YamlValue::Map);
+    }
+
+    Ok(YamlValue::Scalar(unquote(text)))
+}
+
+/// Remove the quotes around a scalar, if it has them
+fn unquote(text: &str) -> String {
+    for quote in ['"', '\''] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|text| text.strip_suffix(quote))
+        {
+            return inner.to_string();
+        }
+    }
+    text.to_string()
+}
+
+/// Parse the block starting at `lines[*position]`, whose lines are indented by `indent`
+fn parse_block(
+    lines: &[YamlLine],
+    position: &mut usize,
+    indent: usize,
+) -> Result<YamlValue, ParseSerializeError> {
+    let error = |number: usize, message: &str| ParseSerializeError {
+        message: format!("YAML line {} {}", number, message),
+    };
+    let is_item = |text: &str| text == "-" || text.starts_with("- ");
+
+    if lines.get(*position).is_some_and(|line| is_item(line.text)) {
+        let mut items = Vec::new();
+        while let Some(line) = lines.get(*position).filter(|line| line.indent == indent) {
+            if !is_item(line.text) {
+                return Err(error(line.number, "mixes list items and keys"));
+            }
+            *position += 1;
+            let item = line.text[1..].trim();
+            items.push(if item.is_empty() {
+                parse_nested(lines, position, indent)?
+            } else {
+                parse_inline(item, line.number)?
+            });
+        }
+        return Ok(YamlValue::List(items));
+    }
+
+    let mut entries: Vec<(String, YamlValue)> = Vec::new();
+    while let Some(line) = lines.get(*position).filter(|line| line.indent == indent) {
+        *position += 1;
+        let (key, value) = split_key(line.text)
+            .ok_or_else(|| error(line.number, "is neither a key nor a list item"))?;
+        let key = unquote(key);
+        if entries.iter().any(|(existing, _)| *existing == key) {
+            return Err(error(line.number, &format!("repeats the key {}", key)));
+        }
+
+        let value = if value.is_empty() {
+            parse_nested(lines, position, indent)?
+        } else {
+            parse_inline(value, line.number)?
+        };
+        entries.push((key, value));
+    }
+    if let Some(line) = lines.get(*position).filter(|line| line.indent > indent) {
+        return Err(error(
+            line.number,
+            "is indented more than the lines before it",
+        ));
+    }
+
+    Ok(YamlValue::Map(entries))
+}
+
+/// Parse the block nested under a line ending in `:` or `-`, which is empty if there isn't one
+fn parse_nested(
+    lines: &[YamlLine],
+    position: &mut usize,
+    indent: usize,
+) -> Result<YamlValue, ParseSerializeError> {
+    match lines.get(*position) {
+        Some(next) if next.indent > indent => parse_block(lines, position, next.indent),
+        _ => Ok(YamlValue::Scalar(String::new())),
+    }
+}
+
+/// Parse a document of the YAML subset into a value
+fn parse_yaml_value(input: &str) -> Result<YamlValue, ParseSerializeError> {
+    let mut lines = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        if line.starts_with('\t') {
+            return Err(ParseSerializeError {
+                message: format!("YAML line {} is indented with a tab", index + 1),
+            });
+        }
+        let text = strip_comment(line).trim_end();
+        let trimmed = text.trim_start();
+        if trimmed.is_empty() || trimmed == "---" {
+            continue;
+        }
+        lines.push(YamlLine {
+            number: index + 1,
+            indent: text.len() - trimmed.len(),
+            text: trimmed,
+        });
+    }
+
+    let mut position = 0;
+    let indent = lines.first().map_or(0, |line| line.indent);
+    let value = parse_block(&lines, &mut position, indent)?;
+    if let Some(line) = lines.get(position) {
+        return Err(ParseSerializeError {
+            message: format!(
+                "YAML line {} is indented less than the document",
+                line.number
+            ),
+        });
+    }
+    Ok(value)
+}
+
+impl TransitionTable {
+    /// Read a table from a declarative state machine spec in a subset of YAML
+    ///
+    /// The spec is a mapping with a `states` mapping from each state's name to its `accepting`
+    /// flag and an `on` mapping from events to target states. An optional `start` names the
+    /// starting state, which is otherwise the first state, and an optional `events` list fixes
+    /// the columns, which are otherwise the events in the order they first appear:
+    ///
+    /// ```yaml
+    /// start: locked
+    /// events: [coin, push]
+    /// states:
+    ///   locked:
+    ///     on: { coin: unlocked, push: locked }
+    ///   unlocked:
+    ///     accepting: true
+    ///     on:
+    ///       coin: unlocked
+    ///       push: locked
+    /// ```
+    ///
+    /// Fails if a transition names a state that doesn't exist or an event missing from `events`.
+    /// Unknown keys are ignored and listed in the warnings. The subset covers block and flow
+    /// mappings and lists, quoted and plain scalars, and comments, but not anchors, tags, or
+    /// multi-line scalars.
+    pub fn parse_yaml(input: &str) -> Result<ImportedTable, ParseSerializeError> {
+        let error = |message: String| ParseSerializeError { message };
+        let YamlValue::Map(spec) = parse_yaml_value(input)? else {
+            return Err(error("YAML spec is not a mapping".to_string()));
+        };
+
+        let mut warnings = Vec::new();
+        let (mut start, mut events, mut states) = (None, None, None);
+        for (key, value) in spec {
+            match (key.as_str(), value) {
+                ("start", YamlValue::Scalar(name)) => start = Some(name),
+                ("events", YamlValue::List(list)) => events = Some(list),
+                ("states", YamlValue::Map(map)) => states = Some(map),
+                ("start" | "events" | "states", value) => {
+                    return Err(error(format!("YAML spec's {} is {}", key, value.kind())));
+                }
+                (key, _) => warnings.push(format!("key {} is ignored", key)),
+            }
+        }
+        let states = states.ok_or_else(|| error("YAML spec has no states".to_string()))?;
+
+        let mut symbols = SymbolMap::new();
+        let fixed_events = events.is_some();
+        for event in events.into_iter().flatten() {
+            match event {
+                YamlValue::Scalar(event) => {
+                    symbols.insert(event);
+                }
+                value => return Err(error(format!("YAML event is {}", value.kind()))),
+            }
+        }
+
+        // Put the starting state first
+        let mut names: Vec<String> = states.iter().map(|(name, _)| name.clone()).collect();
+        if let Some(start) = &start {
+            let index = names
+                .iter()
+                .position(|name| name == start)
+                .ok_or_else(|| error(format!("YAML starting state {} doesn't exist", start)))?;
+            let start = names.remove(index);
+            names.insert(0, start);
+        }
+        let id_of = |name: &str| names.iter().position(|candidate| candidate == name);
+
+        let mut rows = Vec::new();
+        for (name, state) in &states {
+            let mut row = TransitionTableRow {
+                accepting: false,
+                id: id_of(name).unwrap(),
+                transitions: Vec::new(),
+                doc: None,
+                label: None,
+            };
+            let entries = match state {
+                YamlValue::Map(entries) => entries.as_slice(),
+                YamlValue::Scalar(empty) if empty.is_empty() => &[],
+                value => {
+                    return Err(error(format!("YAML state {} is {}", name, value.kind())));
+                }
+            };
+
+            for (key, value) in entries {
+                match (key.as_str(), value) {
+                    ("accepting", YamlValue::Scalar(flag)) => {
+                        row.accepting = match flag.as_str() {
+                            "true" | "yes" => true,
+                            "false" | "no" => false,
+                            _ => {
+                                return Err(error(format!(
+                                    "YAML state {} has an invalid accepting flag {}",
+                                    name, flag
+                                )))
+                            }
+                        }
+                    }
+                    ("on", YamlValue::Map(transitions)) => {
+                        for (event, target) in transitions {
+                            let YamlValue::Scalar(target) = target else {
+                                return Err(error(format!(
+                                    "YAML state {} has a target on {} that is {}",
+                                    name,
+                                    event,
+                                    target.kind()
+                                )));
+                            };
+                            let target = id_of(target).ok_or_else(|| {
+                                error(format!(
+                                    "YAML state {} goes to {} on {}, which doesn't exist",
+                                    name, target, event
+                                ))
+                            })?;
+                            let column = match symbols.column(event) {
+                                Some(column) => column,
+                                None if !fixed_events => symbols.insert(event.clone()),
+                                None => {
+                                    return Err(error(format!(
+                                    "YAML state {} has a transition on {}, which isn't an event",
+                                    name, event
+                                )))
+                                }
+                            };
+                            if row.transitions.len() <= column {
+                                row.transitions.resize(column + 1, None);
+                            }
+                            row.transitions[column] = Some(target);
+                        }
+                    }
+                    ("accepting" | "on", value) => {
+                        return Err(error(format!(
+                            "YAML state {}'s {} is {}",
+                            name,
+                            key,
+                            value.kind()
+                        )));
+                    }
+                    (key, _) => warnings.push(format!("key {} of state {} is ignored", key, name)),
+                }
+            }
+            rows.push(row);
+        }
+
+        for row in &mut rows {
+            row.transitions.resize(symbols.len(), None);
+        }
+        rows.sort_by_key(|row| row.id);
+
+        Ok(ImportedTable {
+            table: TransitionTable {
+                rows,
+                alphabet: None,
+                metadata: Default::default(),
+                comment: None,
+            },
+            symbols,
+            states: names,
+            warnings,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_yaml() -> Result<(), ParseSerializeError> {
+        let spec = "
+# A turnstile
+start: locked
+description: 'Locks again once pushed'
+states:
+  unlocked:
+    accepting: true
+    on:
+      coin: unlocked   # Extra coins are kept
+      push: locked
+  locked:
+    on: { coin: unlocked, push: locked }
+    color: red
+";
+        let imported = TransitionTable::parse_yaml(spec)?;
+        assert_eq!(imported.table.serialize(), "- 0 1 0\n+ 1 1 0\n");
+        assert_eq!(imported.states, ["locked", "unlocked"]);
+        assert_eq!(
+            imported.symbols.iter().collect::<Vec<_>>(),
+            ["coin", "push"]
+        );
+        assert_eq!(
+            imported.warnings,
+            [
+                "key description is ignored",
+                "key color of state locked is ignored"
+            ]
+        );
+
+        // Listed events fix the columns
+        let listed = TransitionTable::parse_yaml(
+            "events:\n  - push\n  - coin\nstates:\n  a:\n    on: {coin: b}\n  b: {accepting: yes}\n",
+        )?;
+        assert_eq!(listed.table.serialize(), "- 0 E 1\n+ 1 E E\n");
+
+        assert!(TransitionTable::parse_yaml("states:\n  a:\n    on: {x: c}\n").is_err());
+        assert!(
+            TransitionTable::parse_yaml("events: [x]\nstates:\n  a:\n    on: {y: a}\n").is_err()
+        );
+        assert!(TransitionTable::parse_yaml("states:\n  a:\n  a:\n").is_err());
+
+        Ok(())
+    }
+}